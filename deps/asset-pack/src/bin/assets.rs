@@ -0,0 +1,80 @@
+use std::{path::PathBuf, process::ExitCode};
+
+use anyhow::{Result, bail};
+use asset_pack::{MANIFEST_FILE, Manifest, PACK_FILE, Pack, verify};
+
+const USAGE: &str = "Usage: assets <manifest|pack|verify> [project root]
+
+  manifest  Write Assets/manifest.json with hashes of all assets
+  pack      Write Assets.pack with all assets and update the manifest
+  verify    Fail if the manifest is outdated or code references an asset missing from it";
+
+fn main() -> ExitCode {
+    match run() {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("{err}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run() -> Result<()> {
+    let mut args = std::env::args().skip(1);
+
+    let Some(command) = args.next() else {
+        bail!(USAGE);
+    };
+
+    let root = args.next().map_or_else(|| PathBuf::from("."), PathBuf::from);
+    let assets = root.join("Assets");
+    let manifest_path = assets.join(MANIFEST_FILE);
+
+    match command.as_str() {
+        "manifest" => {
+            let manifest = Manifest::build(&assets)?;
+            manifest.save(&manifest_path)?;
+            println!(
+                "{} assets written to {}",
+                manifest.entries.len(),
+                manifest_path.display()
+            );
+        }
+        "pack" => {
+            let pack_path = root.join(PACK_FILE);
+            let manifest = Pack::create(&assets, &pack_path)?;
+            manifest.save(&manifest_path)?;
+            println!(
+                "{} assets packed to {}",
+                manifest.entries.len(),
+                pack_path.display()
+            );
+        }
+        "verify" => {
+            let manifest = Manifest::load(&manifest_path)?;
+
+            let outdated = manifest.diff(&Manifest::build(&assets)?);
+            for key in &outdated {
+                eprintln!("Manifest is outdated: {key}");
+            }
+
+            let missing = verify(&manifest, &[&root])?;
+            for reference in &missing {
+                eprintln!("{reference} is not in the manifest");
+            }
+
+            if !outdated.is_empty() || !missing.is_empty() {
+                bail!(
+                    "Asset verification failed: {} outdated, {} missing",
+                    outdated.len(),
+                    missing.len()
+                );
+            }
+
+            println!("{} assets verified", manifest.entries.len());
+        }
+        _ => bail!(USAGE),
+    }
+
+    Ok(())
+}
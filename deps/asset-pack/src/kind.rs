@@ -0,0 +1,39 @@
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum AssetKind {
+    Image,
+    Sound,
+    Font,
+    Model,
+}
+
+impl AssetKind {
+    pub const ALL: [Self; 4] = [Self::Image, Self::Sound, Self::Font, Self::Model];
+
+    /// Directory inside `Assets` folder where assets of this kind are stored.
+    pub const fn dir(self) -> &'static str {
+        match self {
+            Self::Image => "Images",
+            Self::Sound => "Sounds",
+            Self::Font => "Fonts",
+            Self::Model => "Models",
+        }
+    }
+
+    pub const fn extensions(self) -> &'static [&'static str] {
+        match self {
+            Self::Image => &["png", "jpg", "jpeg", "gif", "bmp"],
+            Self::Sound => &["wav", "mp3", "ogg", "flac"],
+            Self::Font => &["ttf", "otf"],
+            Self::Model => &["fbx", "blend", "obj", "gltf", "glb"],
+        }
+    }
+
+    pub fn from_extension(path: impl AsRef<Path>) -> Option<Self> {
+        let ext = path.as_ref().extension()?.to_str()?.to_lowercase();
+        Self::ALL.into_iter().find(|kind| kind.extensions().contains(&ext.as_str()))
+    }
+}
@@ -0,0 +1,11 @@
+mod kind;
+mod manifest;
+mod pack;
+mod source;
+mod verify;
+
+pub use kind::AssetKind;
+pub use manifest::{MANIFEST_FILE, Manifest, ManifestEntry, hash};
+pub use pack::{PACK_FILE, Pack};
+pub use source::{is_mounted, mount, read, unmount};
+pub use verify::{Reference, find_references, verify};
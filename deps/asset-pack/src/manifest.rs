@@ -0,0 +1,196 @@
+use std::{
+    collections::BTreeMap,
+    fmt::Write,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Result, anyhow};
+use blake2::{Blake2s256, Digest};
+use serde::{Deserialize, Serialize};
+use walkdir::WalkDir;
+
+use crate::AssetKind;
+
+pub const MANIFEST_FILE: &str = "manifest.json";
+
+const MANIFEST_VERSION: u32 = 1;
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ManifestEntry {
+    pub kind: AssetKind,
+    pub size: u64,
+    /// Hex encoded BLAKE2s-256 of file contents.
+    pub hash: String,
+}
+
+/// List of all assets with content hashes. Keys are paths relative to
+/// `Assets` folder with `/` separators. E.g. `Images/sky.png`.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Manifest {
+    pub version: u32,
+    pub entries: BTreeMap<String, ManifestEntry>,
+}
+
+impl Manifest {
+    /// Walks `Images`, `Sounds`, `Fonts` and `Models` folders and hashes
+    /// every file with a known extension.
+    pub fn build(assets: impl AsRef<Path>) -> Result<Self> {
+        let assets = assets.as_ref();
+
+        let mut entries = BTreeMap::new();
+
+        for (key, path, kind) in Self::files(assets)? {
+            let data = fs::read(&path)?;
+            entries.insert(key, ManifestEntry {
+                kind,
+                size: data.len() as u64,
+                hash: hash(&data),
+            });
+        }
+
+        Ok(Self {
+            version: MANIFEST_VERSION,
+            entries,
+        })
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let data = fs::read(path).map_err(|err| anyhow!("Failed to read {}: {err}", path.display()))?;
+        let manifest: Self = serde_json::from_slice(&data)?;
+
+        if manifest.version != MANIFEST_VERSION {
+            return Err(anyhow!(
+                "Unsupported manifest version {}. Expected {MANIFEST_VERSION}",
+                manifest.version
+            ));
+        }
+
+        Ok(manifest)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        let mut json = serde_json::to_string_pretty(self)?;
+        json.push('\n');
+        fs::write(path, json)?;
+        Ok(())
+    }
+
+    pub fn get(&self, kind: AssetKind, name: &str) -> Option<&ManifestEntry> {
+        self.entries.get(&format!("{}/{name}", kind.dir()))
+    }
+
+    /// Checks if any asset has this name. Name is a path relative to its kind
+    /// folder as it is passed to `Image::get` or `Sound::get`.
+    pub fn contains_name(&self, name: &str) -> bool {
+        AssetKind::ALL.into_iter().any(|kind| self.get(kind, name).is_some())
+    }
+
+    /// Keys of entries which differ between manifests. Missing on either side
+    /// count as different.
+    pub fn diff(&self, other: &Self) -> Vec<String> {
+        let mut keys: Vec<_> = self
+            .entries
+            .iter()
+            .filter(|(key, entry)| other.entries.get(*key) != Some(entry))
+            .map(|(key, _)| key.clone())
+            .collect();
+
+        keys.extend(other.entries.keys().filter(|key| !self.entries.contains_key(*key)).cloned());
+        keys.sort();
+        keys
+    }
+
+    /// Key, full path and kind of every asset file.
+    pub(crate) fn files(assets: &Path) -> Result<Vec<(String, PathBuf, AssetKind)>> {
+        let mut files = vec![];
+
+        for kind in AssetKind::ALL {
+            let dir = assets.join(kind.dir());
+
+            if !dir.exists() {
+                continue;
+            }
+
+            for entry in WalkDir::new(&dir).sort_by_file_name() {
+                let entry = entry?;
+
+                if !entry.file_type().is_file() || !has_extension(entry.path(), kind) {
+                    continue;
+                }
+
+                let key = entry.path().strip_prefix(assets).expect("Entry outside assets directory");
+                files.push((
+                    key.to_string_lossy().replace('\\', "/"),
+                    entry.path().to_path_buf(),
+                    kind,
+                ));
+            }
+        }
+
+        Ok(files)
+    }
+}
+
+fn has_extension(path: &Path, kind: AssetKind) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| kind.extensions().contains(&ext.to_lowercase().as_str()))
+}
+
+pub fn hash(data: &[u8]) -> String {
+    Blake2s256::digest(data)
+        .iter()
+        .fold(String::with_capacity(64), |mut hex, byte| {
+            _ = write!(hex, "{byte:02x}");
+            hex
+        })
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs;
+
+    use anyhow::Result;
+
+    use crate::{AssetKind, Manifest, hash};
+
+    #[test]
+    fn build_manifest() -> Result<()> {
+        let dir = std::env::temp_dir().join("asset_pack_build_manifest");
+        _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("Images/ui"))?;
+        fs::create_dir_all(dir.join("Sounds"))?;
+
+        fs::write(dir.join("Images/sky.png"), b"sky")?;
+        fs::write(dir.join("Images/ui/plus.png"), b"plus")?;
+        fs::write(dir.join("Images/notes.txt"), b"not an asset")?;
+        fs::write(dir.join("Sounds/pek.wav"), b"pek")?;
+
+        let manifest = Manifest::build(&dir)?;
+
+        assert_eq!(manifest.entries.keys().collect::<Vec<_>>(), [
+            "Images/sky.png",
+            "Images/ui/plus.png",
+            "Sounds/pek.wav"
+        ]);
+        assert_eq!(
+            manifest.get(AssetKind::Image, "sky.png").unwrap().hash,
+            hash(b"sky")
+        );
+        assert!(manifest.contains_name("ui/plus.png"));
+        assert!(!manifest.contains_name("notes.txt"));
+
+        manifest.save(dir.join("manifest.json"))?;
+        let loaded = Manifest::load(dir.join("manifest.json"))?;
+        assert_eq!(loaded, manifest);
+
+        fs::write(dir.join("Sounds/pek.wav"), b"changed")?;
+        assert_eq!(manifest.diff(&Manifest::build(&dir)?), ["Sounds/pek.wav"]);
+
+        fs::remove_dir_all(&dir)?;
+
+        Ok(())
+    }
+}
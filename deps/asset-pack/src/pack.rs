@@ -0,0 +1,208 @@
+use std::{
+    collections::BTreeMap,
+    fs::{self, File},
+    io::{BufWriter, Read, Seek, SeekFrom, Write},
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+use anyhow::{Result, anyhow, bail};
+use serde::{Deserialize, Serialize};
+
+use crate::{AssetKind, Manifest, ManifestEntry, hash};
+
+pub const PACK_FILE: &str = "Assets.pack";
+
+const MAGIC: &[u8; 4] = b"TPAK";
+const PACK_VERSION: u8 = 1;
+/// Magic + version + index length.
+const HEADER_LEN: usize = 4 + 1 + 8;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct PackEntry {
+    kind:   AssetKind,
+    /// Relative to the start of blobs section.
+    offset: u64,
+    len:    u64,
+    hash:   String,
+}
+
+/// All assets in a single file.
+///
+/// Layout: `b"TPAK" | version: u8 | index length: u64 LE | index | blobs`.
+/// Index is a postcard encoded map from asset key to its blob location.
+/// Keys are the same as in [`Manifest`].
+#[derive(Debug)]
+pub struct Pack {
+    path:       PathBuf,
+    file:       Mutex<File>,
+    blobs_from: u64,
+    index:      BTreeMap<String, PackEntry>,
+}
+
+impl Pack {
+    /// Packs every asset from `assets` folder into `output`. Returns manifest
+    /// of packed assets.
+    pub fn create(assets: impl AsRef<Path>, output: impl AsRef<Path>) -> Result<Manifest> {
+        let files = Manifest::files(assets.as_ref())?;
+
+        let mut index = BTreeMap::new();
+        let mut blobs = vec![];
+
+        for (key, path, kind) in files {
+            let data = fs::read(&path)?;
+            index.insert(key, PackEntry {
+                kind,
+                offset: blobs.len() as u64,
+                len: data.len() as u64,
+                hash: hash(&data),
+            });
+            blobs.extend_from_slice(&data);
+        }
+
+        let encoded_index = postcard::to_stdvec(&index)?;
+
+        let mut out = BufWriter::new(File::create(output)?);
+        out.write_all(MAGIC)?;
+        out.write_all(&[PACK_VERSION])?;
+        out.write_all(&(encoded_index.len() as u64).to_le_bytes())?;
+        out.write_all(&encoded_index)?;
+        out.write_all(&blobs)?;
+        out.flush()?;
+
+        Ok(manifest(&index))
+    }
+
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let mut file = File::open(path).map_err(|err| anyhow!("Failed to open {}: {err}", path.display()))?;
+
+        let mut header = [0; HEADER_LEN];
+        file.read_exact(&mut header)?;
+
+        if &header[..4] != MAGIC {
+            bail!("{} is not an asset pack", path.display());
+        }
+
+        if header[4] != PACK_VERSION {
+            bail!(
+                "Unsupported asset pack version {} in {}. Expected {PACK_VERSION}",
+                header[4],
+                path.display()
+            );
+        }
+
+        let index_len = u64::from_le_bytes(header[5..].try_into().unwrap());
+        let mut encoded_index = vec![0; usize::try_from(index_len)?];
+        file.read_exact(&mut encoded_index)?;
+
+        Ok(Self {
+            path:       path.to_path_buf(),
+            file:       file.into(),
+            blobs_from: HEADER_LEN as u64 + index_len,
+            index:      postcard::from_bytes(&encoded_index)?,
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn contains(&self, key: &str) -> bool {
+        self.index.contains_key(key)
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = &str> {
+        self.index.keys().map(String::as_str)
+    }
+
+    /// Reads asset and checks its hash.
+    pub fn read(&self, key: &str) -> Result<Vec<u8>> {
+        let entry = self
+            .index
+            .get(key)
+            .ok_or_else(|| anyhow!("No asset '{key}' in {}", self.path.display()))?;
+
+        let mut data = vec![0; usize::try_from(entry.len)?];
+
+        {
+            let mut file = self.file.lock().unwrap();
+            file.seek(SeekFrom::Start(self.blobs_from + entry.offset))?;
+            file.read_exact(&mut data)?;
+        }
+
+        if hash(&data) != entry.hash {
+            bail!("Asset '{key}' in {} is corrupted", self.path.display());
+        }
+
+        Ok(data)
+    }
+
+    pub fn manifest(&self) -> Manifest {
+        manifest(&self.index)
+    }
+}
+
+fn manifest(index: &BTreeMap<String, PackEntry>) -> Manifest {
+    Manifest {
+        version: 1,
+        entries: index
+            .iter()
+            .map(|(key, entry)| {
+                (key.clone(), ManifestEntry {
+                    kind: entry.kind,
+                    size: entry.len,
+                    hash: entry.hash.clone(),
+                })
+            })
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs;
+
+    use anyhow::Result;
+
+    use crate::{Manifest, Pack};
+
+    #[test]
+    fn pack() -> Result<()> {
+        let dir = std::env::temp_dir().join("asset_pack_pack");
+        _ = fs::remove_dir_all(&dir);
+        let assets = dir.join("Assets");
+        fs::create_dir_all(assets.join("Images"))?;
+        fs::create_dir_all(assets.join("Fonts"))?;
+
+        fs::write(assets.join("Images/sky.png"), b"sky image")?;
+        fs::write(assets.join("Images/cat.png"), b"cat")?;
+        fs::write(assets.join("Fonts/Mono.ttf"), b"")?;
+
+        let pack_path = dir.join("Assets.pack");
+        let manifest = Pack::create(&assets, &pack_path)?;
+        assert_eq!(manifest, Manifest::build(&assets)?);
+
+        let pack = Pack::open(&pack_path)?;
+        assert_eq!(pack.manifest(), manifest);
+        assert_eq!(pack.read("Images/sky.png")?, b"sky image");
+        assert_eq!(pack.read("Images/cat.png")?, b"cat");
+        assert_eq!(pack.read("Fonts/Mono.ttf")?, b"");
+        assert!(pack.read("Images/dog.png").is_err());
+
+        let mut data = fs::read(&pack_path)?;
+        let len = data.len();
+        data[len - 4] ^= 0xFF;
+        fs::write(&pack_path, data)?;
+
+        let pack = Pack::open(&pack_path)?;
+        assert!(pack.read("Images/sky.png").is_err());
+
+        fs::write(&pack_path, b"garbage data")?;
+        assert!(Pack::open(&pack_path).is_err());
+
+        fs::remove_dir_all(&dir)?;
+
+        Ok(())
+    }
+}
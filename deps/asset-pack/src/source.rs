@@ -0,0 +1,84 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    sync::{Arc, RwLock},
+};
+
+use crate::Pack;
+
+struct Mounted {
+    root: PathBuf,
+    pack: Arc<Pack>,
+}
+
+static MOUNTED: RwLock<Option<Mounted>> = RwLock::new(None);
+
+/// Serves files under `root` from the pack instead of the file system.
+/// `root` is the `Assets` folder the pack was created from.
+pub fn mount(root: impl Into<PathBuf>, pack: Pack) {
+    *MOUNTED.write().unwrap() = Some(Mounted {
+        root: root.into(),
+        pack: pack.into(),
+    });
+}
+
+pub fn unmount() {
+    *MOUNTED.write().unwrap() = None;
+}
+
+pub fn is_mounted() -> bool {
+    MOUNTED.read().unwrap().is_some()
+}
+
+/// Reads asset file from mounted pack if there is one and it has this file.
+/// Falls back to the file system otherwise.
+pub fn read(path: impl AsRef<Path>) -> io::Result<Vec<u8>> {
+    let path = path.as_ref();
+
+    let pack = MOUNTED.read().unwrap().as_ref().and_then(|mounted| {
+        let key = path.strip_prefix(&mounted.root).ok()?.to_string_lossy().replace('\\', "/");
+        mounted.pack.contains(&key).then(|| (mounted.pack.clone(), key))
+    });
+
+    let Some((pack, key)) = pack else {
+        return fs::read(path);
+    };
+
+    pack.read(&key).map_err(io::Error::other)
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs;
+
+    use anyhow::Result;
+
+    use crate::{Pack, is_mounted, mount, read, unmount};
+
+    #[test]
+    fn mounted_read() -> Result<()> {
+        let dir = std::env::temp_dir().join("asset_pack_mounted_read");
+        _ = fs::remove_dir_all(&dir);
+        let assets = dir.join("Assets");
+        fs::create_dir_all(assets.join("Sounds"))?;
+        fs::write(assets.join("Sounds/pek.wav"), b"packed")?;
+
+        let pack_path = dir.join("Assets.pack");
+        Pack::create(&assets, &pack_path)?;
+
+        fs::write(assets.join("Sounds/pek.wav"), b"loose")?;
+        fs::write(assets.join("Sounds/new.wav"), b"not packed")?;
+
+        mount(&assets, Pack::open(&pack_path)?);
+        assert!(is_mounted());
+        assert_eq!(read(assets.join("Sounds/pek.wav"))?, b"packed");
+        assert_eq!(read(assets.join("Sounds/new.wav"))?, b"not packed");
+
+        unmount();
+        assert_eq!(read(assets.join("Sounds/pek.wav"))?, b"loose");
+
+        fs::remove_dir_all(&dir)?;
+
+        Ok(())
+    }
+}
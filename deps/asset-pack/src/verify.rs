@@ -0,0 +1,189 @@
+use std::{
+    fmt::{Display, Formatter},
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::Result;
+use walkdir::{DirEntry, WalkDir};
+
+use crate::{AssetKind, Manifest};
+
+/// String literal in source code which looks like an asset name.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Reference {
+    pub file: PathBuf,
+    pub line: usize,
+    pub name: String,
+}
+
+impl Display for Reference {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}: \"{}\"", self.file.display(), self.line, self.name)
+    }
+}
+
+/// Finds string literals with asset extensions in all `.rs` files under
+/// `dir`. Files passed to `include_bytes!` are embedded into the binary so
+/// their names are skipped. Test modules are skipped too.
+pub fn find_references(dir: impl AsRef<Path>) -> Result<Vec<Reference>> {
+    let mut references = vec![];
+
+    for entry in WalkDir::new(dir)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_entry(|e| !is_ignored(e))
+    {
+        let entry = entry?;
+
+        if !entry.file_type().is_file() || entry.path().extension().is_none_or(|ext| ext != "rs") {
+            continue;
+        }
+
+        let source = fs::read_to_string(entry.path())?;
+
+        let mut embedded = vec![];
+        let mut found = vec![];
+
+        for (i, line) in source.lines().enumerate() {
+            let code = line.trim_start();
+
+            // Tests are at the bottom of the file and use fake assets.
+            if code.starts_with("#[cfg(test)]") {
+                break;
+            }
+
+            if code.starts_with("//") {
+                continue;
+            }
+
+            if code.contains("include_bytes!") || code.contains("include_str!") {
+                embedded.extend(string_literals(line).into_iter().filter_map(file_name));
+                continue;
+            }
+
+            for name in string_literals(line) {
+                if is_asset_name(name) {
+                    found.push(Reference {
+                        file: entry.path().to_path_buf(),
+                        line: i + 1,
+                        name: name.to_string(),
+                    });
+                }
+            }
+        }
+
+        // Names of embedded files are used as keys next to `include_bytes!`.
+        references.extend(found.into_iter().filter(|r| !embedded.contains(&r.name.as_str())));
+    }
+
+    Ok(references)
+}
+
+/// References to assets which are not in the manifest.
+pub fn verify(manifest: &Manifest, dirs: &[impl AsRef<Path>]) -> Result<Vec<Reference>> {
+    let mut missing = vec![];
+
+    for dir in dirs {
+        missing.extend(
+            find_references(dir)?
+                .into_iter()
+                .filter(|reference| !manifest.contains_name(&reference.name)),
+        );
+    }
+
+    Ok(missing)
+}
+
+fn is_ignored(entry: &DirEntry) -> bool {
+    entry.depth() > 0 && entry.file_type().is_dir() && {
+        let name = entry.file_name().to_string_lossy();
+        name == "target" || name.starts_with('.')
+    }
+}
+
+fn is_asset_name(literal: &str) -> bool {
+    !literal.is_empty()
+        && !literal.contains(|c: char| c.is_whitespace() || matches!(c, '{' | '}' | '\\'))
+        && AssetKind::from_extension(literal).is_some()
+}
+
+fn file_name(path: &str) -> Option<&str> {
+    Path::new(path).file_name()?.to_str()
+}
+
+fn string_literals(line: &str) -> Vec<&str> {
+    let mut literals = vec![];
+    let mut start = None;
+    let mut escaped = false;
+
+    for (i, c) in line.char_indices() {
+        match (start, c) {
+            (Some(_), _) if escaped => escaped = false,
+            (Some(_), '\\') => escaped = true,
+            (Some(from), '"') => {
+                literals.push(&line[from..i]);
+                start = None;
+            }
+            (None, '"') => start = Some(i + 1),
+            _ => {}
+        }
+    }
+
+    literals
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs;
+
+    use anyhow::Result;
+
+    use crate::{Manifest, find_references, verify};
+
+    #[test]
+    fn verify_references() -> Result<()> {
+        let dir = std::env::temp_dir().join("asset_pack_verify_references");
+        _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("Assets/Images"))?;
+        fs::create_dir_all(dir.join("src"))?;
+        fs::create_dir_all(dir.join("target"))?;
+
+        fs::write(dir.join("Assets/Images/sky.png"), b"sky")?;
+
+        fs::write(
+            dir.join("src/level.rs"),
+            r#"
+fn setup() {
+    let sky = Image::get("sky.png");
+    let cat = Image::get("cat.png"); let log = "Loaded {}.png";
+    // Image::get("commented.png");
+    let sound = Sound::get("pek.wav");
+    let embedded = include_bytes!("fonts/embedded.ttf");
+    let font = Font::new("embedded.ttf", embedded);
+    let text = "not an asset";
+}
+"#,
+        )?;
+        fs::write(dir.join("target/generated.rs"), r#"Image::get("generated.png");"#)?;
+        fs::write(
+            dir.join("src/tests.rs"),
+            "#[cfg(test)]\nmod test {\n    const FAKE: &str = \"fake.png\";\n}\n",
+        )?;
+
+        let names: Vec<_> = find_references(dir.join("src"))?.into_iter().map(|r| r.name).collect();
+        assert_eq!(names, ["sky.png", "cat.png", "pek.wav"]);
+
+        let manifest = Manifest::build(dir.join("Assets"))?;
+        let missing = verify(&manifest, &[&dir])?;
+
+        assert_eq!(
+            missing.iter().map(|r| (r.name.as_str(), r.line)).collect::<Vec<_>>(),
+            [("cat.png", 4), ("pek.wav", 6)]
+        );
+
+        fs::remove_dir_all(&dir)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,36 @@
+use std::time::Duration;
+
+use rodio::{Source, source::SeekError};
+
+use crate::Bus;
+
+pub type BoxedSource = Box<dyn Source<Item = f32> + Send>;
+
+pub struct PlayRequest {
+    /// Name of the sound file or music track.
+    pub name:   String,
+    pub bus:    Bus,
+    /// Initial volume with bus, master and spatial attenuation applied.
+    pub volume: f32,
+    pub source: BoxedSource,
+}
+
+/// Where the mixer sends sounds to be played. [`crate::RodioBackend`] plays
+/// them on the audio device. [`crate::RecordingBackend`] records them for
+/// tests.
+pub trait AudioBackend: Send + Sync {
+    /// Returns `None` if the sound can't be played.
+    fn play(&self, request: PlayRequest) -> Option<Box<dyn Voice>>;
+}
+
+/// Single playing sound.
+pub trait Voice: Send + Sync {
+    fn set_volume(&self, volume: f32);
+    fn stop(&self);
+    fn pause(&self);
+    fn resume(&self);
+    fn is_paused(&self) -> bool;
+    fn is_finished(&self) -> bool;
+    fn seek(&self, position: Duration) -> Result<(), SeekError>;
+    fn elapsed(&self) -> Duration;
+}
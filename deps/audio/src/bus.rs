@@ -0,0 +1,81 @@
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Bus {
+    Music,
+    Sfx,
+    UI,
+}
+
+impl Bus {
+    pub const ALL: [Self; 3] = [Self::Music, Self::Sfx, Self::UI];
+
+    pub(crate) const fn index(self) -> usize {
+        self as usize
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+struct Level {
+    volume: f32,
+    muted:  bool,
+}
+
+impl Level {
+    const DEFAULT: Self = Self {
+        volume: 1.0,
+        muted:  false,
+    };
+
+    fn gain(self) -> f32 {
+        if self.muted { 0.0 } else { self.volume }
+    }
+}
+
+/// Master and per bus volume.
+#[derive(Copy, Clone, Debug)]
+pub(crate) struct Levels {
+    master: Level,
+    buses:  [Level; 3],
+}
+
+impl Levels {
+    pub(crate) const DEFAULT: Self = Self {
+        master: Level::DEFAULT,
+        buses:  [Level::DEFAULT; 3],
+    };
+
+    pub(crate) fn gain(&self, bus: Bus) -> f32 {
+        self.master.gain() * self.buses[bus.index()].gain()
+    }
+
+    pub(crate) fn master_volume(&self) -> f32 {
+        self.master.volume
+    }
+
+    pub(crate) fn set_master_volume(&mut self, volume: f32) {
+        self.master.volume = volume.max(0.0);
+    }
+
+    pub(crate) fn is_master_muted(&self) -> bool {
+        self.master.muted
+    }
+
+    pub(crate) fn set_master_muted(&mut self, muted: bool) {
+        self.master.muted = muted;
+    }
+
+    pub(crate) fn volume(&self, bus: Bus) -> f32 {
+        self.buses[bus.index()].volume
+    }
+
+    pub(crate) fn set_volume(&mut self, bus: Bus, volume: f32) {
+        self.buses[bus.index()].volume = volume.max(0.0);
+    }
+
+    pub(crate) fn is_muted(&self, bus: Bus) -> bool {
+        self.buses[bus.index()].muted
+    }
+
+    pub(crate) fn set_muted(&mut self, bus: Bus, muted: bool) {
+        self.buses[bus.index()].muted = muted;
+    }
+}
@@ -0,0 +1,147 @@
+use std::{io::Cursor, sync::Arc, time::Duration};
+
+use gm::{LossyConvert, ToF32};
+use rodio::{Decoder, Source, decoder::DecoderError, source::SeekError};
+
+/// Sound decoded to PCM samples. Doesn't open audio output so it can be
+/// created on any thread and turned into [`crate::Sound`] later. Cloning
+/// shares the samples.
+#[derive(Clone, Debug)]
+pub struct DecodedSound {
+    samples:     Arc<[f32]>,
+    channels:    u16,
+    sample_rate: u32,
+}
+
+impl DecodedSound {
+    pub fn decode(data: &[u8]) -> Result<Self, DecoderError> {
+        let decoder = Decoder::new(Cursor::new(data.to_vec()))?;
+
+        let channels = decoder.channels().max(1);
+        let sample_rate = decoder.sample_rate().max(1);
+
+        Ok(Self {
+            samples: decoder.convert_samples().collect(),
+            channels,
+            sample_rate,
+        })
+    }
+
+    pub fn channels(&self) -> u16 {
+        self.channels
+    }
+
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    pub fn duration(&self) -> Duration {
+        Duration::from_secs_f32(self.samples.len().to_f32() / self.frame_rate())
+    }
+
+    /// New playing instance. Instances read the same samples.
+    pub(crate) fn source(&self, looped: bool) -> Samples {
+        Samples {
+            sound: self.clone(),
+            position: 0,
+            looped,
+        }
+    }
+
+    /// Samples per second of all channels.
+    fn frame_rate(&self) -> f32 {
+        self.sample_rate.to_f32() * f32::from(self.channels)
+    }
+}
+
+/// Plays [`DecodedSound`] without copying its samples.
+pub(crate) struct Samples {
+    sound:    DecodedSound,
+    position: usize,
+    looped:   bool,
+}
+
+impl Iterator for Samples {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        if self.looped && self.position >= self.sound.samples.len() {
+            self.position = 0;
+        }
+
+        let sample = self.sound.samples.get(self.position)?;
+        self.position += 1;
+        Some(*sample)
+    }
+}
+
+impl Source for Samples {
+    fn current_frame_len(&self) -> Option<usize> {
+        None
+    }
+
+    fn channels(&self) -> u16 {
+        self.sound.channels
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.sound.sample_rate
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        (!self.looped).then(|| self.sound.duration())
+    }
+
+    fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
+        let channels = usize::from(self.sound.channels);
+        let len = self.sound.samples.len();
+
+        let mut position: usize = (pos.as_secs_f32() * self.sound.frame_rate()).lossy_convert();
+
+        if self.looped && len > 0 {
+            position %= len;
+        }
+
+        // Keep channels in place
+        self.position = (position.min(len) / channels) * channels;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{sync::Arc, time::Duration};
+
+    use rodio::Source;
+
+    use crate::DecodedSound;
+
+    static DATA: &[u8] = include_bytes!("pek.wav");
+
+    #[test]
+    fn shared_samples() {
+        let sound = DecodedSound::decode(DATA).unwrap();
+        let len = sound.samples.len();
+        assert!(len > 0);
+
+        let first = sound.source(false);
+        let second = sound.source(false);
+        assert_eq!(Arc::strong_count(&sound.samples), 3);
+        assert_eq!(first.total_duration(), Some(sound.duration()));
+
+        assert_eq!(first.count(), len);
+        assert_eq!(Arc::strong_count(&sound.samples), 2);
+
+        drop(second);
+        let looped = sound.source(true);
+        assert_eq!(looped.total_duration(), None);
+        assert_eq!(looped.take(len * 2 + 1).count(), len * 2 + 1);
+
+        let mut seeked = sound.source(false);
+        seeked.try_seek(sound.duration() * 2).unwrap();
+        assert_eq!(seeked.next(), None);
+        seeked.try_seek(Duration::ZERO).unwrap();
+        assert_eq!(seeked.count(), len);
+    }
+}
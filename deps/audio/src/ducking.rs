@@ -0,0 +1,74 @@
+use std::time::Duration;
+
+/// Lowers music while UI sounds are playing. See [`crate::Music::set_ducking`].
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Ducking {
+    /// Music volume multiplier while ducked.
+    pub level:   f32,
+    /// How long it takes to go down to `level`.
+    pub attack:  Duration,
+    /// How long it takes to go back to full volume.
+    pub release: Duration,
+}
+
+impl Ducking {
+    pub const DEFAULT: Self = Self {
+        level:   0.3,
+        attack:  Duration::from_millis(50),
+        release: Duration::from_millis(400),
+    };
+
+    /// Moves current music gain towards ducked or full volume.
+    pub(crate) fn step(&self, gain: f32, ducked: bool, delta: Duration) -> f32 {
+        let (target, time) = if ducked {
+            (self.level, self.attack)
+        } else {
+            (1.0, self.release)
+        };
+
+        if time.is_zero() {
+            return target;
+        }
+
+        let max_step = (1.0 - self.level).abs() * delta.as_secs_f32() / time.as_secs_f32();
+
+        gain + (target - gain).clamp(-max_step, max_step)
+    }
+}
+
+impl Default for Ducking {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use crate::Ducking;
+
+    #[test]
+    fn ducking() {
+        let ducking = Ducking {
+            level:   0.2,
+            attack:  Duration::from_millis(100),
+            release: Duration::from_millis(400),
+        };
+
+        let step = Duration::from_millis(50);
+
+        let gain = ducking.step(1.0, true, step);
+        assert!((gain - 0.6).abs() < 0.0001);
+
+        let gain = ducking.step(gain, true, step);
+        assert!((gain - 0.2).abs() < 0.0001);
+
+        assert!((ducking.step(gain, true, step) - 0.2).abs() < 0.0001);
+
+        let gain = ducking.step(gain, false, step);
+        assert!((gain - 0.3).abs() < 0.0001);
+
+        assert!((ducking.step(gain, false, Duration::from_secs(1)) - 1.0).abs() < 0.0001);
+    }
+}
@@ -0,0 +1,68 @@
+use std::time::Duration;
+
+use gm::LossyConvert;
+
+use crate::effects::processed::Processor;
+
+/// Repeats are dropped when they get this quiet.
+const SILENCE: f32 = 0.001;
+
+const MAX_FEEDBACK: f32 = 0.95;
+
+pub(crate) struct Echo {
+    delay:    Duration,
+    feedback: f32,
+    mix:      f32,
+    lines:    Vec<Vec<f32>>,
+    position: usize,
+}
+
+impl Echo {
+    pub(crate) fn new(delay: Duration, feedback: f32, mix: f32, sample_rate: u32, channels: usize) -> Self {
+        let len: usize = (delay.as_secs_f64() * f64::from(sample_rate)).round().lossy_convert();
+        let len = len.max(1);
+
+        Self {
+            delay,
+            feedback: feedback.clamp(0.0, MAX_FEEDBACK),
+            mix,
+            lines: vec![vec![0.0; len]; channels],
+            position: 0,
+        }
+    }
+}
+
+impl Processor for Echo {
+    fn process(&mut self, channel: usize, sample: f32) -> f32 {
+        let last = channel + 1 == self.lines.len();
+
+        let Some(line) = self.lines.get_mut(channel) else {
+            return sample;
+        };
+
+        let delayed = line[self.position];
+        line[self.position] = sample + delayed * self.feedback;
+
+        if last {
+            self.position = (self.position + 1) % line.len();
+        }
+
+        sample + delayed * self.mix
+    }
+
+    fn reset(&mut self) {
+        for line in &mut self.lines {
+            line.fill(0.0);
+        }
+        self.position = 0;
+    }
+
+    fn tail(&self) -> Duration {
+        if self.feedback <= 0.0 {
+            return self.delay;
+        }
+
+        let repeats = (SILENCE.ln() / self.feedback.ln()).ceil();
+        self.delay.mul_f32(repeats + 1.0)
+    }
+}
@@ -0,0 +1,87 @@
+use std::f32::consts::{FRAC_1_SQRT_2, PI};
+
+use gm::ToF32;
+
+use crate::effects::processed::Processor;
+
+#[derive(Copy, Clone, Debug, Default)]
+struct History {
+    x1: f32,
+    x2: f32,
+    y1: f32,
+    y2: f32,
+}
+
+/// Second order Butterworth filter. Coefficients are from the Audio EQ
+/// Cookbook.
+pub(crate) struct Biquad {
+    b0:      f32,
+    b1:      f32,
+    b2:      f32,
+    a1:      f32,
+    a2:      f32,
+    history: Vec<History>,
+}
+
+impl Biquad {
+    pub(crate) fn low_pass(cutoff: f32, sample_rate: u32, channels: usize) -> Self {
+        let (cos, alpha) = Self::params(cutoff, sample_rate);
+        let b1 = 1.0 - cos;
+        Self::new(
+            [b1 / 2.0, b1, b1 / 2.0],
+            [1.0 + alpha, -2.0 * cos, 1.0 - alpha],
+            channels,
+        )
+    }
+
+    pub(crate) fn high_pass(cutoff: f32, sample_rate: u32, channels: usize) -> Self {
+        let (cos, alpha) = Self::params(cutoff, sample_rate);
+        let b0 = (1.0 + cos) / 2.0;
+        Self::new(
+            [b0, -(1.0 + cos), b0],
+            [1.0 + alpha, -2.0 * cos, 1.0 - alpha],
+            channels,
+        )
+    }
+
+    fn params(cutoff: f32, sample_rate: u32) -> (f32, f32) {
+        let sample_rate = sample_rate.to_f32();
+        let cutoff = cutoff.clamp(10.0, sample_rate * 0.49);
+        let w0 = 2.0 * PI * cutoff / sample_rate;
+        (w0.cos(), w0.sin() / (2.0 * FRAC_1_SQRT_2))
+    }
+
+    fn new([b0, b1, b2]: [f32; 3], [a0, a1, a2]: [f32; 3], channels: usize) -> Self {
+        Self {
+            b0:      b0 / a0,
+            b1:      b1 / a0,
+            b2:      b2 / a0,
+            a1:      a1 / a0,
+            a2:      a2 / a0,
+            history: vec![History::default(); channels],
+        }
+    }
+}
+
+impl Processor for Biquad {
+    fn process(&mut self, channel: usize, x: f32) -> f32 {
+        if channel >= self.history.len() {
+            self.history.resize(channel + 1, History::default());
+        }
+
+        let h = &mut self.history[channel];
+
+        let y = self.b0 * x + self.b1 * h.x1 + self.b2 * h.x2 - self.a1 * h.y1 - self.a2 * h.y2;
+
+        h.x2 = h.x1;
+        h.x1 = x;
+        h.y2 = h.y1;
+        h.y1 = y;
+
+        y
+    }
+
+    fn reset(&mut self) {
+        self.history.fill(History::default());
+    }
+}
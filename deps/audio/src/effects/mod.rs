@@ -0,0 +1,230 @@
+mod echo;
+mod filter;
+mod pitch;
+mod processed;
+mod reverb;
+mod speed;
+
+use std::time::Duration;
+
+use crate::{
+    BoxedSource,
+    effects::{
+        echo::Echo, filter::Biquad, pitch::PitchShift, processed::Processed, reverb::Reverb, speed::Speed,
+    },
+};
+
+/// Processes samples of a sound before it reaches the backend. Effects are
+/// applied in the order they were added.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Effect {
+    /// Removes frequencies above `cutoff` Hz.
+    LowPass { cutoff: f32 },
+    /// Removes frequencies below `cutoff` Hz.
+    HighPass { cutoff: f32 },
+    /// `room` from 0.0 to 1.0 sets how long the reverb rings. `mix` is the
+    /// level of the reverberated signal.
+    Reverb { room: f32, mix: f32 },
+    /// Repeats the sound every `delay`. Each repeat is `feedback` times
+    /// quieter than the previous one.
+    Echo {
+        delay:    Duration,
+        feedback: f32,
+        mix:      f32,
+    },
+    /// Shifts pitch by semitones. Duration stays the same.
+    Pitch(f32),
+    /// Playback rate. Changes pitch together with duration.
+    Speed(f32),
+}
+
+impl Effect {
+    fn apply(self, source: BoxedSource) -> BoxedSource {
+        let rate = source.sample_rate();
+        let channels = usize::from(source.channels());
+
+        match self {
+            Self::LowPass { cutoff } => {
+                Box::new(Processed::new(source, Biquad::low_pass(cutoff, rate, channels)))
+            }
+            Self::HighPass { cutoff } => {
+                Box::new(Processed::new(source, Biquad::high_pass(cutoff, rate, channels)))
+            }
+            Self::Reverb { room, mix } => {
+                Box::new(Processed::new(source, Reverb::new(room, mix, rate, channels)))
+            }
+            Self::Echo { delay, feedback, mix } => Box::new(Processed::new(
+                source,
+                Echo::new(delay, feedback, mix, rate, channels),
+            )),
+            Self::Pitch(semitones) => {
+                Box::new(Processed::new(source, PitchShift::new(semitones, rate, channels)))
+            }
+            Self::Speed(speed) => Box::new(Speed::new(source, speed)),
+        }
+    }
+}
+
+pub(crate) fn apply(source: BoxedSource, effects: &[Effect]) -> BoxedSource {
+    effects.iter().fold(source, |source, effect| effect.apply(source))
+}
+
+#[cfg(test)]
+mod test {
+    use std::{f32::consts::PI, time::Duration};
+
+    use gm::ToF32;
+    use rodio::{Source, buffer::SamplesBuffer};
+
+    use crate::{Effect, effects::apply};
+
+    fn process(channels: u16, rate: u32, samples: Vec<f32>, effects: &[Effect]) -> Vec<f32> {
+        apply(Box::new(SamplesBuffer::new(channels, rate, samples)), effects).collect()
+    }
+
+    fn assert_close(actual: &[f32], expected: &[f32]) {
+        assert_eq!(actual.len(), expected.len(), "{actual:?}");
+        for (actual, expected) in actual.iter().zip(expected) {
+            assert!(
+                (actual - expected).abs() < 0.0001,
+                "Expected: {expected:?}. Got: {actual:?}"
+            );
+        }
+    }
+
+    fn impulse(len: usize) -> Vec<f32> {
+        let mut samples = vec![0.0; len];
+        samples[0] = 1.0;
+        samples
+    }
+
+    fn sine(frequency: f32, rate: u32, len: usize) -> Vec<f32> {
+        (0..len)
+            .map(|i| (2.0 * PI * frequency * i.to_f32() / rate.to_f32()).sin())
+            .collect()
+    }
+
+    fn zero_crossings(samples: &[f32]) -> usize {
+        samples.windows(2).filter(|pair| pair[0] < 0.0 && pair[1] >= 0.0).count()
+    }
+
+    #[test]
+    fn echo() {
+        let echo = Effect::Echo {
+            delay:    Duration::from_millis(200),
+            feedback: 0.5,
+            mix:      1.0,
+        };
+
+        let samples = process(1, 10, impulse(6), &[echo]);
+
+        assert_close(&samples[..9], &[1.0, 0.0, 1.0, 0.0, 0.5, 0.0, 0.25, 0.0, 0.125]);
+        // Tail keeps ringing after input has ended.
+        assert!(samples.len() > 6);
+        assert!(samples.last().unwrap().abs() < 0.001);
+
+        let stereo = process(2, 10, vec![1.0, -1.0, 0.0, 0.0, 0.0, 0.0], &[echo]);
+        assert_close(&stereo[..6], &[1.0, -1.0, 0.0, 0.0, 1.0, -1.0]);
+    }
+
+    #[test]
+    fn filters() {
+        let low = Effect::LowPass { cutoff: 1000.0 };
+        let high = Effect::HighPass { cutoff: 1000.0 };
+
+        let dc = vec![1.0; 2000];
+        let nyquist: Vec<f32> = (0..2000).map(|i| if i % 2 == 0 { 1.0 } else { -1.0 }).collect();
+
+        let low_dc = process(1, 44_100, dc.clone(), &[low]);
+        let low_nyquist = process(1, 44_100, nyquist.clone(), &[low]);
+        let high_dc = process(1, 44_100, dc, &[high]);
+        let high_nyquist = process(1, 44_100, nyquist, &[high]);
+
+        assert_close(&low_dc[1990..], &[1.0; 10]);
+        assert_close(&low_nyquist[1990..], &[0.0; 10]);
+        assert_close(&high_dc[1990..], &[0.0; 10]);
+        assert_close(&high_nyquist[1990..], &[
+            1.0, -1.0, 1.0, -1.0, 1.0, -1.0, 1.0, -1.0, 1.0, -1.0,
+        ]);
+
+        // Channels are filtered separately.
+        let stereo = process(2, 44_100, [1.0, -1.0].repeat(2000), &[low]);
+        assert_close(&stereo[3990..], &[1.0, -1.0].repeat(5));
+    }
+
+    #[test]
+    fn speed() {
+        let ramp: Vec<f32> = (0..8u8).map(f32::from).collect();
+
+        assert_close(&process(1, 10, ramp.clone(), &[Effect::Speed(2.0)]), &[
+            0.0, 2.0, 4.0, 6.0,
+        ]);
+        assert_close(&process(1, 10, ramp[..4].to_vec(), &[Effect::Speed(0.5)]), &[
+            0.0, 0.5, 1.0, 1.5, 2.0, 2.5, 3.0, 3.0,
+        ]);
+        assert_close(
+            &process(2, 10, vec![0.0, 10.0, 1.0, 11.0, 2.0, 12.0, 3.0, 13.0], &[
+                Effect::Speed(2.0),
+            ]),
+            &[0.0, 10.0, 2.0, 12.0],
+        );
+
+        let source = apply(Box::new(SamplesBuffer::new(1, 10, ramp)), &[Effect::Speed(2.0)]);
+        assert_eq!(source.total_duration().unwrap().as_millis(), 400);
+    }
+
+    #[test]
+    fn pitch() {
+        let input = sine(200.0, 8000, 8000);
+
+        let up = process(1, 8000, input.clone(), &[Effect::Pitch(12.0)]);
+        let down = process(1, 8000, input.clone(), &[Effect::Pitch(-12.0)]);
+
+        assert_eq!(up.len(), input.len());
+        assert_eq!(down.len(), input.len());
+
+        let original = zero_crossings(&input[1000..]);
+        let up = zero_crossings(&up[1000..]);
+        let down = zero_crossings(&down[1000..]);
+
+        assert!(up.abs_diff(original * 2) < original / 5, "{up} {original}");
+        assert!(down.abs_diff(original / 2) < original / 10, "{down} {original}");
+    }
+
+    #[test]
+    fn reverb() {
+        let dry = process(1, 44_100, impulse(100), &[Effect::Reverb {
+            room: 0.5,
+            mix:  0.0,
+        }]);
+        assert_close(&dry[..100], &impulse(100));
+
+        let wet = process(1, 44_100, impulse(100), &[Effect::Reverb {
+            room: 0.5,
+            mix:  0.5,
+        }]);
+        assert!(wet.len() > 44_100 / 2);
+
+        let energy = |samples: &[f32]| samples.iter().map(|sample| sample * sample).sum::<f32>();
+
+        let early = energy(&wet[2000..6000]);
+        let late = energy(&wet[wet.len() - 4000..]);
+
+        assert!(early > 0.0);
+        assert!(late < early / 100.0, "{late} {early}");
+        assert!(wet.iter().all(|sample| sample.abs() <= 1.0));
+    }
+
+    #[test]
+    fn chain() {
+        let echo = Effect::Echo {
+            delay:    Duration::from_millis(200),
+            feedback: 0.0,
+            mix:      0.5,
+        };
+
+        assert_close(&process(1, 10, impulse(4), &[echo, Effect::Speed(2.0)]), &[
+            1.0, 0.5, 0.0,
+        ]);
+    }
+}
@@ -0,0 +1,97 @@
+use std::time::Duration;
+
+use gm::{LossyConvert, ToF32};
+
+use crate::effects::processed::Processor;
+
+/// Length of the grain which is replayed at a different rate.
+const WINDOW: Duration = Duration::from_millis(40);
+
+#[derive(Clone)]
+struct Channel {
+    buffer: Vec<f32>,
+    write:  usize,
+    /// Read head position within the window. From 0.0 to 1.0. Second head is
+    /// half a window apart.
+    phase:  f32,
+}
+
+/// Delay line pitch shifter. Two read heads sweep through a short delay line
+/// faster or slower than it is written and crossfade into each other.
+pub(crate) struct PitchShift {
+    /// Phase change per sample.
+    step:     f32,
+    window:   usize,
+    channels: Vec<Channel>,
+}
+
+impl PitchShift {
+    pub(crate) fn new(semitones: f32, sample_rate: u32, channels: usize) -> Self {
+        let ratio = 2.0f32.powf(semitones / 12.0);
+
+        let window: usize = (WINDOW.as_secs_f64() * f64::from(sample_rate)).lossy_convert();
+        let window = window.max(4);
+
+        let step = (1.0 - ratio) / window.to_f32();
+
+        Self {
+            step,
+            window,
+            channels: vec![
+                Channel {
+                    buffer: vec![0.0; window + 2],
+                    write:  0,
+                    phase:  0.0,
+                };
+                channels
+            ],
+        }
+    }
+
+    fn read(channel: &Channel, window: usize, phase: f32) -> f32 {
+        let len = channel.buffer.len();
+        let delay = phase * window.to_f32();
+        let position = (channel.write + len).to_f32() - delay;
+
+        let index = position.floor();
+        let fraction = position - index;
+        let index: usize = index.lossy_convert();
+
+        let a = channel.buffer[index % len];
+        let b = channel.buffer[(index + 1) % len];
+
+        // Triangular window. Two heads half a window apart always sum to 1.
+        let gain = 1.0 - (2.0 * phase - 1.0).abs();
+
+        (a + (b - a) * fraction) * gain
+    }
+}
+
+impl Processor for PitchShift {
+    fn process(&mut self, channel: usize, sample: f32) -> f32 {
+        let window = self.window;
+        let step = self.step;
+
+        let Some(channel) = self.channels.get_mut(channel) else {
+            return sample;
+        };
+
+        channel.buffer[channel.write] = sample;
+
+        let second = (channel.phase + 0.5).fract();
+        let output = Self::read(channel, window, channel.phase) + Self::read(channel, window, second);
+
+        channel.write = (channel.write + 1) % channel.buffer.len();
+        channel.phase = (channel.phase + step).rem_euclid(1.0);
+
+        output
+    }
+
+    fn reset(&mut self) {
+        for channel in &mut self.channels {
+            channel.buffer.fill(0.0);
+            channel.write = 0;
+            channel.phase = 0.0;
+        }
+    }
+}
@@ -0,0 +1,109 @@
+use std::time::Duration;
+
+use gm::LossyConvert;
+use rodio::{Source, source::SeekError};
+
+use crate::BoxedSource;
+
+/// Effect which processes one sample at a time without changing duration.
+pub(crate) trait Processor: Send {
+    fn process(&mut self, channel: usize, sample: f32) -> f32;
+
+    /// Clears delay lines and filter memory. Called on seek.
+    fn reset(&mut self);
+
+    /// How long the effect keeps sounding after input has ended.
+    fn tail(&self) -> Duration {
+        Duration::ZERO
+    }
+}
+
+/// Runs input through a [`Processor`]. Silence is fed to the processor after
+/// input has ended so reverb and echo tails are not cut off.
+pub(crate) struct Processed<P> {
+    input:     BoxedSource,
+    processor: P,
+    channel:   usize,
+    /// Samples left to play after input has ended.
+    tail:      Option<usize>,
+}
+
+impl<P: Processor> Processed<P> {
+    pub(crate) fn new(input: BoxedSource, processor: P) -> Self {
+        Self {
+            input,
+            processor,
+            channel: 0,
+            tail: None,
+        }
+    }
+
+    fn channel_count(&self) -> usize {
+        usize::from(self.input.channels().max(1))
+    }
+
+    fn tail_len(&self) -> usize {
+        let frames: usize =
+            (self.processor.tail().as_secs_f64() * f64::from(self.input.sample_rate())).lossy_convert();
+        frames * self.channel_count()
+    }
+}
+
+impl<P: Processor> Iterator for Processed<P> {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        let sample = match &mut self.tail {
+            None => {
+                let Some(sample) = self.input.next() else {
+                    self.tail = Some(self.tail_len());
+                    return self.next();
+                };
+                sample
+            }
+            Some(0) => return None,
+            Some(left) => {
+                *left -= 1;
+                0.0
+            }
+        };
+
+        let output = self.processor.process(self.channel, sample);
+        self.channel = (self.channel + 1) % self.channel_count();
+
+        Some(output)
+    }
+}
+
+impl<P: Processor> Source for Processed<P> {
+    fn current_frame_len(&self) -> Option<usize> {
+        match self.tail {
+            Some(left) => Some(left),
+            // Input is over but the tail is not. Consumers stop on empty frame.
+            None => match self.input.current_frame_len() {
+                Some(0) => Some(self.tail_len()),
+                len => len,
+            },
+        }
+    }
+
+    fn channels(&self) -> u16 {
+        self.input.channels()
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.input.sample_rate()
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        Some(self.input.total_duration()? + self.processor.tail())
+    }
+
+    fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
+        self.input.try_seek(pos)?;
+        self.processor.reset();
+        self.channel = 0;
+        self.tail = None;
+        Ok(())
+    }
+}
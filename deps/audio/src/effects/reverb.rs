@@ -0,0 +1,142 @@
+use std::time::Duration;
+
+use gm::{LossyConvert, ToF32};
+
+use crate::effects::processed::Processor;
+
+/// Freeverb tunings for 44100 Hz.
+const COMBS: [usize; 4] = [1116, 1188, 1277, 1356];
+const ALLPASSES: [usize; 2] = [556, 441];
+/// Offset of right channel delay lines to widen stereo image.
+const STEREO_SPREAD: usize = 23;
+
+const INPUT_GAIN: f32 = 0.1;
+const DAMPING: f32 = 0.2;
+const ALLPASS_FEEDBACK: f32 = 0.5;
+/// Reverb time is cut to this.
+const MAX_TAIL: Duration = Duration::from_secs(5);
+
+struct Comb {
+    buffer:   Vec<f32>,
+    index:    usize,
+    filtered: f32,
+}
+
+impl Comb {
+    fn process(&mut self, input: f32, feedback: f32) -> f32 {
+        let output = self.buffer[self.index];
+        self.filtered = output * (1.0 - DAMPING) + self.filtered * DAMPING;
+        self.buffer[self.index] = input + self.filtered * feedback;
+        self.index = (self.index + 1) % self.buffer.len();
+        output
+    }
+}
+
+struct Allpass {
+    buffer: Vec<f32>,
+    index:  usize,
+}
+
+impl Allpass {
+    fn process(&mut self, input: f32) -> f32 {
+        let delayed = self.buffer[self.index];
+        self.buffer[self.index] = input + delayed * ALLPASS_FEEDBACK;
+        self.index = (self.index + 1) % self.buffer.len();
+        delayed - input
+    }
+}
+
+struct Channel {
+    combs:     Vec<Comb>,
+    allpasses: Vec<Allpass>,
+}
+
+/// Schroeder reverb with parallel comb filters followed by allpass filters.
+pub(crate) struct Reverb {
+    feedback: f32,
+    mix:      f32,
+    channels: Vec<Channel>,
+}
+
+impl Reverb {
+    pub(crate) fn new(room: f32, mix: f32, sample_rate: u32, channels: usize) -> Self {
+        let scale = |len: usize| {
+            let len: usize = (f64::from(len.to_f32()) * f64::from(sample_rate) / 44_100.0).lossy_convert();
+            vec![0.0; len.max(1)]
+        };
+
+        let channels = (0..channels)
+            .map(|channel| {
+                let spread = if channel % 2 == 1 { STEREO_SPREAD } else { 0 };
+                Channel {
+                    combs:     COMBS
+                        .iter()
+                        .map(|len| Comb {
+                            buffer:   scale(len + spread),
+                            index:    0,
+                            filtered: 0.0,
+                        })
+                        .collect(),
+                    allpasses: ALLPASSES
+                        .iter()
+                        .map(|len| Allpass {
+                            buffer: scale(len + spread),
+                            index:  0,
+                        })
+                        .collect(),
+                }
+            })
+            .collect();
+
+        Self {
+            feedback: 0.7 + 0.28 * room.clamp(0.0, 1.0),
+            mix: mix.clamp(0.0, 1.0),
+            channels,
+        }
+    }
+}
+
+impl Processor for Reverb {
+    fn process(&mut self, channel: usize, sample: f32) -> f32 {
+        let feedback = self.feedback;
+
+        let Some(channel) = self.channels.get_mut(channel) else {
+            return sample;
+        };
+
+        let input = sample * INPUT_GAIN;
+
+        let mut wet: f32 = channel.combs.iter_mut().map(|comb| comb.process(input, feedback)).sum();
+
+        for allpass in &mut channel.allpasses {
+            wet = allpass.process(wet);
+        }
+
+        sample * (1.0 - self.mix) + wet * self.mix
+    }
+
+    fn reset(&mut self) {
+        for channel in &mut self.channels {
+            for comb in &mut channel.combs {
+                comb.buffer.fill(0.0);
+                comb.filtered = 0.0;
+            }
+            for allpass in &mut channel.allpasses {
+                allpass.buffer.fill(0.0);
+            }
+        }
+    }
+
+    /// Time for the longest comb to decay by 60 dB.
+    fn tail(&self) -> Duration {
+        if self.mix <= 0.0 {
+            return Duration::ZERO;
+        }
+
+        let longest = COMBS[COMBS.len() - 1] + STEREO_SPREAD;
+        let loop_time = longest.to_f32() / 44_100.0;
+        let loops = 0.001f32.ln() / self.feedback.ln();
+
+        Duration::from_secs_f32(loop_time * loops).min(MAX_TAIL)
+    }
+}
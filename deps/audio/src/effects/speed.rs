@@ -0,0 +1,127 @@
+use std::time::Duration;
+
+use rodio::{Source, source::SeekError};
+
+use crate::BoxedSource;
+
+const MIN_SPEED: f32 = 0.01;
+
+/// Resamples input with linear interpolation so it plays `speed` times
+/// faster.
+pub(crate) struct Speed {
+    input:    BoxedSource,
+    rate:     f32,
+    channels: usize,
+    current:  Vec<f32>,
+    next:     Vec<f32>,
+    /// Position between current and next frame.
+    fraction: f32,
+    /// Index of next sample of output frame.
+    channel:  usize,
+    started:  bool,
+}
+
+impl Speed {
+    pub(crate) fn new(input: BoxedSource, speed: f32) -> Self {
+        let channels = usize::from(input.channels().max(1));
+        Self {
+            input,
+            rate: speed.max(MIN_SPEED),
+            channels,
+            current: Vec::with_capacity(channels),
+            next: Vec::with_capacity(channels),
+            fraction: 0.0,
+            channel: 0,
+            started: false,
+        }
+    }
+
+    /// Returns `false` if input has ended. Partial frames are dropped.
+    fn read_frame(&mut self) -> bool {
+        self.next.clear();
+        for _ in 0..self.channels {
+            let Some(sample) = self.input.next() else {
+                self.next.clear();
+                return false;
+            };
+            self.next.push(sample);
+        }
+        true
+    }
+
+    /// Moves to the next input frame. Last frame is held when there is no
+    /// next one.
+    fn advance(&mut self) -> bool {
+        if self.next.is_empty() {
+            self.current.clear();
+            return false;
+        }
+        std::mem::swap(&mut self.current, &mut self.next);
+        self.read_frame();
+        true
+    }
+}
+
+impl Iterator for Speed {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        if !self.started {
+            self.started = true;
+            self.read_frame();
+            self.advance();
+        }
+
+        if self.current.is_empty() {
+            return None;
+        }
+
+        let channel = self.channel;
+        let current = self.current[channel];
+        let next = self.next.get(channel).copied().unwrap_or(current);
+        let sample = current + (next - current) * self.fraction;
+
+        self.channel += 1;
+
+        if self.channel == self.channels {
+            self.channel = 0;
+            self.fraction += self.rate;
+            while self.fraction >= 1.0 {
+                self.fraction -= 1.0;
+                if !self.advance() {
+                    break;
+                }
+            }
+        }
+
+        Some(sample)
+    }
+}
+
+impl Source for Speed {
+    fn current_frame_len(&self) -> Option<usize> {
+        None
+    }
+
+    fn channels(&self) -> u16 {
+        u16::try_from(self.channels).unwrap_or(u16::MAX)
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.input.sample_rate()
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        Some(self.input.total_duration()?.div_f32(self.rate))
+    }
+
+    fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
+        self.input.try_seek(pos.mul_f32(self.rate))?;
+        self.current.clear();
+        self.next.clear();
+        self.fraction = 0.0;
+        self.channel = 0;
+        self.started = false;
+        Ok(())
+    }
+}
@@ -0,0 +1,98 @@
+use std::{sync::Arc, time::Duration};
+
+use gm::flat::Point;
+use log::error;
+
+use crate::mixer::{Instance, Mixer};
+
+/// Controls a playing sound. Does nothing if the sound has finished or there
+/// is no audio output. Dropping the handle doesn't stop the sound.
+#[derive(Clone, Default)]
+pub struct SoundHandle {
+    instance: Option<Arc<Instance>>,
+}
+
+impl SoundHandle {
+    pub(crate) fn new(instance: Arc<Instance>) -> Self {
+        Self {
+            instance: Some(instance),
+        }
+    }
+
+    fn with(&self, action: impl FnOnce(&Instance)) {
+        if let Some(instance) = &self.instance {
+            action(instance);
+        }
+    }
+
+    pub fn is_playing(&self) -> bool {
+        self.instance
+            .as_ref()
+            .is_some_and(|instance| !instance.voice.is_finished() && !instance.voice.is_paused())
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.instance.as_ref().is_none_or(|instance| instance.voice.is_finished())
+    }
+
+    pub fn stop(&self) {
+        self.with(|instance| instance.voice.stop());
+    }
+
+    pub fn pause(&self) {
+        self.with(|instance| instance.voice.pause());
+    }
+
+    pub fn resume(&self) {
+        self.with(|instance| instance.voice.resume());
+    }
+
+    /// Volume of this sound before bus and master volume are applied.
+    pub fn volume(&self) -> f32 {
+        self.instance.as_ref().map_or(0.0, |instance| instance.volume())
+    }
+
+    pub fn set_volume(&self, volume: f32) {
+        self.with(|instance| {
+            instance.set_volume(volume);
+            Mixer::apply(instance);
+        });
+    }
+
+    pub fn fade_to(&self, volume: f32, duration: Duration) {
+        self.with(|instance| instance.fade_to(volume, duration, false));
+    }
+
+    /// Fades to silence and stops.
+    pub fn fade_out(&self, duration: Duration) {
+        self.with(|instance| instance.fade_to(0.0, duration, true));
+    }
+
+    pub fn seek(&self, position: Duration) {
+        self.with(|instance| {
+            if let Err(err) = instance.voice.seek(position) {
+                error!("Failed to seek sound: {err}");
+            }
+        });
+    }
+
+    /// Playback position from the start of the sound.
+    pub fn elapsed(&self) -> Duration {
+        self.instance
+            .as_ref()
+            .map_or(Duration::ZERO, |instance| instance.voice.elapsed())
+    }
+
+    pub fn position(&self) -> Option<Point> {
+        self.instance.as_ref().and_then(|instance| instance.position())
+    }
+
+    /// Moves positional sound. `None` makes it non positional.
+    pub fn set_position(&self, position: impl Into<Option<Point>>) {
+        let position = position.into();
+        self.with(|instance| {
+            instance.set_position(position);
+            Mixer::apply(instance);
+        });
+    }
+}
@@ -1,8 +1,34 @@
 // mod android_sound;
 // use android_sound as sound;
+mod backend;
+mod bus;
+mod decoded_sound;
+mod ducking;
+mod effects;
+mod handle;
+mod mixer;
+mod music;
+mod output;
+mod panned;
+mod play_options;
+mod recording_backend;
+mod rodio_backend;
 mod sound;
+mod spatial;
 
+pub use backend::{AudioBackend, BoxedSource, PlayRequest, Voice};
+pub use bus::Bus;
+pub use decoded_sound::DecodedSound;
+pub use ducking::Ducking;
+pub use effects::Effect;
+pub use handle::SoundHandle;
 use manage::managed;
+pub use mixer::Mixer;
+pub use music::Music;
+pub use play_options::PlayOptions;
+pub use recording_backend::{AudioEvent, RecordingBackend};
+pub use rodio_backend::RodioBackend;
 pub use sound::Sound;
+pub use spatial::SpatialRange;
 
 managed!(Sound);
@@ -0,0 +1,354 @@
+use std::{
+    sync::{Arc, Mutex, MutexGuard},
+    time::{Duration, Instant},
+};
+
+use gm::flat::Point;
+use rodio::Source;
+
+use crate::{
+    AudioBackend, Bus, Ducking, Effect, PlayOptions, RodioBackend, SoundHandle, SpatialRange, Voice,
+    backend::PlayRequest, bus::Levels, effects, panned::Panned, spatial::PanGains,
+};
+
+static MIXER: Mutex<MixerState> = Mutex::new(MixerState {
+    backend:   None,
+    levels:    Levels::DEFAULT,
+    listener:  Point::new(0.0, 0.0),
+    range:     SpatialRange::DEFAULT,
+    instances: vec![],
+    effects:   [const { Vec::new() }; 3],
+    ducking:   None,
+    duck:      1.0,
+    updated:   None,
+});
+
+/// Tests share the global mixer.
+#[cfg(test)]
+pub(crate) static TEST_LOCK: Mutex<()> = Mutex::new(());
+
+struct MixerState {
+    /// [`RodioBackend`] if not set.
+    backend:   Option<Arc<dyn AudioBackend>>,
+    levels:    Levels,
+    listener:  Point,
+    range:     SpatialRange,
+    instances: Vec<Arc<Instance>>,
+    effects:   [Vec<Effect>; 3],
+    ducking:   Option<Ducking>,
+    /// Current music gain from ducking.
+    duck:      f32,
+    updated:   Option<Instant>,
+}
+
+impl MixerState {
+    fn backend(&mut self) -> Arc<dyn AudioBackend> {
+        self.backend.get_or_insert_with(|| Arc::new(RodioBackend)).clone()
+    }
+
+    fn gain(&self, bus: Bus, state: &InstanceState) -> (f32, (f32, f32)) {
+        let mut volume = self.levels.gain(bus) * state.volume;
+
+        if bus == Bus::Music {
+            volume *= self.duck;
+        }
+
+        let Some(position) = state.position else {
+            return (volume, (1.0, 1.0));
+        };
+
+        let (attenuation, left, right) = self.range.apply(self.listener, position);
+        volume *= attenuation;
+
+        (volume, (left, right))
+    }
+
+    fn apply(&self, instance: &Instance) {
+        let (volume, (left, right)) = self.gain(instance.bus, &instance.state());
+        instance.pan.set(left, right);
+        instance.voice.set_volume(volume);
+    }
+
+    fn apply_all(&self) {
+        for instance in &self.instances {
+            self.apply(instance);
+        }
+    }
+
+    fn update_ducking(&mut self, now: Instant) {
+        let delta = self.updated.map_or(Duration::ZERO, |updated| now.duration_since(updated));
+        self.updated = Some(now);
+
+        let Some(ducking) = self.ducking else {
+            self.duck = 1.0;
+            return;
+        };
+
+        let ducked = self
+            .instances
+            .iter()
+            .any(|instance| instance.bus == Bus::UI && !instance.voice.is_paused());
+
+        self.duck = ducking.step(self.duck, ducked, delta);
+    }
+}
+
+/// Volume of all playing sounds, buses and the listener position for
+/// positional sounds.
+pub struct Mixer;
+
+impl Mixer {
+    /// Stops all sounds and sends new ones to `backend`.
+    pub fn set_backend(backend: impl AudioBackend + 'static) {
+        Self::stop_all();
+        state().backend = Some(Arc::new(backend));
+    }
+
+    pub fn master_volume() -> f32 {
+        state().levels.master_volume()
+    }
+
+    pub fn set_master_volume(volume: f32) {
+        let mut state = state();
+        state.levels.set_master_volume(volume);
+        state.apply_all();
+    }
+
+    pub fn is_master_muted() -> bool {
+        state().levels.is_master_muted()
+    }
+
+    pub fn set_master_muted(muted: bool) {
+        let mut state = state();
+        state.levels.set_master_muted(muted);
+        state.apply_all();
+    }
+
+    pub fn volume(bus: Bus) -> f32 {
+        state().levels.volume(bus)
+    }
+
+    pub fn set_volume(bus: Bus, volume: f32) {
+        let mut state = state();
+        state.levels.set_volume(bus, volume);
+        state.apply_all();
+    }
+
+    pub fn is_muted(bus: Bus) -> bool {
+        state().levels.is_muted(bus)
+    }
+
+    pub fn set_muted(bus: Bus, muted: bool) {
+        let mut state = state();
+        state.levels.set_muted(bus, muted);
+        state.apply_all();
+    }
+
+    pub fn listener() -> Point {
+        state().listener
+    }
+
+    /// Position positional sounds are heard from. Usually the camera
+    /// position.
+    pub fn set_listener(position: Point) {
+        state().listener = position;
+    }
+
+    pub fn set_spatial_range(range: SpatialRange) {
+        state().range = range;
+    }
+
+    pub fn effects(bus: Bus) -> Vec<Effect> {
+        state().effects[bus.index()].clone()
+    }
+
+    /// Effects applied to every sound on `bus` after its own effects. Sounds
+    /// which are already playing keep the effects they were started with.
+    pub fn set_effects(bus: Bus, effects: Vec<Effect>) {
+        state().effects[bus.index()] = effects;
+    }
+
+    pub(crate) fn set_ducking(ducking: Option<Ducking>) {
+        let mut state = state();
+        state.ducking = ducking;
+        if ducking.is_none() {
+            state.duck = 1.0;
+            state.apply_all();
+        }
+    }
+
+    /// Number of sounds currently playing.
+    pub fn playing() -> usize {
+        state().instances.len()
+    }
+
+    pub fn stop_all() {
+        let mut state = state();
+        for instance in state.instances.drain(..) {
+            instance.voice.stop();
+        }
+    }
+
+    pub fn stop_bus(bus: Bus) {
+        state().instances.retain(|instance| {
+            if instance.bus == bus {
+                instance.voice.stop();
+            }
+            instance.bus != bus
+        });
+    }
+
+    /// Advances fades, updates positional sounds and drops finished ones.
+    /// Should be called every frame.
+    pub fn update() {
+        let mut state = state();
+        let now = Instant::now();
+
+        state.instances.retain(|instance| {
+            instance.advance_fade(now);
+            !instance.voice.is_finished()
+        });
+
+        state.update_ducking(now);
+        state.apply_all();
+    }
+
+    pub(crate) fn play(
+        name: impl ToString,
+        source: impl Source<Item = f32> + Send + 'static,
+        options: PlayOptions,
+    ) -> SoundHandle {
+        let fade = options.fade_in.map(|duration| Fade {
+            from: 0.0,
+            to: options.volume,
+            started: Instant::now(),
+            duration,
+            stop: false,
+        });
+
+        let instance_state = InstanceState {
+            volume: if fade.is_some() { 0.0 } else { options.volume },
+            position: options.position,
+            fade,
+        };
+
+        let pan = Arc::<PanGains>::default();
+
+        let mut state = state();
+
+        let (volume, (left, right)) = state.gain(options.bus, &instance_state);
+        pan.set(left, right);
+
+        let source = effects::apply(Box::new(source), &options.effects);
+        let source = effects::apply(source, &state.effects[options.bus.index()]);
+
+        let Some(voice) = state.backend().play(PlayRequest {
+            name: name.to_string(),
+            bus: options.bus,
+            volume,
+            source: Box::new(Panned::new(source, pan.clone())),
+        }) else {
+            return SoundHandle::default();
+        };
+
+        let instance = Arc::new(Instance {
+            voice,
+            bus: options.bus,
+            pan,
+            state: Mutex::new(instance_state),
+        });
+
+        state.instances.push(instance.clone());
+
+        SoundHandle::new(instance)
+    }
+
+    pub(crate) fn apply(instance: &Instance) {
+        state().apply(instance);
+    }
+}
+
+fn state() -> MutexGuard<'static, MixerState> {
+    MIXER.lock().unwrap()
+}
+
+#[derive(Debug)]
+struct Fade {
+    from:     f32,
+    to:       f32,
+    started:  Instant,
+    duration: Duration,
+    /// Stop the sound when the fade is finished.
+    stop:     bool,
+}
+
+#[derive(Debug)]
+struct InstanceState {
+    volume:   f32,
+    position: Option<Point>,
+    fade:     Option<Fade>,
+}
+
+pub(crate) struct Instance {
+    pub(crate) voice: Box<dyn Voice>,
+    pub(crate) bus:   Bus,
+    pan:              Arc<PanGains>,
+    state:            Mutex<InstanceState>,
+}
+
+impl Instance {
+    fn state(&self) -> MutexGuard<'_, InstanceState> {
+        self.state.lock().unwrap()
+    }
+
+    pub(crate) fn volume(&self) -> f32 {
+        self.state().volume
+    }
+
+    pub(crate) fn set_volume(&self, volume: f32) {
+        let mut state = self.state();
+        state.volume = volume.max(0.0);
+        state.fade = None;
+    }
+
+    pub(crate) fn position(&self) -> Option<Point> {
+        self.state().position
+    }
+
+    pub(crate) fn set_position(&self, position: Option<Point>) {
+        self.state().position = position;
+    }
+
+    pub(crate) fn fade_to(&self, volume: f32, duration: Duration, stop: bool) {
+        let mut state = self.state();
+        state.fade = Some(Fade {
+            from: state.volume,
+            to: volume.max(0.0),
+            started: Instant::now(),
+            duration,
+            stop,
+        });
+    }
+
+    fn advance_fade(&self, now: Instant) {
+        let mut state = self.state();
+
+        let Some(fade) = state.fade.take() else {
+            return;
+        };
+
+        let elapsed = now.duration_since(fade.started);
+
+        if elapsed >= fade.duration {
+            state.volume = fade.to;
+            if fade.stop {
+                self.voice.stop();
+            }
+            return;
+        }
+
+        let progress = elapsed.as_secs_f32() / fade.duration.as_secs_f32();
+        state.volume = fade.from + (fade.to - fade.from) * progress;
+        state.fade = Some(fade);
+    }
+}
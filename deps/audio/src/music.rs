@@ -0,0 +1,182 @@
+use std::{
+    fs::File,
+    io::{BufReader, Cursor},
+    path::PathBuf,
+    sync::Mutex,
+    time::Duration,
+};
+
+use log::error;
+use rodio::{Decoder, Source};
+
+use crate::{BoxedSource, Bus, Ducking, Mixer, PlayOptions, SoundHandle};
+
+static ROOT: Mutex<Option<PathBuf>> = Mutex::new(None);
+static CURRENT: Mutex<Option<SoundHandle>> = Mutex::new(None);
+
+/// Music tracks are streamed from disk instead of being loaded into memory.
+/// Only one track plays at a time except during crossfade.
+pub struct Music;
+
+impl Music {
+    pub fn set_root_path(path: impl Into<PathBuf>) {
+        *ROOT.lock().unwrap() = Some(path.into());
+    }
+
+    /// Plays track in a loop on music bus. Stops current one.
+    pub fn play(name: &str) -> SoundHandle {
+        Self::play_with(name, PlayOptions::new().looped())
+    }
+
+    pub fn play_with(name: &str, options: PlayOptions) -> SoundHandle {
+        Self::stop();
+        Self::start(name, options)
+    }
+
+    /// Fades current track out while new one fades in. New track is looped.
+    pub fn crossfade(name: &str, duration: Duration) -> SoundHandle {
+        Self::crossfade_with(name, duration, PlayOptions::new().looped())
+    }
+
+    pub fn crossfade_with(name: &str, duration: Duration, options: PlayOptions) -> SoundHandle {
+        Self::fade_out(duration);
+        Self::start(name, options.fade_in(duration))
+    }
+
+    /// Music is lowered while sounds on UI bus are playing. `None` disables
+    /// ducking. Requires [`Mixer::update`] to be called every frame.
+    pub fn set_ducking(ducking: impl Into<Option<Ducking>>) {
+        Mixer::set_ducking(ducking.into());
+    }
+
+    fn start(name: &str, options: PlayOptions) -> SoundHandle {
+        let handle = match Self::source(name, options.looped) {
+            Ok(source) => Mixer::play(name, source, options.bus(Bus::Music)),
+            Err(err) => {
+                error!("Failed to play music {name}: {err}");
+                SoundHandle::default()
+            }
+        };
+
+        *CURRENT.lock().unwrap() = Some(handle.clone());
+
+        handle
+    }
+
+    pub fn current() -> Option<SoundHandle> {
+        CURRENT.lock().unwrap().clone().filter(|handle| !handle.is_finished())
+    }
+
+    pub fn stop() {
+        if let Some(handle) = CURRENT.lock().unwrap().take() {
+            handle.stop();
+        }
+    }
+
+    pub fn fade_out(duration: Duration) {
+        if let Some(handle) = CURRENT.lock().unwrap().take() {
+            handle.fade_out(duration);
+        }
+    }
+
+    fn source(name: &str, looped: bool) -> anyhow::Result<BoxedSource> {
+        let path = ROOT.lock().unwrap().clone().unwrap_or_default().join(name);
+
+        // Packed assets can't be streamed from a file.
+        if asset_pack::is_mounted() || !path.exists() {
+            let data = Cursor::new(asset_pack::read(&path)?);
+            return Ok(if looped {
+                Box::new(Decoder::new_looped(data)?.convert_samples())
+            } else {
+                Box::new(Decoder::new(data)?.convert_samples())
+            });
+        }
+
+        let file = BufReader::new(File::open(&path)?);
+
+        Ok(if looped {
+            Box::new(Decoder::new_looped(file)?.convert_samples())
+        } else {
+            Box::new(Decoder::new(file)?.convert_samples())
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{fs, time::Duration};
+
+    use manage::resource_loader::ResourceLoader;
+
+    use crate::{
+        AudioEvent, Bus, Ducking, Mixer, Music, RecordingBackend, Sound, mixer::TEST_LOCK,
+        recording_backend::test::wav,
+    };
+
+    #[test]
+    fn crossfade() {
+        let _lock = TEST_LOCK.lock().unwrap();
+
+        let backend = RecordingBackend::install();
+
+        let root = std::env::temp_dir().join("audio-music-test");
+        fs::create_dir_all(&root).unwrap();
+        fs::write(root.join("calm.wav"), wav(&[16384; 4410])).unwrap();
+        fs::write(root.join("battle.wav"), wav(&[16384; 4410])).unwrap();
+        Music::set_root_path(&root);
+
+        let calm = Music::play("calm.wav");
+        let battle = Music::crossfade("battle.wav", Duration::ZERO);
+
+        assert_eq!(backend.events()[1], AudioEvent::Play {
+            name:   "battle.wav".into(),
+            bus:    Bus::Music,
+            volume: 0.0,
+        });
+        assert_eq!(backend.active(), 2);
+
+        Mixer::update();
+
+        assert!(calm.is_finished());
+        assert!((battle.volume() - 1.0).abs() < 0.0001);
+        assert!((backend.render(1)[0] - 0.5).abs() < 0.001);
+        assert_eq!(backend.active(), 1);
+
+        Music::stop();
+        assert!(battle.is_finished());
+        assert!(Music::current().is_none());
+    }
+
+    #[test]
+    fn ducking() {
+        let _lock = TEST_LOCK.lock().unwrap();
+
+        let backend = RecordingBackend::install();
+
+        Music::set_ducking(Ducking {
+            level:   0.25,
+            attack:  Duration::ZERO,
+            release: Duration::ZERO,
+        });
+
+        let theme = Sound::load_data(&wav(&[16384; 44_100]), "theme.wav");
+        let click = Sound::load_data(&wav(&[16384; 44_100]), "click.wav");
+
+        theme.play_on(Bus::Music);
+        Mixer::update();
+        assert!((backend.render(1)[0] - 0.5).abs() < 0.001);
+
+        let handle = click.play_on(Bus::UI);
+        Mixer::update();
+        let ducked = backend.render(1)[0];
+        // Music and click.
+        assert!((ducked - (0.125 + 0.5)).abs() < 0.001, "{ducked}");
+
+        handle.stop();
+        Mixer::update();
+        assert!((backend.render(1)[0] - 0.5).abs() < 0.001);
+
+        Music::set_ducking(None);
+        Mixer::stop_all();
+    }
+}
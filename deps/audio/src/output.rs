@@ -0,0 +1,40 @@
+use std::{
+    sync::{OnceLock, mpsc::sync_channel},
+    thread,
+};
+
+use dispatch::blocked_on;
+use log::error;
+use rodio::{OutputStream, OutputStreamHandle};
+
+static OUTPUT: OnceLock<Option<OutputStreamHandle>> = OnceLock::new();
+
+/// Handle to the output stream shared by all sounds. The stream itself is
+/// not `Send` so it lives on its own thread for the lifetime of the app.
+/// `None` if there is no audio device.
+pub(crate) fn output() -> Option<&'static OutputStreamHandle> {
+    OUTPUT
+        .get_or_init(|| {
+            let (sender, receiver) = sync_channel(1);
+
+            let thread = thread::Builder::new()
+                .name("audio-output".into())
+                .spawn(move || match OutputStream::try_default() {
+                    Ok((_stream, handle)) => {
+                        _ = sender.send(Some(handle));
+                        loop {
+                            thread::park();
+                        }
+                    }
+                    Err(err) => {
+                        error!("Failed to open audio output. Sounds are disabled. Error: {err}");
+                        _ = sender.send(None);
+                    }
+                })
+                .expect("Failed to spawn audio output thread");
+
+            let _blocked = blocked_on(thread.thread());
+            receiver.recv().ok().flatten()
+        })
+        .as_ref()
+}
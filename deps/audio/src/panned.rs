@@ -0,0 +1,97 @@
+use std::{sync::Arc, time::Duration};
+
+use rodio::{Source, source::SeekError};
+
+use crate::spatial::PanGains;
+
+/// Applies left and right gains. Mono input is turned into stereo so it can
+/// be panned. Input with more than 2 channels is passed as is.
+pub(crate) struct Panned<S> {
+    input:   S,
+    gains:   Arc<PanGains>,
+    channel: u16,
+    right:   Option<f32>,
+}
+
+impl<S: Source<Item = f32>> Panned<S> {
+    pub(crate) fn new(input: S, gains: Arc<PanGains>) -> Self {
+        Self {
+            input,
+            gains,
+            channel: 0,
+            right: None,
+        }
+    }
+
+    fn is_mono(&self) -> bool {
+        self.input.channels() == 1
+    }
+}
+
+impl<S: Source<Item = f32>> Iterator for Panned<S> {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        if let Some(right) = self.right.take() {
+            return Some(right);
+        }
+
+        let sample = self.input.next()?;
+
+        match self.input.channels() {
+            1 => {
+                self.right = Some(sample * self.gains.right());
+                Some(sample * self.gains.left())
+            }
+            2 => {
+                let gain = if self.channel == 0 {
+                    self.gains.left()
+                } else {
+                    self.gains.right()
+                };
+                self.channel = 1 - self.channel;
+                Some(sample * gain)
+            }
+            _ => Some(sample),
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (min, max) = self.input.size_hint();
+        if self.is_mono() {
+            let pending = usize::from(self.right.is_some());
+            (min * 2 + pending, max.map(|max| max * 2 + pending))
+        } else {
+            (min, max)
+        }
+    }
+}
+
+impl<S: Source<Item = f32>> Source for Panned<S> {
+    fn current_frame_len(&self) -> Option<usize> {
+        let len = self.input.current_frame_len()?;
+        Some(if self.is_mono() {
+            len * 2 + usize::from(self.right.is_some())
+        } else {
+            len
+        })
+    }
+
+    fn channels(&self) -> u16 {
+        if self.is_mono() { 2 } else { self.input.channels() }
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.input.sample_rate()
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        self.input.total_duration()
+    }
+
+    fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
+        self.channel = 0;
+        self.right = None;
+        self.input.try_seek(pos)
+    }
+}
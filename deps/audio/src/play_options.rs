@@ -0,0 +1,68 @@
+use std::time::Duration;
+
+use gm::flat::Point;
+
+use crate::{Bus, Effect};
+
+#[derive(Clone, Debug)]
+pub struct PlayOptions {
+    pub(crate) bus:      Bus,
+    pub(crate) volume:   f32,
+    pub(crate) looped:   bool,
+    pub(crate) position: Option<Point>,
+    pub(crate) fade_in:  Option<Duration>,
+    pub(crate) effects:  Vec<Effect>,
+}
+
+impl PlayOptions {
+    pub const fn new() -> Self {
+        Self {
+            bus:      Bus::Sfx,
+            volume:   1.0,
+            looped:   false,
+            position: None,
+            fade_in:  None,
+            effects:  Vec::new(),
+        }
+    }
+
+    pub const fn bus(mut self, bus: Bus) -> Self {
+        self.bus = bus;
+        self
+    }
+
+    pub const fn volume(mut self, volume: f32) -> Self {
+        self.volume = volume;
+        self
+    }
+
+    pub const fn looped(mut self) -> Self {
+        self.looped = true;
+        self
+    }
+
+    /// Position in level coordinates. Sound is panned and attenuated
+    /// relative to the listener. See [`crate::Mixer::set_listener`].
+    pub const fn at(mut self, position: Point) -> Self {
+        self.position = Some(position);
+        self
+    }
+
+    pub const fn fade_in(mut self, duration: Duration) -> Self {
+        self.fade_in = Some(duration);
+        self
+    }
+
+    /// Adds effect to the end of this sound's effect chain. Bus effects are
+    /// applied after it. See [`crate::Mixer::set_effects`].
+    pub fn effect(mut self, effect: Effect) -> Self {
+        self.effects.push(effect);
+        self
+    }
+}
+
+impl Default for PlayOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,414 @@
+use std::{
+    sync::{Arc, Mutex, Weak},
+    time::Duration,
+};
+
+use gm::{LossyConvert, ToF32};
+use rodio::{
+    Source,
+    source::{SeekError, UniformSourceIterator},
+};
+
+use crate::{AudioBackend, BoxedSource, Bus, Mixer, Voice, backend::PlayRequest};
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum AudioEvent {
+    Play {
+        name:   String,
+        bus:    Bus,
+        volume: f32,
+    },
+    Stop {
+        name: String,
+    },
+    Pause {
+        name: String,
+    },
+    Resume {
+        name: String,
+    },
+    Volume {
+        name:   String,
+        volume: f32,
+    },
+    Seek {
+        name:     String,
+        position: Duration,
+    },
+}
+
+/// Doesn't need an audio device. Records what the mixer does and mixes
+/// playing sounds into a sample buffer on demand. For tests.
+#[derive(Clone, Default)]
+pub struct RecordingBackend {
+    recorder: Arc<Recorder>,
+}
+
+impl RecordingBackend {
+    pub const SAMPLE_RATE: u32 = 44_100;
+    pub const CHANNELS: u16 = 2;
+
+    /// Creates recording backend and sets it to the mixer.
+    pub fn install() -> Self {
+        let backend = Self::default();
+        Mixer::set_backend(backend.clone());
+        backend
+    }
+
+    pub fn events(&self) -> Vec<AudioEvent> {
+        self.recorder.events.lock().unwrap().clone()
+    }
+
+    pub fn take_events(&self) -> Vec<AudioEvent> {
+        std::mem::take(&mut self.recorder.events.lock().unwrap())
+    }
+
+    /// How many times sound with this name was played.
+    pub fn play_count(&self, name: &str) -> usize {
+        self.recorder
+            .events
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|event| matches!(event, AudioEvent::Play { name: played, .. } if played == name))
+            .count()
+    }
+
+    /// Number of sounds which are not finished or stopped.
+    pub fn active(&self) -> usize {
+        self.recorder.voices.lock().unwrap().len()
+    }
+
+    /// Mixes next `frames` frames of all playing sounds. Returns interleaved
+    /// stereo samples at [`Self::SAMPLE_RATE`].
+    pub fn render(&self, frames: usize) -> Vec<f32> {
+        let mut output = vec![0.0; frames * usize::from(Self::CHANNELS)];
+
+        self.recorder.voices.lock().unwrap().retain(|voice| {
+            let mut state = voice.state.lock().unwrap();
+
+            if state.paused {
+                return true;
+            }
+
+            let volume = state.volume;
+
+            let Some(source) = &mut state.source else {
+                return false;
+            };
+
+            let mut rendered = 0;
+
+            for sample in &mut output {
+                let Some(next) = source.next() else {
+                    break;
+                };
+                *sample += next * volume;
+                rendered += 1;
+            }
+
+            state.rendered += rendered;
+
+            if rendered < output.len() {
+                state.source = None;
+            }
+
+            state.source.is_some()
+        });
+
+        output
+    }
+}
+
+impl AudioBackend for RecordingBackend {
+    fn play(&self, request: PlayRequest) -> Option<Box<dyn Voice>> {
+        self.recorder.push(AudioEvent::Play {
+            name:   request.name.clone(),
+            bus:    request.bus,
+            volume: request.volume,
+        });
+
+        let input = Shared::new(request.source);
+
+        let voice = Arc::new(RecordingVoice {
+            name:     request.name,
+            recorder: Arc::downgrade(&self.recorder),
+            input:    input.clone(),
+            state:    Mutex::new(VoiceState {
+                source:   UniformSourceIterator::new(input, Self::CHANNELS, Self::SAMPLE_RATE).into(),
+                volume:   request.volume,
+                paused:   false,
+                rendered: 0,
+            }),
+        });
+
+        self.recorder.voices.lock().unwrap().push(voice.clone());
+
+        Some(Box::new(voice))
+    }
+}
+
+#[derive(Default)]
+struct Recorder {
+    events: Mutex<Vec<AudioEvent>>,
+    voices: Mutex<Vec<Arc<RecordingVoice>>>,
+}
+
+impl Recorder {
+    fn push(&self, event: AudioEvent) {
+        self.events.lock().unwrap().push(event);
+    }
+}
+
+/// Source shared between the sample rate converter and the voice so the voice
+/// can seek it.
+#[derive(Clone)]
+struct Shared(Arc<Mutex<BoxedSource>>);
+
+impl Shared {
+    fn new(source: BoxedSource) -> Self {
+        Self(Arc::new(Mutex::new(source)))
+    }
+
+    fn seek(&self, position: Duration) -> Result<(), SeekError> {
+        self.0.lock().unwrap().try_seek(position)
+    }
+}
+
+impl Iterator for Shared {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        self.0.lock().unwrap().next()
+    }
+}
+
+impl Source for Shared {
+    fn current_frame_len(&self) -> Option<usize> {
+        self.0.lock().unwrap().current_frame_len()
+    }
+
+    fn channels(&self) -> u16 {
+        self.0.lock().unwrap().channels()
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.0.lock().unwrap().sample_rate()
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        self.0.lock().unwrap().total_duration()
+    }
+}
+
+struct VoiceState {
+    source:   Option<UniformSourceIterator<Shared, f32>>,
+    volume:   f32,
+    paused:   bool,
+    /// Samples rendered so far.
+    rendered: usize,
+}
+
+struct RecordingVoice {
+    name:     String,
+    recorder: Weak<Recorder>,
+    input:    Shared,
+    state:    Mutex<VoiceState>,
+}
+
+impl RecordingVoice {
+    fn push(&self, event: AudioEvent) {
+        if let Some(recorder) = self.recorder.upgrade() {
+            recorder.push(event);
+        }
+    }
+
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+}
+
+impl Voice for Arc<RecordingVoice> {
+    fn set_volume(&self, volume: f32) {
+        let mut state = self.state.lock().unwrap();
+
+        #[allow(clippy::float_cmp)]
+        if state.volume == volume {
+            return;
+        }
+
+        state.volume = volume;
+        self.push(AudioEvent::Volume {
+            name: self.name(),
+            volume,
+        });
+    }
+
+    fn stop(&self) {
+        if self.state.lock().unwrap().source.take().is_some() {
+            self.push(AudioEvent::Stop { name: self.name() });
+        }
+    }
+
+    fn pause(&self) {
+        self.state.lock().unwrap().paused = true;
+        self.push(AudioEvent::Pause { name: self.name() });
+    }
+
+    fn resume(&self) {
+        self.state.lock().unwrap().paused = false;
+        self.push(AudioEvent::Resume { name: self.name() });
+    }
+
+    fn is_paused(&self) -> bool {
+        self.state.lock().unwrap().paused
+    }
+
+    fn is_finished(&self) -> bool {
+        self.state.lock().unwrap().source.is_none()
+    }
+
+    fn seek(&self, position: Duration) -> Result<(), SeekError> {
+        let mut state = self.state.lock().unwrap();
+
+        if state.source.is_some() {
+            self.input.seek(position)?;
+        }
+
+        let frames: usize =
+            (position.as_secs_f64() * f64::from(RecordingBackend::SAMPLE_RATE)).lossy_convert();
+        state.rendered = frames * usize::from(RecordingBackend::CHANNELS);
+
+        drop(state);
+
+        self.push(AudioEvent::Seek {
+            name: self.name(),
+            position,
+        });
+
+        Ok(())
+    }
+
+    fn elapsed(&self) -> Duration {
+        let frames = self.state.lock().unwrap().rendered / usize::from(RecordingBackend::CHANNELS);
+        Duration::from_secs_f64(f64::from(frames.to_f32()) / f64::from(RecordingBackend::SAMPLE_RATE))
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod test {
+    use gm::flat::Point;
+    use manage::resource_loader::ResourceLoader;
+
+    use crate::{AudioEvent, Bus, Mixer, RecordingBackend, Sound, mixer::TEST_LOCK};
+
+    /// 16 bit mono PCM WAV.
+    pub(crate) fn wav(samples: &[i16]) -> Vec<u8> {
+        let data_len = u32::try_from(samples.len() * 2).unwrap();
+
+        let mut wav = vec![];
+        wav.extend_from_slice(b"RIFF");
+        wav.extend_from_slice(&(36 + data_len).to_le_bytes());
+        wav.extend_from_slice(b"WAVEfmt ");
+        wav.extend_from_slice(&16u32.to_le_bytes());
+        wav.extend_from_slice(&1u16.to_le_bytes());
+        wav.extend_from_slice(&1u16.to_le_bytes());
+        wav.extend_from_slice(&RecordingBackend::SAMPLE_RATE.to_le_bytes());
+        wav.extend_from_slice(&(RecordingBackend::SAMPLE_RATE * 2).to_le_bytes());
+        wav.extend_from_slice(&2u16.to_le_bytes());
+        wav.extend_from_slice(&16u16.to_le_bytes());
+        wav.extend_from_slice(b"data");
+        wav.extend_from_slice(&data_len.to_le_bytes());
+
+        for sample in samples {
+            wav.extend_from_slice(&sample.to_le_bytes());
+        }
+
+        wav
+    }
+
+    fn assert_all(samples: &[f32], expected: f32) {
+        assert!(
+            samples.iter().all(|sample| (sample - expected).abs() < 0.001),
+            "Expected all samples to be {expected}. Got: {:?}",
+            &samples[..4]
+        );
+    }
+
+    #[test]
+    fn recording() {
+        let _lock = TEST_LOCK.lock().unwrap();
+
+        let backend = RecordingBackend::install();
+        Mixer::set_volume(Bus::UI, 0.5);
+
+        let click = Sound::load_data(&wav(&[16384; 4410]), "click.wav");
+        let handle = click.play_on(Bus::UI);
+
+        assert_eq!(backend.play_count("click.wav"), 1);
+        assert_eq!(backend.events(), [AudioEvent::Play {
+            name:   "click.wav".into(),
+            bus:    Bus::UI,
+            volume: 0.5,
+        }]);
+
+        let samples = backend.render(100);
+        assert_eq!(samples.len(), 200);
+        assert_all(&samples, 0.25);
+
+        handle.set_volume(0.5);
+        assert_all(&backend.render(100), 0.125);
+
+        handle.stop();
+        assert!(handle.is_finished());
+        assert_all(&backend.render(100), 0.0);
+        assert_eq!(backend.active(), 0);
+
+        assert_eq!(backend.take_events()[1..], [
+            AudioEvent::Volume {
+                name:   "click.wav".into(),
+                volume: 0.25,
+            },
+            AudioEvent::Stop {
+                name: "click.wav".into(),
+            }
+        ]);
+
+        let short = Sound::load_data(&wav(&[16384; 10]), "short.wav");
+        let handle = short.play();
+        assert_eq!(backend.render(100)[..20].iter().filter(|s| **s > 0.0).count(), 20);
+        assert!(handle.is_finished());
+
+        Mixer::update();
+        assert_eq!(Mixer::playing(), 0);
+
+        Mixer::set_volume(Bus::UI, 1.0);
+    }
+
+    #[test]
+    fn positional() {
+        let _lock = TEST_LOCK.lock().unwrap();
+
+        let backend = RecordingBackend::install();
+        Mixer::set_listener((0, 0).into());
+
+        let step = Sound::load_data(&wav(&[16384; 4410]), "step.wav");
+
+        step.play_at((0, 0));
+        let samples = backend.render(10);
+        assert_all(&samples, 0.5 * std::f32::consts::FRAC_1_SQRT_2);
+
+        Mixer::stop_all();
+
+        let handle = step.play_at((30, 0));
+        let samples = backend.render(10);
+        let (left, right) = (samples[0], samples[1]);
+        assert!(right > left);
+        assert!(right < 0.5);
+
+        handle.set_position(Point::new(1000.0, 0.0));
+        assert_all(&backend.render(10), 0.0);
+
+        Mixer::stop_all();
+    }
+}
@@ -0,0 +1,60 @@
+use std::time::Duration;
+
+use log::error;
+use rodio::{Sink, source::SeekError};
+
+use crate::{AudioBackend, Voice, backend::PlayRequest, output::output};
+
+/// Plays sounds on the default audio device.
+pub struct RodioBackend;
+
+impl AudioBackend for RodioBackend {
+    fn play(&self, request: PlayRequest) -> Option<Box<dyn Voice>> {
+        let sink = match Sink::try_new(output()?) {
+            Ok(sink) => sink,
+            Err(err) => {
+                error!("Failed to create audio sink: {err}");
+                return None;
+            }
+        };
+
+        sink.set_volume(request.volume);
+        sink.append(request.source);
+
+        Some(Box::new(sink))
+    }
+}
+
+impl Voice for Sink {
+    fn set_volume(&self, volume: f32) {
+        Sink::set_volume(self, volume);
+    }
+
+    fn stop(&self) {
+        Sink::stop(self);
+    }
+
+    fn pause(&self) {
+        Sink::pause(self);
+    }
+
+    fn resume(&self) {
+        self.play();
+    }
+
+    fn is_paused(&self) -> bool {
+        Sink::is_paused(self)
+    }
+
+    fn is_finished(&self) -> bool {
+        self.empty()
+    }
+
+    fn seek(&self, position: Duration) -> Result<(), SeekError> {
+        self.try_seek(position)
+    }
+
+    fn elapsed(&self) -> Duration {
+        self.get_pos()
+    }
+}
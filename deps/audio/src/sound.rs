@@ -2,32 +2,66 @@
 
 use std::{
     fmt::{Debug, Formatter},
-    fs::read,
-    io::Cursor,
     path::{Path, PathBuf},
 };
 
+use asset_pack::read;
+use gm::flat::Point;
 use log::error;
-use manage::resource_loader::ResourceLoader;
-use rodio::{Decoder, OutputStream, OutputStreamHandle, Sink};
+use manage::{data_manager::DataManager, resource_loader::ResourceLoader};
+use refs::Weak;
+
+use crate::{Bus, DecodedSound, Mixer, PlayOptions, SoundHandle};
 
 pub struct Sound {
-    path:          PathBuf,
-    _stream:       OutputStream,
-    stream_handle: OutputStreamHandle,
-    data:          Vec<u8>,
+    path:    PathBuf,
+    /// Decoded once on load. Playing instances share the samples.
+    decoded: DecodedSound,
 }
 
 impl Sound {
-    pub fn play(&mut self) {
-        let cursor = Cursor::new(self.data.clone());
-        let input = Decoder::new(cursor).unwrap();
+    pub fn new(decoded: DecodedSound, name: impl ToString) -> Self {
+        Self {
+            path: name.to_string().into(),
+            decoded,
+        }
+    }
+
+    /// Adds sound decoded on another thread.
+    pub fn from_decoded(decoded: DecodedSound, name: &str) -> Weak<Sound> {
+        Sound::add_with_name(name, || Self::new(decoded, name))
+    }
+
+    /// Plays on sfx bus.
+    pub fn play(&self) -> SoundHandle {
+        self.play_with(PlayOptions::new())
+    }
+
+    pub fn play_on(&self, bus: Bus) -> SoundHandle {
+        self.play_with(PlayOptions::new().bus(bus))
+    }
+
+    /// Plays positional sound. Position is in level coordinates.
+    pub fn play_at(&self, position: impl Into<Point>) -> SoundHandle {
+        self.play_with(PlayOptions::new().at(position.into()))
+    }
+
+    pub fn play_with(&self, options: PlayOptions) -> SoundHandle {
+        let source = self.decoded.source(options.looped);
+        Mixer::play(self.name(), source, options)
+    }
 
-        let sink = Sink::try_new(&self.stream_handle).unwrap();
+    /// File name of the sound.
+    pub fn name(&self) -> String {
+        self.path
+            .file_name()
+            .unwrap_or(self.path.as_os_str())
+            .to_string_lossy()
+            .into_owned()
+    }
 
-        sink.set_volume(0.1);
-        sink.append(input);
-        sink.detach();
+    pub fn decoded(&self) -> &DecodedSound {
+        &self.decoded
     }
 }
 
@@ -50,14 +84,14 @@ impl ResourceLoader for Sound {
     }
 
     fn load_data(data: &[u8], name: impl ToString) -> Self {
-        let (stream, stream_handle) = OutputStream::try_default().unwrap();
+        let name = name.to_string();
 
-        Self {
-            path: name.to_string().into(),
-            _stream: stream,
-            stream_handle,
-            data: data.into(),
-        }
+        let decoded = DecodedSound::decode(data).unwrap_or_else(|err| {
+            error!("Failed to decode sound: {name}. Error: {err} Returning default sound");
+            DecodedSound::decode(DEFAULT_SOUND_DATA).expect("Failed to decode default sound")
+        });
+
+        Self::new(decoded, name)
     }
 }
 
@@ -0,0 +1,109 @@
+use std::{
+    f32::consts::FRAC_PI_4,
+    sync::atomic::{AtomicU32, Ordering},
+};
+
+use gm::flat::Point;
+
+/// How positional sounds are attenuated with distance to the listener.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct SpatialRange {
+    /// Sounds closer than this play at full volume.
+    pub min: f32,
+    /// Sounds further than this are silent.
+    pub max: f32,
+}
+
+impl SpatialRange {
+    pub const DEFAULT: Self = Self { min: 5.0, max: 60.0 };
+
+    pub(crate) fn attenuation(self, distance: f32) -> f32 {
+        if distance <= self.min {
+            return 1.0;
+        }
+
+        if distance >= self.max || self.max <= self.min {
+            return 0.0;
+        }
+
+        1.0 - (distance - self.min) / (self.max - self.min)
+    }
+
+    /// Equal power left and right gains for emitter relative to listener.
+    pub(crate) fn pan(self, listener: Point, emitter: Point) -> (f32, f32) {
+        let pan = ((emitter.x - listener.x) / self.max).clamp(-1.0, 1.0);
+        let angle = (pan + 1.0) * FRAC_PI_4;
+        (angle.cos(), angle.sin())
+    }
+
+    /// Volume, left and right gains.
+    pub(crate) fn apply(self, listener: Point, emitter: Point) -> (f32, f32, f32) {
+        let distance = (emitter - listener).length();
+        let (left, right) = self.pan(listener, emitter);
+        (self.attenuation(distance), left, right)
+    }
+}
+
+impl Default for SpatialRange {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+/// Left and right channel gains shared between the mixer and a playing
+/// source.
+#[derive(Debug)]
+pub(crate) struct PanGains {
+    left:  AtomicU32,
+    right: AtomicU32,
+}
+
+impl PanGains {
+    pub(crate) fn set(&self, left: f32, right: f32) {
+        self.left.store(left.to_bits(), Ordering::Relaxed);
+        self.right.store(right.to_bits(), Ordering::Relaxed);
+    }
+
+    pub(crate) fn left(&self) -> f32 {
+        f32::from_bits(self.left.load(Ordering::Relaxed))
+    }
+
+    pub(crate) fn right(&self) -> f32 {
+        f32::from_bits(self.right.load(Ordering::Relaxed))
+    }
+}
+
+impl Default for PanGains {
+    fn default() -> Self {
+        Self {
+            left:  AtomicU32::new(1.0f32.to_bits()),
+            right: AtomicU32::new(1.0f32.to_bits()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::SpatialRange;
+
+    #[test]
+    fn spatial() {
+        let range = SpatialRange { min: 10.0, max: 30.0 };
+
+        assert_eq!(range.attenuation(0.0), 1.0);
+        assert_eq!(range.attenuation(10.0), 1.0);
+        assert_eq!(range.attenuation(20.0), 0.5);
+        assert_eq!(range.attenuation(30.0), 0.0);
+        assert_eq!(range.attenuation(100.0), 0.0);
+
+        let (left, right) = range.pan((0, 0).into(), (0, 15).into());
+        assert!((left - right).abs() < 0.0001);
+
+        let (left, right) = range.pan((0, 0).into(), (-30, 0).into());
+        assert!((left - 1.0).abs() < 0.0001);
+        assert!(right.abs() < 0.0001);
+
+        let (left, right) = range.pan((10, 0).into(), (25, 0).into());
+        assert!(right > left);
+    }
+}
@@ -1,107 +1,91 @@
 use std::{
     future::Future,
+    panic::Location,
     sync::{Arc, Mutex},
-    thread::sleep,
     time::Duration,
 };
 
 use gm::ToF32;
-use log::warn;
 use refs::is_main_thread;
-use tokio::{
-    spawn,
-    sync::oneshot::{Sender, channel},
-};
-
-type Callback = Box<dyn FnOnce() + Send>;
-type Callbacks = Mutex<Vec<Callback>>;
-type SignalledCallbacks = Mutex<Vec<(Sender<()>, Callback)>>;
+use tokio::{spawn, sync::oneshot::channel, time::sleep};
 
-static CALLBACKS: Callbacks = Callbacks::new(vec![]);
-static SIGNALLED: SignalledCallbacks = SignalledCallbacks::new(vec![]);
+use crate::{
+    Delay, Lane, Scheduler, TaskHandle, Timer,
+    main_wait::waiting_for_main,
+    queue::{process, push},
+};
 
-pub async fn from_main<T, A>(action: A) -> T
+#[track_caller]
+pub fn from_main<T, A>(action: A) -> impl Future<Output = T>
 where
     A: FnOnce() -> T + Send + 'static,
     T: Send + 'static, {
-    assert!(
-        !is_main_thread(),
-        "This is already main thread. Just call it without `from_main`"
-    );
-
-    let result = Arc::<Mutex<Option<T>>>::default();
-
-    let (sender, receiver) = channel::<()>();
-
-    let capture = result.clone();
-    SIGNALLED.lock().unwrap().push((
-        sender,
-        Box::new(move || {
-            let mut res = capture.lock().unwrap();
-            *res = action().into();
-        }),
-    ));
-
-    receiver.await.expect("Failed to receive result in on_main");
-
-    let res = result.lock().unwrap().take().unwrap();
-    res
+    waiting_for_main(Location::caller(), async move {
+        assert!(
+            !is_main_thread(),
+            "This is already main thread. Just call it without `from_main`"
+        );
+
+        let result = Arc::<Mutex<Option<T>>>::default();
+
+        let (sender, receiver) = channel::<()>();
+
+        let capture = result.clone();
+        push(
+            Lane::UI,
+            Box::new(move || {
+                *capture.lock().unwrap() = action().into();
+                _ = sender.send(());
+            }),
+        );
+
+        receiver.await.expect("Failed to receive result in on_main");
+
+        let res = result.lock().unwrap().take().unwrap();
+        res
+    })
 }
 
-pub async fn wait_for_next_frame() {
-    from_main(|| {}).await;
+#[track_caller]
+pub fn wait_for_next_frame() -> impl Future<Output = ()> {
+    from_main(|| {})
 }
 
 pub fn on_main(action: impl FnOnce() + Send + 'static) {
-    if is_main_thread() {
-        action();
-    } else {
-        CALLBACKS.lock().unwrap().push(Box::new(action));
-    }
+    on_main_in(Lane::UI, action);
 }
 
-pub fn on_main_sync(action: impl FnOnce() + Send + 'static) {
+pub fn on_main_in(lane: Lane, action: impl FnOnce() + Send + 'static) {
     if is_main_thread() {
         action();
     } else {
-        let (sender, mut receiver) = channel::<()>();
-        SIGNALLED.lock().unwrap().push((sender, Box::new(action)));
-        while receiver.try_recv().is_err() {}
+        push(lane, Box::new(action));
     }
 }
 
-pub fn after(delay: impl ToF32, action: impl FnOnce() + Send + 'static) {
-    spawn(async move {
-        sleep(Duration::from_secs_f32(delay.to_f32()));
-        CALLBACKS.lock().unwrap().push(Box::new(action));
-    });
+pub fn after(delay: impl ToF32, action: impl FnOnce() + Send + 'static) -> TaskHandle {
+    Timer::after(Delay::secs(delay)).start_once(action)
 }
 
 pub fn async_after(delay: impl ToF32, action: impl Future + Send + 'static) {
     spawn(async move {
-        sleep(Duration::from_secs_f32(delay.to_f32()));
+        sleep(Duration::from_secs_f32(delay.to_f32())).await;
         action.await;
     });
 }
 
-pub fn invoke_dispatched() {
-    let Ok(mut callback) = CALLBACKS.try_lock() else {
-        warn!("Failed to lock CALLBACKS");
-        return;
-    };
-
-    for action in callback.drain(..) {
-        action();
-    }
-    drop(callback);
-
-    let Ok(mut signalled) = SIGNALLED.try_lock() else {
-        warn!("Failed to lock SIGNALLED");
-        return;
-    };
+/// Waits using scheduler clock. Unlike `tokio::time::sleep` respects manual
+/// clock and pause.
+#[track_caller]
+pub fn wait(timer: Timer) -> impl Future<Output = ()> {
+    waiting_for_main(Location::caller(), async move {
+        let (sender, receiver) = channel::<()>();
+        timer.start_once(move || _ = sender.send(()));
+        receiver.await.expect("Failed to receive scheduler signal");
+    })
+}
 
-    for (signal, action) in signalled.drain(..) {
-        action();
-        signal.send(()).unwrap();
-    }
+pub fn invoke_dispatched() {
+    Scheduler::tick();
+    process();
 }
@@ -4,5 +4,13 @@
 extern crate core;
 
 mod dispatch;
+mod main_wait;
+mod queue;
+mod scheduler;
 
-pub use crate::dispatch::*;
+pub use crate::{
+    dispatch::*,
+    main_wait::{BlockedOn, DispatchError, blocked_on, on_main_sync, on_main_sync_timeout},
+    queue::{DispatchStats, Lane, dispatch_stats, set_frame_budget},
+    scheduler::{Delay, Scheduler, TaskHandle, Timer},
+};
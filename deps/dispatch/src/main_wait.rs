@@ -0,0 +1,424 @@
+use std::{
+    collections::HashMap,
+    error::Error,
+    fmt::{Display, Formatter},
+    future::Future,
+    panic::Location,
+    sync::{
+        Mutex,
+        mpsc::{RecvTimeoutError, sync_channel},
+    },
+    thread::{self, Thread, ThreadId},
+    time::Duration,
+};
+
+use log::error;
+use refs::is_main_thread;
+use tokio::task;
+use web_time::Instant;
+
+use crate::{
+    Lane,
+    queue::{frames_processed, push},
+};
+
+/// How often waiting thread checks if main thread is still processing frames.
+/// See [`report_stall`].
+const STALL_CHECK_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Wait for graph. Every blocked thread points to the thread it waits for.
+/// Only tracked in debug builds.
+static WAITS: Mutex<Option<HashMap<Node, Wait>>> = Mutex::new(None);
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum DispatchError {
+    Timeout(Duration),
+    /// Callback was dropped without being called.
+    Dropped,
+}
+
+impl Display for DispatchError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Timeout(timeout) => write!(f, "Main thread didn't respond in {timeout:?}"),
+            Self::Dropped => write!(f, "Main thread callback was dropped"),
+        }
+    }
+}
+
+impl Error for DispatchError {}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+enum Node {
+    Main,
+    Thread(ThreadId),
+}
+
+impl Node {
+    fn current() -> Self {
+        if is_main_thread() {
+            Self::Main
+        } else {
+            Self::Thread(thread::current().id())
+        }
+    }
+}
+
+struct Wait {
+    thread:   String,
+    on:       Node,
+    location: &'static Location<'static>,
+    since:    Instant,
+}
+
+impl Display for Wait {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let on = match self.on {
+            Node::Main => "main thread",
+            Node::Thread(_) => "another thread",
+        };
+        write!(
+            f,
+            "'{}' waits for {on} at {} for {:?}",
+            self.thread,
+            self.location,
+            self.since.elapsed()
+        )
+    }
+}
+
+/// Blocks current thread until action is executed on main thread.
+#[track_caller]
+pub fn on_main_sync<T: Send + 'static>(action: impl FnOnce() -> T + Send + 'static) -> T {
+    match wait_for_main(action, None, Location::caller()) {
+        Ok(result) => result,
+        Err(err) => panic!("on_main_sync failed: {err}"),
+    }
+}
+
+/// Same as `on_main_sync` but gives up after `timeout`. The action may still
+/// run on main thread later. Its result is discarded in this case.
+#[track_caller]
+pub fn on_main_sync_timeout<T: Send + 'static>(
+    timeout: Duration,
+    action: impl FnOnce() -> T + Send + 'static,
+) -> Result<T, DispatchError> {
+    wait_for_main(action, Some(timeout), Location::caller())
+}
+
+/// Marks current thread as blocked on `thread` until the returned guard is
+/// dropped. Wrap joins and channel reads on main thread with it so debug
+/// builds can report a cycle with threads waiting in `on_main_sync`,
+/// `from_main` or scheduler `wait`.
+#[track_caller]
+#[must_use = "Thread is only marked as blocked while the guard is alive"]
+pub fn blocked_on(thread: &Thread) -> BlockedOn {
+    BlockedOn {
+        _guard: WaitGuard::register(Node::Thread(thread.id()), Location::caller()),
+    }
+}
+
+pub struct BlockedOn {
+    _guard: Option<WaitGuard>,
+}
+
+/// Marks current thread as waiting for main thread until `future` completes
+/// if the thread is blocked on it, like in `block_on`. Spawned tokio tasks
+/// don't block their worker thread so they are not tracked.
+pub(crate) async fn waiting_for_main<T>(
+    location: &'static Location<'static>,
+    future: impl Future<Output = T>,
+) -> T {
+    let _waiter = task::try_id()
+        .is_none()
+        .then(|| WaitGuard::register(Node::Main, location))
+        .flatten();
+    future.await
+}
+
+fn wait_for_main<T: Send + 'static>(
+    action: impl FnOnce() -> T + Send + 'static,
+    timeout: Option<Duration>,
+    location: &'static Location<'static>,
+) -> Result<T, DispatchError> {
+    if is_main_thread() {
+        return Ok(action());
+    }
+
+    let (sender, receiver) = sync_channel::<T>(1);
+
+    push(
+        Lane::UI,
+        Box::new(move || {
+            _ = sender.send(action());
+        }),
+    );
+
+    let _waiter = WaitGuard::register(Node::Main, location);
+
+    let start = Instant::now();
+    let mut last_frame = frames_processed();
+    let mut reported = false;
+
+    loop {
+        let check_in = match timeout {
+            Some(timeout) => {
+                let Some(left) = timeout.checked_sub(start.elapsed()) else {
+                    return Err(DispatchError::Timeout(timeout));
+                };
+                left.min(STALL_CHECK_INTERVAL)
+            }
+            None => STALL_CHECK_INTERVAL,
+        };
+
+        match receiver.recv_timeout(check_in) {
+            Ok(result) => return Ok(result),
+            Err(RecvTimeoutError::Disconnected) => return Err(DispatchError::Dropped),
+            Err(RecvTimeoutError::Timeout) => (),
+        }
+
+        let frame = frames_processed();
+
+        if cfg!(debug_assertions) && frame == last_frame && !reported {
+            report_stall(location, start.elapsed());
+            reported = true;
+        }
+
+        last_frame = frame;
+    }
+}
+
+/// Debug only. Main thread didn't process a single frame for
+/// [`STALL_CHECK_INTERVAL`] while this thread waited for it. Cycles through
+/// [`blocked_on`] are reported right away by [`report_cycle`], this catches
+/// main thread blocked on something that is not tracked.
+fn report_stall(location: &'static Location<'static>, waiting: Duration) {
+    let current = Node::current();
+
+    let waits = WAITS.lock().unwrap();
+
+    let others: Vec<String> = waits
+        .iter()
+        .flatten()
+        .filter(|(node, _)| **node != current)
+        .map(|(_, wait)| wait.to_string())
+        .collect();
+
+    error!(
+        "Main thread stalled: thread '{}' is waiting for main thread at {location} for {waiting:?} but main \
+         thread is not processing frames. It is either busy with a long call or blocked on a lock or a \
+         channel owned by a waiting thread. Other waiting threads: {others:?}",
+        thread::current().name().unwrap_or("unnamed"),
+    );
+}
+
+/// Follows the wait for graph from `from`. Returns waits forming the cycle
+/// starting with the one of `from` if the path leads back to it.
+fn find_cycle(waits: &HashMap<Node, Wait>, from: Node) -> Option<Vec<&Wait>> {
+    let mut cycle = vec![];
+    let mut node = from;
+
+    loop {
+        let wait = waits.get(&node)?;
+
+        // Path ended in a cycle that doesn't include `from`.
+        if cycle.len() > waits.len() {
+            return None;
+        }
+
+        cycle.push(wait);
+        node = wait.on;
+
+        if node == from {
+            return Some(cycle);
+        }
+    }
+}
+
+fn report_cycle(cycle: &[&Wait]) {
+    let chain: Vec<String> = cycle.iter().map(ToString::to_string).collect();
+
+    error!(
+        "Main thread wait cycle. None of these threads can proceed: {}",
+        chain.join(" -> ")
+    );
+}
+
+struct WaitGuard {
+    node: Node,
+}
+
+impl WaitGuard {
+    fn register(on: Node, location: &'static Location<'static>) -> Option<Self> {
+        if !cfg!(debug_assertions) {
+            return None;
+        }
+
+        let node = Node::current();
+
+        let mut waits = WAITS.lock().unwrap();
+        let waits = waits.get_or_insert_default();
+
+        waits.insert(node, Wait {
+            thread: thread::current().name().unwrap_or("unnamed").to_string(),
+            on,
+            location,
+            since: Instant::now(),
+        });
+
+        if let Some(cycle) = find_cycle(waits, node) {
+            report_cycle(&cycle);
+        }
+
+        Some(Self { node })
+    }
+}
+
+impl Drop for WaitGuard {
+    fn drop(&mut self) {
+        if let Some(waits) = WAITS.lock().unwrap().as_mut() {
+            waits.remove(&self.node);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{
+        collections::HashMap,
+        panic::Location,
+        thread::{sleep, spawn},
+        time::Duration,
+    };
+
+    use refs::set_current_thread_as_main;
+    use tokio::runtime::Builder;
+    use web_time::Instant;
+
+    use crate::{
+        DispatchError, blocked_on, from_main,
+        main_wait::{Node, WAITS, Wait, find_cycle},
+        on_main_sync_timeout,
+        queue::{TEST_LOCK, process},
+    };
+
+    fn wait(thread: &str, on: Node) -> Wait {
+        Wait {
+            thread: thread.to_string(),
+            on,
+            location: Location::caller(),
+            since: Instant::now(),
+        }
+    }
+
+    #[test]
+    fn timeout() {
+        let _lock = TEST_LOCK.lock().unwrap();
+
+        // Nothing processes the queue here so the call has to time out.
+        let result = spawn(|| on_main_sync_timeout(Duration::from_millis(50), || 5)).join().unwrap();
+
+        assert_eq!(result, Err(DispatchError::Timeout(Duration::from_millis(50))));
+    }
+
+    #[test]
+    fn cycle() {
+        let loader = Node::Thread(spawn(|| {}).thread().id());
+        let decoder = Node::Thread(spawn(|| {}).thread().id());
+
+        let mut waits = HashMap::new();
+        waits.insert(loader, wait("loader", Node::Main));
+
+        // Loader waits for main but main is free.
+        assert!(find_cycle(&waits, loader).is_none());
+
+        waits.insert(Node::Main, wait("main", decoder));
+
+        // Main waits for decoder which is free.
+        assert!(find_cycle(&waits, Node::Main).is_none());
+
+        waits.insert(decoder, wait("decoder", loader));
+
+        let cycle = find_cycle(&waits, Node::Main).unwrap();
+        let threads: Vec<&str> = cycle.iter().map(|wait| wait.thread.as_str()).collect();
+        assert_eq!(threads, ["main", "decoder", "loader"]);
+
+        assert_eq!(find_cycle(&waits, loader).unwrap().len(), 3);
+    }
+
+    #[test]
+    fn cycle_not_including_start() {
+        let a = Node::Thread(spawn(|| {}).thread().id());
+        let b = Node::Thread(spawn(|| {}).thread().id());
+        let c = Node::Thread(spawn(|| {}).thread().id());
+
+        let mut waits = HashMap::new();
+        waits.insert(a, wait("a", b));
+        waits.insert(b, wait("b", c));
+        waits.insert(c, wait("c", b));
+
+        assert!(find_cycle(&waits, a).is_none());
+        assert!(find_cycle(&waits, b).is_some());
+    }
+
+    #[test]
+    fn blocked_on_guard() {
+        let _lock = TEST_LOCK.lock().unwrap();
+
+        let other = spawn(|| {});
+        let node = Node::current();
+
+        let guard = blocked_on(other.thread());
+
+        let on = WAITS.lock().unwrap().as_ref().unwrap().get(&node).map(|wait| wait.on);
+        assert_eq!(on, Some(Node::Thread(other.thread().id())));
+
+        drop(guard);
+
+        assert!(!WAITS.lock().unwrap().as_ref().unwrap().contains_key(&node));
+
+        other.join().unwrap();
+    }
+
+    #[test]
+    fn block_on_from_main() {
+        let _lock = TEST_LOCK.lock().unwrap();
+        set_current_thread_as_main();
+
+        let waiter = spawn(|| Builder::new_current_thread().build().unwrap().block_on(from_main(|| 5)));
+        let node = Node::Thread(waiter.thread().id());
+
+        let start = Instant::now();
+        loop {
+            let on = WAITS
+                .lock()
+                .unwrap()
+                .as_ref()
+                .and_then(|waits| waits.get(&node).map(|wait| wait.on));
+            if on == Some(Node::Main) {
+                break;
+            }
+            assert!(
+                start.elapsed() < Duration::from_secs(5),
+                "Wait was not registered"
+            );
+            sleep(Duration::from_millis(1));
+        }
+
+        {
+            let _blocked = blocked_on(waiter.thread());
+            let waits = WAITS.lock().unwrap();
+            let cycle = find_cycle(waits.as_ref().unwrap(), Node::Main).unwrap();
+            assert_eq!(cycle.len(), 2);
+        }
+
+        // Main thread is free again and lets the waiter finish
+        while !waiter.is_finished() {
+            process();
+            sleep(Duration::from_millis(1));
+        }
+
+        assert_eq!(waiter.join().unwrap(), 5);
+        assert!(!WAITS.lock().unwrap().as_ref().unwrap().contains_key(&node));
+    }
+}
@@ -0,0 +1,202 @@
+use std::{
+    collections::VecDeque,
+    sync::{
+        Mutex,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::Duration,
+};
+
+use web_time::Instant;
+
+pub(crate) type Callback = Box<dyn FnOnce() + Send>;
+
+static QUEUE: Mutex<Queue> = Mutex::new(Queue::new());
+static BUDGET: Mutex<Option<Duration>> = Mutex::new(None);
+static STATS: Mutex<DispatchStats> = Mutex::new(DispatchStats::new());
+static FRAMES_PROCESSED: AtomicU64 = AtomicU64::new(0);
+
+/// Queue is global so tests touching it can't run in parallel.
+#[cfg(test)]
+pub(crate) static TEST_LOCK: Mutex<()> = Mutex::new(());
+
+/// Priority of main thread callbacks. Lanes are processed in declaration
+/// order.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Lane {
+    /// Always processed in full, even when frame budget is exceeded.
+    Input,
+    UI,
+    Background,
+}
+
+impl Lane {
+    pub const ALL: [Self; 3] = [Self::Input, Self::UI, Self::Background];
+
+    const fn index(self) -> usize {
+        self as usize
+    }
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct DispatchStats {
+    /// Callbacks left in each lane after last frame. Indexed by `Lane`.
+    pub queued:   [usize; 3],
+    /// Callbacks executed during last frame.
+    pub executed: usize,
+    /// Time spent executing callbacks during last frame.
+    pub time:     Duration,
+}
+
+impl DispatchStats {
+    const fn new() -> Self {
+        Self {
+            queued:   [0; 3],
+            executed: 0,
+            time:     Duration::ZERO,
+        }
+    }
+
+    pub fn queued_in(&self, lane: Lane) -> usize {
+        self.queued[lane.index()]
+    }
+
+    pub fn total_queued(&self) -> usize {
+        self.queued.iter().sum()
+    }
+}
+
+struct Queue {
+    lanes: [VecDeque<Callback>; 3],
+}
+
+impl Queue {
+    const fn new() -> Self {
+        Self {
+            lanes: [VecDeque::new(), VecDeque::new(), VecDeque::new()],
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.lanes.iter().map(VecDeque::len).sum()
+    }
+
+    fn pop(&mut self, input_only: bool) -> Option<Callback> {
+        if input_only {
+            return self.lanes[Lane::Input.index()].pop_front();
+        }
+        self.lanes.iter_mut().find_map(VecDeque::pop_front)
+    }
+}
+
+pub(crate) fn push(lane: Lane, callback: Callback) {
+    QUEUE.lock().unwrap().lanes[lane.index()].push_back(callback);
+}
+
+/// Limits time spent on callbacks per frame. Callbacks which didn't fit are
+/// carried over to the next frame. `None` - no limit.
+pub fn set_frame_budget(budget: Option<Duration>) {
+    *BUDGET.lock().unwrap() = budget;
+}
+
+/// Increases every time main thread processes the queue.
+pub(crate) fn frames_processed() -> u64 {
+    FRAMES_PROCESSED.load(Ordering::Relaxed)
+}
+
+pub fn dispatch_stats() -> DispatchStats {
+    *STATS.lock().unwrap()
+}
+
+/// Runs callbacks queued before this frame in lane priority order until frame
+/// budget is spent. Queue lock is not held while callbacks run.
+pub(crate) fn process() {
+    FRAMES_PROCESSED.fetch_add(1, Ordering::Relaxed);
+
+    let budget = *BUDGET.lock().unwrap();
+    let start = Instant::now();
+
+    // Callbacks added while processing wait for next frame so busy background
+    // threads can't stall the frame.
+    let mut limit = QUEUE.lock().unwrap().len();
+    let mut executed = 0;
+
+    while limit > 0 {
+        let over_budget = budget.is_some_and(|budget| start.elapsed() >= budget);
+
+        let Some(callback) = QUEUE.lock().unwrap().pop(over_budget) else {
+            break;
+        };
+
+        callback();
+
+        limit -= 1;
+        executed += 1;
+    }
+
+    let queue = QUEUE.lock().unwrap();
+
+    *STATS.lock().unwrap() = DispatchStats {
+        queued: Lane::ALL.map(|lane| queue.lanes[lane.index()].len()),
+        executed,
+        time: start.elapsed(),
+    };
+}
+
+#[cfg(test)]
+mod test {
+    use std::{
+        sync::{Arc, Mutex},
+        thread::sleep,
+        time::Duration,
+    };
+
+    use crate::{
+        Lane, dispatch_stats,
+        queue::{TEST_LOCK, process, push},
+        set_frame_budget,
+    };
+
+    #[test]
+    fn lanes_and_budget() {
+        let _lock = TEST_LOCK.lock().unwrap();
+        process();
+
+        let order = Arc::new(Mutex::new(vec![]));
+
+        for lane in [Lane::Background, Lane::UI, Lane::Input] {
+            let order = order.clone();
+            push(lane, Box::new(move || order.lock().unwrap().push(lane)));
+        }
+
+        process();
+
+        assert_eq!(*order.lock().unwrap(), vec![
+            Lane::Input,
+            Lane::UI,
+            Lane::Background
+        ]);
+        assert_eq!(dispatch_stats().executed, 3);
+
+        set_frame_budget(Some(Duration::from_millis(5)));
+
+        for _ in 0..4 {
+            push(Lane::Background, Box::new(|| sleep(Duration::from_millis(10))));
+        }
+        push(Lane::Input, Box::new(|| {}));
+        push(Lane::Input, Box::new(|| {}));
+
+        process();
+
+        let stats = dispatch_stats();
+        assert_eq!(stats.executed, 3);
+        assert_eq!(stats.queued_in(Lane::Background), 3);
+        assert_eq!(stats.queued_in(Lane::Input), 0);
+
+        set_frame_budget(None);
+        process();
+
+        assert_eq!(dispatch_stats().executed, 3);
+        assert_eq!(dispatch_stats().total_queued(), 0);
+    }
+}
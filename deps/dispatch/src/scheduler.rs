@@ -0,0 +1,342 @@
+use std::{
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::Duration,
+};
+
+use gm::ToF32;
+use web_time::Instant;
+
+static SCHEDULER: Mutex<Scheduler> = Mutex::new(Scheduler::new());
+
+type Action = Box<dyn FnMut() + Send>;
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Delay {
+    Frames(u64),
+    Time(Duration),
+}
+
+impl Delay {
+    pub fn secs(secs: impl ToF32) -> Self {
+        Self::Time(Duration::from_secs_f32(secs.to_f32()))
+    }
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+struct Clock {
+    frame: u64,
+    time:  Duration,
+}
+
+impl Clock {
+    fn due(&self, delay: Delay) -> Due {
+        match delay {
+            Delay::Frames(frames) => Due::Frame(self.frame + frames.max(1)),
+            Delay::Time(time) => Due::Time(self.time + time),
+        }
+    }
+
+    fn reached(&self, due: Due) -> bool {
+        match due {
+            Due::Frame(frame) => self.frame >= frame,
+            Due::Time(time) => self.time >= time,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+enum Due {
+    Frame(u64),
+    Time(Duration),
+}
+
+struct Task {
+    due:       Due,
+    interval:  Option<Delay>,
+    pausable:  bool,
+    cancelled: Arc<AtomicBool>,
+    action:    Action,
+}
+
+/// Runs delayed and repeating actions on main thread. Driven by
+/// `invoke_dispatched` so actions are executed between frames.
+///
+/// Pausable tasks use game clock which stops while scheduler is paused.
+/// In manual clock mode time only moves with `Scheduler::advance` so tests can
+/// control it without real sleeps.
+pub struct Scheduler {
+    real:         Clock,
+    game:         Clock,
+    paused:       bool,
+    manual_clock: bool,
+    last_tick:    Option<Instant>,
+    tasks:        Vec<Task>,
+}
+
+impl Scheduler {
+    const fn new() -> Self {
+        Self {
+            real:         Clock {
+                frame: 0,
+                time:  Duration::ZERO,
+            },
+            game:         Clock {
+                frame: 0,
+                time:  Duration::ZERO,
+            },
+            paused:       false,
+            manual_clock: false,
+            last_tick:    None,
+            tasks:        vec![],
+        }
+    }
+
+    pub fn pause() {
+        SCHEDULER.lock().unwrap().paused = true;
+    }
+
+    pub fn resume() {
+        SCHEDULER.lock().unwrap().paused = false;
+    }
+
+    pub fn is_paused() -> bool {
+        SCHEDULER.lock().unwrap().paused
+    }
+
+    pub fn set_manual_clock(manual: bool) {
+        let mut scheduler = SCHEDULER.lock().unwrap();
+        scheduler.manual_clock = manual;
+        scheduler.last_tick = None;
+    }
+
+    /// Moves time forward in manual clock mode. Due tasks run on next frame.
+    pub fn advance(duration: Duration) {
+        let mut scheduler = SCHEDULER.lock().unwrap();
+        assert!(scheduler.manual_clock, "Scheduler::advance requires manual clock");
+        scheduler.advance_time(duration);
+    }
+
+    /// Number of tasks waiting to run.
+    pub fn pending() -> usize {
+        SCHEDULER.lock().unwrap().tasks.len()
+    }
+
+    fn advance_time(&mut self, duration: Duration) {
+        self.real.time += duration;
+        if !self.paused {
+            self.game.time += duration;
+        }
+    }
+
+    fn clock(&self, pausable: bool) -> &Clock {
+        if pausable { &self.game } else { &self.real }
+    }
+
+    fn add(task: Task) {
+        SCHEDULER.lock().unwrap().tasks.push(task);
+    }
+
+    /// Advances the clocks by one frame and runs due tasks.
+    pub(crate) fn tick() {
+        let due = {
+            let mut scheduler = SCHEDULER.lock().unwrap();
+
+            if !scheduler.manual_clock {
+                let now = Instant::now();
+                if let Some(last) = scheduler.last_tick {
+                    scheduler.advance_time(now - last);
+                }
+                scheduler.last_tick = Some(now);
+            }
+
+            scheduler.real.frame += 1;
+            if !scheduler.paused {
+                scheduler.game.frame += 1;
+            }
+
+            let (real, game) = (scheduler.real, scheduler.game);
+
+            let (due, waiting): (Vec<_>, Vec<_>) = scheduler
+                .tasks
+                .drain(..)
+                .filter(|task| !task.cancelled.load(Ordering::Relaxed))
+                .partition(|task| {
+                    let clock = if task.pausable { game } else { real };
+                    clock.reached(task.due)
+                });
+
+            scheduler.tasks = waiting;
+
+            due
+        };
+
+        // Lock is released so actions can schedule or cancel other tasks.
+        for mut task in due {
+            if task.cancelled.load(Ordering::Relaxed) {
+                continue;
+            }
+
+            (task.action)();
+
+            let Some(interval) = task.interval else {
+                continue;
+            };
+
+            let mut scheduler = SCHEDULER.lock().unwrap();
+            task.due = scheduler.clock(task.pausable).due(interval);
+            scheduler.tasks.push(task);
+        }
+    }
+}
+
+/// Cancels the task. Dropping the handle doesn't cancel it.
+#[derive(Clone, Debug, Default)]
+pub struct TaskHandle {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl TaskHandle {
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+}
+
+/// Builder for scheduled tasks.
+#[derive(Copy, Clone, Debug)]
+#[must_use]
+pub struct Timer {
+    delay:    Delay,
+    repeat:   bool,
+    pausable: bool,
+}
+
+impl Timer {
+    pub fn after(delay: Delay) -> Self {
+        Self {
+            delay,
+            repeat: false,
+            pausable: false,
+        }
+    }
+
+    pub fn every(interval: Delay) -> Self {
+        Self {
+            delay:    interval,
+            repeat:   true,
+            pausable: false,
+        }
+    }
+
+    /// Task doesn't advance while scheduler is paused.
+    pub fn pausable(mut self) -> Self {
+        self.pausable = true;
+        self
+    }
+
+    pub fn start(self, action: impl FnMut() + Send + 'static) -> TaskHandle {
+        let handle = TaskHandle::default();
+
+        let due = SCHEDULER.lock().unwrap().clock(self.pausable).due(self.delay);
+
+        Scheduler::add(Task {
+            due,
+            interval: self.repeat.then_some(self.delay),
+            pausable: self.pausable,
+            cancelled: handle.cancelled.clone(),
+            action: Box::new(action),
+        });
+
+        handle
+    }
+
+    pub fn start_once(self, action: impl FnOnce() + Send + 'static) -> TaskHandle {
+        assert!(!self.repeat, "Repeating timer requires FnMut action");
+        let mut action = Some(action);
+        self.start(move || {
+            if let Some(action) = action.take() {
+                action();
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{
+        sync::{
+            Arc,
+            atomic::{AtomicU32, Ordering},
+        },
+        time::Duration,
+    };
+
+    use crate::{Delay, Scheduler, Timer};
+
+    fn counter() -> (Arc<AtomicU32>, impl FnMut() + Send + 'static) {
+        let counter = Arc::new(AtomicU32::new(0));
+        let capture = counter.clone();
+        (counter, move || {
+            capture.fetch_add(1, Ordering::Relaxed);
+        })
+    }
+
+    // Scheduler is global so all checks live in a single test.
+    #[test]
+    fn scheduler() {
+        Scheduler::set_manual_clock(true);
+
+        let (frames, action) = counter();
+        Timer::after(Delay::Frames(2)).start(action);
+
+        Scheduler::tick();
+        assert_eq!(frames.load(Ordering::Relaxed), 0);
+        Scheduler::tick();
+        assert_eq!(frames.load(Ordering::Relaxed), 1);
+        Scheduler::tick();
+        assert_eq!(frames.load(Ordering::Relaxed), 1);
+
+        let (time, action) = counter();
+        let interval = Timer::every(Delay::secs(1)).start(action);
+
+        Scheduler::advance(Duration::from_millis(500));
+        Scheduler::tick();
+        assert_eq!(time.load(Ordering::Relaxed), 0);
+
+        Scheduler::advance(Duration::from_millis(500));
+        Scheduler::tick();
+        assert_eq!(time.load(Ordering::Relaxed), 1);
+
+        Scheduler::advance(Duration::from_secs(1));
+        Scheduler::tick();
+        assert_eq!(time.load(Ordering::Relaxed), 2);
+
+        interval.cancel();
+        Scheduler::advance(Duration::from_secs(1));
+        Scheduler::tick();
+        assert_eq!(time.load(Ordering::Relaxed), 2);
+
+        let (game, action) = counter();
+        Timer::after(Delay::secs(1)).pausable().start(action);
+
+        Scheduler::pause();
+        Scheduler::advance(Duration::from_secs(5));
+        Scheduler::tick();
+        assert_eq!(game.load(Ordering::Relaxed), 0);
+
+        Scheduler::resume();
+        Scheduler::advance(Duration::from_secs(1));
+        Scheduler::tick();
+        assert_eq!(game.load(Ordering::Relaxed), 1);
+
+        assert_eq!(Scheduler::pending(), 0);
+
+        Scheduler::set_manual_clock(false);
+    }
+}
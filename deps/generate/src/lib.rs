@@ -1,2 +1,3 @@
 pub mod maze;
 pub mod noise;
+pub mod sfx;
@@ -0,0 +1,68 @@
+mod params;
+mod preset;
+mod rng;
+mod synth;
+mod wav;
+
+pub use params::*;
+pub use preset::*;
+pub use wav::*;
+
+#[cfg(test)]
+mod test {
+    use crate::sfx::{SfxParams, SfxPreset, Waveform, wav};
+
+    #[test]
+    fn presets() {
+        for preset in SfxPreset::ALL {
+            let samples = preset.params(5).samples();
+
+            assert!(!samples.is_empty(), "{preset:?}");
+            assert!(samples.len() < SfxParams::SAMPLE_RATE as usize * 10, "{preset:?}");
+            assert!(
+                samples.iter().all(|sample| (-1.0..=1.0).contains(sample)),
+                "{preset:?}"
+            );
+            assert!(samples.iter().any(|sample| sample.abs() > 0.01), "{preset:?}");
+
+            assert_eq!(samples, preset.params(5).samples(), "{preset:?}");
+            assert_ne!(preset.params(5), preset.params(6), "{preset:?}");
+        }
+    }
+
+    #[test]
+    fn envelope() {
+        let params = SfxParams {
+            waveform: Waveform::Square,
+            attack: 0.1,
+            sustain: 0.2,
+            decay: 0.3,
+            ..SfxParams::DEFAULT
+        };
+
+        let samples = params.samples();
+
+        // Stage lengths are squared and scaled by 100000 samples.
+        assert_eq!(samples.len(), 1000 + 4000 + 9000 + 2);
+
+        let peak = |range: std::ops::Range<usize>| {
+            samples[range].iter().fold(0.0f32, |peak, sample| peak.max(sample.abs()))
+        };
+
+        assert!(peak(0..100) < peak(900..1000));
+        assert!(peak(samples.len() - 100..samples.len()) < peak(1000..5000) / 10.0);
+    }
+
+    #[test]
+    fn wav_header() {
+        let data = wav(&[0.0, 1.0, -1.0], 22_050);
+
+        assert_eq!(&data[0..4], b"RIFF");
+        assert_eq!(&data[8..16], b"WAVEfmt ");
+        assert_eq!(u32::from_le_bytes(data[24..28].try_into().unwrap()), 22_050);
+        assert_eq!(u32::from_le_bytes(data[40..44].try_into().unwrap()), 6);
+        assert_eq!(data.len(), 44 + 6);
+        assert_eq!(i16::from_le_bytes([data[46], data[47]]), i16::MAX);
+        assert_eq!(i16::from_le_bytes([data[48], data[49]]), -i16::MAX);
+    }
+}
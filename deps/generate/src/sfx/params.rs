@@ -0,0 +1,107 @@
+use crate::sfx::{synth::Synth, wav};
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Waveform {
+    Square,
+    Sawtooth,
+    Sine,
+    Triangle,
+    Noise,
+}
+
+/// Parameters of an sfxr sound. Values are from 0.0 to 1.0 unless noted
+/// otherwise. Slides and sweeps are from -1.0 to 1.0.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct SfxParams {
+    pub waveform: Waveform,
+
+    pub attack:  f32,
+    pub sustain: f32,
+    /// Extra volume at the start of sustain.
+    pub punch:   f32,
+    pub decay:   f32,
+
+    pub frequency:     f32,
+    /// Sound stops when frequency slides below this.
+    pub min_frequency: f32,
+    pub slide:         f32,
+    /// Change of slide over time.
+    pub delta_slide:   f32,
+
+    pub vibrato_depth: f32,
+    pub vibrato_speed: f32,
+
+    /// Frequency jump. Positive values go down, negative go up.
+    pub arpeggio:       f32,
+    /// How soon the jump happens.
+    pub arpeggio_speed: f32,
+
+    /// Square wave only.
+    pub duty:       f32,
+    pub duty_sweep: f32,
+
+    /// Restarts frequency and arpeggio. 0.0 disables.
+    pub repeat_speed: f32,
+
+    /// 1.0 disables low pass filter.
+    pub low_pass:           f32,
+    pub low_pass_sweep:     f32,
+    pub low_pass_resonance: f32,
+    pub high_pass:          f32,
+    pub high_pass_sweep:    f32,
+
+    pub volume: f32,
+}
+
+impl SfxParams {
+    pub const SAMPLE_RATE: u32 = 44_100;
+
+    pub const DEFAULT: Self = Self {
+        waveform: Waveform::Square,
+
+        attack:  0.0,
+        sustain: 0.3,
+        punch:   0.0,
+        decay:   0.4,
+
+        frequency:     0.3,
+        min_frequency: 0.0,
+        slide:         0.0,
+        delta_slide:   0.0,
+
+        vibrato_depth: 0.0,
+        vibrato_speed: 0.0,
+
+        arpeggio:       0.0,
+        arpeggio_speed: 0.0,
+
+        duty:       0.0,
+        duty_sweep: 0.0,
+
+        repeat_speed: 0.0,
+
+        low_pass:           1.0,
+        low_pass_sweep:     0.0,
+        low_pass_resonance: 0.0,
+        high_pass:          0.0,
+        high_pass_sweep:    0.0,
+
+        volume: 0.5,
+    };
+
+    /// Mono samples at [`Self::SAMPLE_RATE`].
+    pub fn samples(&self) -> Vec<f32> {
+        Synth::new(self).run()
+    }
+
+    /// 16 bit mono WAV file.
+    pub fn wav(&self) -> Vec<u8> {
+        wav(&self.samples(), Self::SAMPLE_RATE)
+    }
+}
+
+impl Default for SfxParams {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
@@ -0,0 +1,144 @@
+use crate::sfx::{SfxParams, Waveform, rng::Rng};
+
+/// Randomized sounds for common game events. Same seed always produces the
+/// same sound.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum SfxPreset {
+    Jump,
+    Hit,
+    Pickup,
+    Explosion,
+}
+
+impl SfxPreset {
+    pub const ALL: [Self; 4] = [Self::Jump, Self::Hit, Self::Pickup, Self::Explosion];
+
+    pub fn params(self, seed: u32) -> SfxParams {
+        let mut rng = Rng::new(seed);
+
+        match self {
+            Self::Jump => jump(&mut rng),
+            Self::Hit => hit(&mut rng),
+            Self::Pickup => pickup(&mut rng),
+            Self::Explosion => explosion(&mut rng),
+        }
+    }
+
+    /// Mono samples at [`SfxParams::SAMPLE_RATE`].
+    pub fn samples(self, seed: u32) -> Vec<f32> {
+        self.params(seed).samples()
+    }
+
+    /// 16 bit mono WAV file.
+    pub fn wav(self, seed: u32) -> Vec<u8> {
+        self.params(seed).wav()
+    }
+}
+
+fn jump(rng: &mut Rng) -> SfxParams {
+    let mut p = SfxParams {
+        waveform: Waveform::Square,
+        duty: rng.float(0.6),
+        frequency: 0.3 + rng.float(0.3),
+        slide: 0.1 + rng.float(0.2),
+        sustain: 0.1 + rng.float(0.3),
+        decay: 0.1 + rng.float(0.2),
+        ..SfxParams::DEFAULT
+    };
+
+    if rng.bool() {
+        p.high_pass = rng.float(0.3);
+    }
+
+    if rng.bool() {
+        p.low_pass = 1.0 - rng.float(0.6);
+    }
+
+    p
+}
+
+fn hit(rng: &mut Rng) -> SfxParams {
+    let waveform = match rng.int(2) {
+        0 => Waveform::Square,
+        1 => Waveform::Sawtooth,
+        _ => Waveform::Noise,
+    };
+
+    let mut p = SfxParams {
+        waveform,
+        frequency: 0.2 + rng.float(0.6),
+        slide: -0.3 - rng.float(0.4),
+        sustain: rng.float(0.1),
+        decay: 0.1 + rng.float(0.2),
+        ..SfxParams::DEFAULT
+    };
+
+    if waveform == Waveform::Square {
+        p.duty = rng.float(0.6);
+    }
+
+    if rng.bool() {
+        p.high_pass = rng.float(0.3);
+    }
+
+    p
+}
+
+fn pickup(rng: &mut Rng) -> SfxParams {
+    let mut p = SfxParams {
+        waveform: Waveform::Square,
+        frequency: 0.4 + rng.float(0.5),
+        sustain: rng.float(0.1),
+        decay: 0.1 + rng.float(0.4),
+        punch: 0.3 + rng.float(0.3),
+        ..SfxParams::DEFAULT
+    };
+
+    if rng.bool() {
+        p.arpeggio_speed = 0.5 + rng.float(0.2);
+        p.arpeggio = 0.2 + rng.float(0.4);
+    }
+
+    p
+}
+
+fn explosion(rng: &mut Rng) -> SfxParams {
+    let mut p = SfxParams {
+        waveform: Waveform::Noise,
+        ..SfxParams::DEFAULT
+    };
+
+    if rng.bool() {
+        p.frequency = 0.1 + rng.float(0.4);
+        p.slide = -0.1 + rng.float(0.4);
+    } else {
+        p.frequency = 0.2 + rng.float(0.7);
+        p.slide = -0.2 - rng.float(0.2);
+    }
+
+    p.frequency *= p.frequency;
+
+    if rng.int(4) == 0 {
+        p.slide = 0.0;
+    }
+
+    if rng.int(2) == 0 {
+        p.repeat_speed = 0.3 + rng.float(0.5);
+    }
+
+    p.sustain = 0.1 + rng.float(0.3);
+    p.decay = rng.float(0.5);
+    p.punch = 0.2 + rng.float(0.6);
+
+    if rng.bool() {
+        p.vibrato_depth = rng.float(0.7);
+        p.vibrato_speed = rng.float(0.6);
+    }
+
+    if rng.int(2) == 0 {
+        p.arpeggio_speed = 0.6 + rng.float(0.3);
+        p.arpeggio = 0.8 - rng.float(1.6);
+    }
+
+    p
+}
@@ -0,0 +1,40 @@
+use gm::ToF32;
+
+/// Small xorshift generator. Presets must sound the same on every platform
+/// and every version so an external generator can't be used.
+pub(crate) struct Rng(u32);
+
+impl Rng {
+    pub(crate) fn new(seed: u32) -> Self {
+        // Zero state would produce only zeros.
+        let mut rng = Self(seed ^ 0x9E37_79B9);
+        if rng.0 == 0 {
+            rng.0 = 1;
+        }
+        rng.next();
+        rng
+    }
+
+    fn next(&mut self) -> u32 {
+        let mut x = self.0;
+        x ^= x << 13;
+        x ^= x >> 17;
+        x ^= x << 5;
+        self.0 = x;
+        x
+    }
+
+    /// From 0.0 to `range`.
+    pub(crate) fn float(&mut self, range: f32) -> f32 {
+        (self.next() >> 8).to_f32() / (1u32 << 24).to_f32() * range
+    }
+
+    /// From 0 to `max` inclusive.
+    pub(crate) fn int(&mut self, max: u32) -> u32 {
+        self.next() % (max + 1)
+    }
+
+    pub(crate) fn bool(&mut self) -> bool {
+        self.int(1) == 1
+    }
+}
@@ -0,0 +1,278 @@
+use std::f32::consts::TAU;
+
+use gm::{LossyConvert, ToF32};
+
+use crate::sfx::{SfxParams, Waveform, rng::Rng};
+
+/// Each output sample is averaged from this many wave samples.
+const SUPERSAMPLING: u32 = 8;
+const NOISE_LEN: usize = 32;
+const NOISE_SEED: u32 = 0x5EED;
+const MIN_PERIOD: f32 = 8.0;
+
+/// Port of sfxr synthesizer by Tomas Pettersson.
+pub(crate) struct Synth<'a> {
+    params: &'a SfxParams,
+    rng:    Rng,
+    noise:  [f32; NOISE_LEN],
+
+    phase:       u32,
+    period:      f32,
+    max_period:  f32,
+    slide:       f32,
+    delta_slide: f32,
+
+    duty:       f32,
+    duty_slide: f32,
+
+    arpeggio:       f32,
+    arpeggio_time:  u32,
+    arpeggio_limit: u32,
+
+    repeat_time:  u32,
+    repeat_limit: u32,
+
+    vibrato_phase: f32,
+    vibrato_speed: f32,
+    vibrato_depth: f32,
+
+    envelope_stage:  usize,
+    envelope_time:   u32,
+    envelope_length: [u32; 3],
+
+    low_pass:           f32,
+    low_pass_position:  f32,
+    low_pass_delta:     f32,
+    low_pass_sweep:     f32,
+    low_pass_damping:   f32,
+    high_pass_position: f32,
+    high_pass:          f32,
+    high_pass_sweep:    f32,
+}
+
+impl<'a> Synth<'a> {
+    pub(crate) fn new(params: &'a SfxParams) -> Self {
+        let p = params;
+
+        let stage = |value: f32| -> u32 { (value * value * 100_000.0).lossy_convert() };
+
+        let mut synth = Self {
+            params,
+            rng: Rng::new(NOISE_SEED),
+            noise: [0.0; NOISE_LEN],
+
+            phase: 0,
+            period: 0.0,
+            max_period: 0.0,
+            slide: 0.0,
+            delta_slide: 0.0,
+
+            duty: 0.0,
+            duty_slide: 0.0,
+
+            arpeggio: 0.0,
+            arpeggio_time: 0,
+            arpeggio_limit: 0,
+
+            repeat_time: 0,
+            repeat_limit: if p.repeat_speed == 0.0 {
+                0
+            } else {
+                ((1.0 - p.repeat_speed).powi(2) * 20_000.0 + 32.0).lossy_convert()
+            },
+
+            vibrato_phase: 0.0,
+            vibrato_speed: p.vibrato_speed.powi(2) * 0.01,
+            vibrato_depth: p.vibrato_depth * 0.5,
+
+            envelope_stage: 0,
+            envelope_time: 0,
+            envelope_length: [stage(p.attack), stage(p.sustain), stage(p.decay)],
+
+            low_pass: p.low_pass.powi(3) * 0.1,
+            low_pass_position: 0.0,
+            low_pass_delta: 0.0,
+            low_pass_sweep: 1.0 + p.low_pass_sweep * 0.0001,
+            low_pass_damping: (5.0 / (1.0 + p.low_pass_resonance.powi(2) * 20.0)
+                * (0.01 + p.low_pass.powi(3) * 0.1))
+                .min(0.8),
+            high_pass_position: 0.0,
+            high_pass: p.high_pass.powi(2) * 0.1,
+            high_pass_sweep: 1.0 + p.high_pass_sweep * 0.0003,
+        };
+
+        synth.restart();
+        synth.fill_noise();
+
+        synth
+    }
+
+    /// Resets pitch related state. Called at start and on repeat.
+    fn restart(&mut self) {
+        let p = self.params;
+
+        self.period = 100.0 / (p.frequency.powi(2) + 0.001);
+        self.max_period = 100.0 / (p.min_frequency.powi(2) + 0.001);
+        self.slide = 1.0 - p.slide.powi(3) * 0.01;
+        self.delta_slide = -p.delta_slide.powi(3) * 0.000_001;
+
+        self.duty = 0.5 - p.duty * 0.5;
+        self.duty_slide = -p.duty_sweep * 0.000_05;
+
+        self.arpeggio = if p.arpeggio >= 0.0 {
+            1.0 - p.arpeggio.powi(2) * 0.9
+        } else {
+            1.0 + p.arpeggio.powi(2) * 10.0
+        };
+        self.arpeggio_time = 0;
+        self.arpeggio_limit = if p.arpeggio_speed >= 1.0 {
+            0
+        } else {
+            ((1.0 - p.arpeggio_speed).powi(2) * 20_000.0 + 32.0).lossy_convert()
+        };
+    }
+
+    fn fill_noise(&mut self) {
+        for sample in &mut self.noise {
+            *sample = self.rng.float(2.0) - 1.0;
+        }
+    }
+
+    pub(crate) fn run(mut self) -> Vec<f32> {
+        let mut samples = Vec::with_capacity(self.envelope_length.iter().sum::<u32>() as usize + 3);
+
+        while let Some(sample) = self.sample() {
+            samples.push(sample);
+        }
+
+        samples
+    }
+
+    /// `None` when the sound has ended.
+    fn sample(&mut self) -> Option<f32> {
+        let p = self.params;
+
+        self.repeat_time += 1;
+        if self.repeat_limit != 0 && self.repeat_time >= self.repeat_limit {
+            self.repeat_time = 0;
+            self.restart();
+        }
+
+        self.arpeggio_time += 1;
+        if self.arpeggio_limit != 0 && self.arpeggio_time >= self.arpeggio_limit {
+            self.arpeggio_limit = 0;
+            self.period *= self.arpeggio;
+        }
+
+        self.slide += self.delta_slide;
+        self.period *= self.slide;
+
+        if self.period > self.max_period {
+            self.period = self.max_period;
+            if p.min_frequency > 0.0 {
+                return None;
+            }
+        }
+
+        let mut period = self.period;
+
+        if self.vibrato_depth > 0.0 {
+            self.vibrato_phase += self.vibrato_speed;
+            period *= 1.0 + self.vibrato_phase.sin() * self.vibrato_depth;
+        }
+
+        let period: u32 = period.max(MIN_PERIOD).lossy_convert();
+
+        self.duty = (self.duty + self.duty_slide).clamp(0.0, 0.5);
+
+        let volume = self.envelope()?;
+
+        if p.high_pass_sweep != 0.0 {
+            self.high_pass = (self.high_pass * self.high_pass_sweep).clamp(0.000_01, 0.1);
+        }
+
+        let mut sum = 0.0;
+
+        for _ in 0..SUPERSAMPLING {
+            self.phase += 1;
+            if self.phase >= period {
+                self.phase %= period;
+                if p.waveform == Waveform::Noise {
+                    self.fill_noise();
+                }
+            }
+
+            let sample = self.filter(self.wave(period));
+
+            sum += sample * volume;
+        }
+
+        let sample = sum / SUPERSAMPLING.to_f32() * 2.0 * p.volume;
+
+        Some(sample.clamp(-1.0, 1.0))
+    }
+
+    fn envelope(&mut self) -> Option<f32> {
+        self.envelope_time += 1;
+
+        if self.envelope_time > self.envelope_length[self.envelope_stage] {
+            self.envelope_time = 0;
+            self.envelope_stage += 1;
+            if self.envelope_stage == self.envelope_length.len() {
+                return None;
+            }
+        }
+
+        let progress = if self.envelope_length[self.envelope_stage] == 0 {
+            0.0
+        } else {
+            self.envelope_time.to_f32() / self.envelope_length[self.envelope_stage].to_f32()
+        };
+
+        Some(match self.envelope_stage {
+            0 => progress,
+            1 => 1.0 + (1.0 - progress) * 2.0 * self.params.punch,
+            _ => 1.0 - progress,
+        })
+    }
+
+    fn wave(&self, period: u32) -> f32 {
+        let fraction = self.phase.to_f32() / period.to_f32();
+
+        match self.params.waveform {
+            Waveform::Square => {
+                if fraction < self.duty {
+                    0.5
+                } else {
+                    -0.5
+                }
+            }
+            Waveform::Sawtooth => 1.0 - fraction * 2.0,
+            Waveform::Sine => (fraction * TAU).sin(),
+            Waveform::Triangle => 4.0 * (fraction - 0.5).abs() - 1.0,
+            Waveform::Noise => self.noise[(self.phase as usize * NOISE_LEN) / period as usize],
+        }
+    }
+
+    fn filter(&mut self, sample: f32) -> f32 {
+        let previous = self.low_pass_position;
+
+        self.low_pass = (self.low_pass * self.low_pass_sweep).clamp(0.0, 0.1);
+
+        let position = if self.params.low_pass < 1.0 {
+            self.low_pass_delta += (sample - previous) * self.low_pass;
+            self.low_pass_delta -= self.low_pass_delta * self.low_pass_damping;
+            previous + self.low_pass_delta
+        } else {
+            self.low_pass_delta = 0.0;
+            sample
+        };
+
+        self.low_pass_position = position;
+
+        self.high_pass_position += position - previous;
+        self.high_pass_position -= self.high_pass_position * self.high_pass;
+
+        self.high_pass_position
+    }
+}
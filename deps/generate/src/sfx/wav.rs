@@ -0,0 +1,31 @@
+use gm::LossyConvert;
+
+/// Encodes mono samples as 16 bit PCM WAV. Result can be passed to
+/// `audio::Sound::load_data`.
+pub fn wav(samples: &[f32], sample_rate: u32) -> Vec<u8> {
+    let data_len = u32::try_from(samples.len() * 2).expect("Sound is too long for WAV");
+
+    let mut wav = Vec::with_capacity(44 + samples.len() * 2);
+
+    wav.extend_from_slice(b"RIFF");
+    wav.extend_from_slice(&(36 + data_len).to_le_bytes());
+    wav.extend_from_slice(b"WAVEfmt ");
+    wav.extend_from_slice(&16u32.to_le_bytes());
+    // PCM
+    wav.extend_from_slice(&1u16.to_le_bytes());
+    // Mono
+    wav.extend_from_slice(&1u16.to_le_bytes());
+    wav.extend_from_slice(&sample_rate.to_le_bytes());
+    wav.extend_from_slice(&(sample_rate * 2).to_le_bytes());
+    wav.extend_from_slice(&2u16.to_le_bytes());
+    wav.extend_from_slice(&16u16.to_le_bytes());
+    wav.extend_from_slice(b"data");
+    wav.extend_from_slice(&data_len.to_le_bytes());
+
+    for sample in samples {
+        let sample: i16 = (sample.clamp(-1.0, 1.0) * f32::from(i16::MAX)).lossy_convert();
+        wav.extend_from_slice(&sample.to_le_bytes());
+    }
+
+    wav
+}
@@ -48,7 +48,7 @@ impl Color {
     pub const LIGHTER_GRAY: Color = Color::rgb(0.9, 0.9, 0.9);
     pub const CLEAR: Color = Color::rgba(0.0, 0.0, 0.0, 0.0);
 
-    const ALL: [Color; 13] = [
+    pub const ALL: [Color; 13] = [
         Color::BLACK,
         Color::WHITE,
         Color::RED,
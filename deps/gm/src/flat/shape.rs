@@ -1,9 +1,11 @@
+use serde::{Deserialize, Serialize};
+
 use crate::{
     ToF32,
     flat::{Point, ProcessPoints, Size},
 };
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum Shape {
     Rect(Size),
     Circle(f32),
@@ -24,6 +26,19 @@ impl Shape {
     pub fn is_rect(&self) -> bool {
         matches!(self, Shape::Rect(_))
     }
+
+    /// Scales shape around its origin. Circle radius is scaled by the bigger
+    /// side.
+    pub fn scaled(&self, scale: impl Into<Size>) -> Self {
+        let scale = scale.into();
+        match self {
+            Self::Rect(size) => Self::Rect((size.width * scale.width, size.height * scale.height).into()),
+            Self::Circle(r) => Self::Circle(r * scale.width.max(scale.height)),
+            Self::Triangle(a, b, c) => Self::Triangle(*a * scale, *b * scale, *c * scale),
+            Self::Polygon(points) => Self::Polygon(points.iter().map(|point| *point * scale).collect()),
+            Self::Polyline(points) => Self::Polyline(points.iter().map(|point| *point * scale).collect()),
+        }
+    }
 }
 
 impl Shape {
@@ -60,3 +75,22 @@ impl Default for Shape {
         Shape::Rect(Size::default())
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::flat::{Point, Shape};
+
+    #[test]
+    fn scaled() {
+        assert_eq!(Shape::rect(2, 4).scaled((2, 0.5)), Shape::rect(4, 2));
+        assert_eq!(Shape::Circle(2.0).scaled((2, 3)), Shape::Circle(6.0));
+        assert_eq!(
+            Shape::triangle((0, 0), (1, 0), (0, 1)).scaled((2, 3)),
+            Shape::triangle((0, 0), (2, 0), (0, 3))
+        );
+        assert_eq!(
+            Shape::Polygon(vec![Point::new(1.0, 1.0), Point::new(-1.0, 2.0)]).scaled((2, 2)),
+            Shape::Polygon(vec![Point::new(2.0, 2.0), Point::new(-2.0, 4.0)])
+        );
+    }
+}
@@ -60,6 +60,15 @@ impl LossyConvert<isize> for f32 {
     }
 }
 
+impl LossyConvert<i16> for f32 {
+    fn lossy_convert(self) -> i16 {
+        assert!(!self.is_nan(), "Lossy convert from Nan f32");
+        assert!(self <= f32::from(i16::MAX), "Lossy convert overflow");
+        assert!(self >= f32::from(i16::MIN), "Lossy convert underflow");
+        self as i16
+    }
+}
+
 impl LossyConvert<u8> for f32 {
     fn lossy_convert(self) -> u8 {
         assert!(!self.is_nan(), "Lossy convert from Nan f32");
@@ -89,6 +98,15 @@ impl LossyConvert<u32> for f64 {
     }
 }
 
+impl LossyConvert<usize> for f64 {
+    fn lossy_convert(self) -> usize {
+        assert!(!self.is_nan(), "Lossy convert from Nan f64");
+        assert!(self >= 0.0, "Lossy convert sign loss");
+        assert!(self < usize::MAX as f64, "Lossy convert overflow");
+        self as usize
+    }
+}
+
 impl LossyConvert<u8> for f64 {
     fn lossy_convert(self) -> u8 {
         assert!(!self.is_nan(), "Lossy convert from Nan f64");
@@ -66,8 +66,13 @@ pub fn level(_args: TokenStream, stream: TokenStream) -> TokenStream {
             fn __internal_update(&self, frame_time: f32) {
                 use test_engine::level::Level;
                 use test_engine::level::LevelSetup;
+                use test_engine::level::SystemStage;
                 let mut level = test_engine::refs::weak_from_ref(self);
+                let mut systems: test_engine::refs::Weak<dyn Level> = level;
+                systems.run_systems(SystemStage::PrePhysics, frame_time);
                 level.update_physics(frame_time);
+                systems.run_systems(SystemStage::PostPhysics, frame_time);
+                systems.update_animations(frame_time);
                 level.update();
             }
         }
@@ -0,0 +1,133 @@
+use gm::flat::Rect;
+
+/// What happens when clip reaches its last frame.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum PlayMode {
+    /// Stops on the last frame.
+    Once,
+    /// Starts over from the first frame.
+    #[default]
+    Loop,
+    /// Plays backwards to the first frame and forward again.
+    PingPong,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Frame {
+    /// In pixels of [`crate::SpriteSheet`] image.
+    pub rect:     Rect,
+    /// Seconds the frame is shown.
+    pub duration: f32,
+}
+
+/// Named sequence of sprite sheet frames. See [`crate::SpriteSheet::add_clip`].
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct AnimationClip {
+    pub frames: Vec<Frame>,
+    pub mode:   PlayMode,
+}
+
+impl AnimationClip {
+    pub fn new(mode: PlayMode) -> Self {
+        Self { frames: vec![], mode }
+    }
+
+    /// Frames shown for the same `duration`.
+    pub fn uniform(frames: impl IntoIterator<Item = Rect>, duration: f32, mode: PlayMode) -> Self {
+        Self {
+            frames: frames.into_iter().map(|rect| Frame { rect, duration }).collect(),
+            mode,
+        }
+    }
+
+    pub fn frame(mut self, rect: impl Into<Rect>, duration: f32) -> Self {
+        self.frames.push(Frame {
+            rect: rect.into(),
+            duration,
+        });
+        self
+    }
+
+    /// Seconds of one cycle. Ping pong cycle goes forward and back.
+    pub fn duration(&self) -> f32 {
+        self.sequence().map(|index| self.frames[index].duration).sum()
+    }
+
+    /// Only [`PlayMode::Once`] clips finish.
+    pub fn is_finished(&self, time: f32) -> bool {
+        self.mode == PlayMode::Once && time >= self.duration()
+    }
+
+    /// Index of frame shown `time` seconds after clip start.
+    pub fn frame_at(&self, time: f32) -> usize {
+        let duration = self.duration();
+
+        if self.frames.is_empty() || duration <= 0.0 {
+            return 0;
+        }
+
+        if self.is_finished(time) {
+            return self.frames.len() - 1;
+        }
+
+        let mut time = time.max(0.0) % duration;
+
+        for index in self.sequence() {
+            time -= self.frames[index].duration;
+            if time < 0.0 {
+                return index;
+            }
+        }
+
+        self.frames.len() - 1
+    }
+
+    /// Frame indices of one cycle. Ping pong doesn't repeat the end frames.
+    fn sequence(&self) -> impl Iterator<Item = usize> {
+        let len = self.frames.len();
+        let back = if self.mode == PlayMode::PingPong && len > 2 {
+            1..len - 1
+        } else {
+            0..0
+        };
+        (0..len).chain(back.rev())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use gm::flat::Rect;
+
+    use crate::{AnimationClip, PlayMode};
+
+    #[test]
+    fn frame_at() {
+        let frames = (0..3u8).map(|i| Rect::new(f32::from(i) * 10.0, 0.0, 10.0, 10.0));
+
+        let looped = AnimationClip::uniform(frames.clone(), 0.1, PlayMode::Loop);
+        assert_eq!(looped.frame_at(0.0), 0);
+        assert_eq!(looped.frame_at(0.15), 1);
+        assert_eq!(looped.frame_at(0.25), 2);
+        assert_eq!(looped.frame_at(0.35), 0);
+        assert!(!looped.is_finished(10.0));
+
+        let once = AnimationClip::uniform(frames.clone(), 0.1, PlayMode::Once);
+        assert_eq!(once.frame_at(0.25), 2);
+        assert_eq!(once.frame_at(5.0), 2);
+        assert!(once.is_finished(0.3));
+
+        let ping_pong = AnimationClip::uniform(frames, 0.1, PlayMode::PingPong);
+        assert!((ping_pong.duration() - 0.4).abs() < 0.001);
+        assert_eq!(
+            [0.05, 0.15, 0.25, 0.35, 0.45].map(|time| ping_pong.frame_at(time)),
+            [0, 1, 2, 1, 0]
+        );
+
+        let custom = AnimationClip::new(PlayMode::Loop)
+            .frame((0, 0, 10, 10), 0.5)
+            .frame((10, 0, 10, 10), 0.1);
+        assert_eq!(custom.frame_at(0.45), 0);
+        assert_eq!(custom.frame_at(0.55), 1);
+        assert_eq!(AnimationClip::default().frame_at(1.0), 0);
+    }
+}
@@ -0,0 +1,169 @@
+use std::rc::Rc;
+
+use crate::{Level, Sprite, SpriteSheet};
+
+#[derive(Copy, Clone)]
+enum Trigger {
+    When(fn(&dyn Sprite) -> bool),
+    Finished,
+}
+
+#[derive(Clone)]
+struct Transition {
+    /// `None` for any state.
+    from:    Option<String>,
+    to:      String,
+    trigger: Trigger,
+}
+
+/// Plays [`SpriteSheet`] clips on a sprite. Add it to
+/// [`crate::LevelBase::components`], animators are updated after
+/// [`crate::SystemStage::PostPhysics`] systems. States are clip names.
+#[derive(Clone)]
+pub struct Animator {
+    sheet:       Rc<SpriteSheet>,
+    state:       String,
+    time:        f32,
+    /// 2 plays twice as fast.
+    pub speed:   f32,
+    transitions: Vec<Transition>,
+}
+
+impl Animator {
+    pub fn new(sheet: Rc<SpriteSheet>, state: impl ToString) -> Self {
+        Self {
+            sheet,
+            state: state.to_string(),
+            time: 0.0,
+            speed: 1.0,
+            transitions: vec![],
+        }
+    }
+
+    /// Switches from `from` state to `to` when `condition` is true. Empty
+    /// `from` matches any state. Checked in order they were added.
+    pub fn transition(mut self, from: &str, to: impl ToString, condition: fn(&dyn Sprite) -> bool) -> Self {
+        self.transitions.push(Transition {
+            from:    (!from.is_empty()).then(|| from.to_string()),
+            to:      to.to_string(),
+            trigger: Trigger::When(condition),
+        });
+        self
+    }
+
+    /// Switches to `to` when [`crate::PlayMode::Once`] clip `from` finishes.
+    pub fn then(mut self, from: impl ToString, to: impl ToString) -> Self {
+        self.transitions.push(Transition {
+            from:    Some(from.to_string()),
+            to:      to.to_string(),
+            trigger: Trigger::Finished,
+        });
+        self
+    }
+
+    pub fn state(&self) -> &str {
+        &self.state
+    }
+
+    pub fn sheet(&self) -> &SpriteSheet {
+        &self.sheet
+    }
+
+    /// Starts clip `state` from the first frame unless it is already playing.
+    pub fn play(&mut self, state: &str) {
+        if self.state != state {
+            self.state = state.to_string();
+            self.time = 0.0;
+        }
+    }
+
+    pub fn restart(&mut self) {
+        self.time = 0.0;
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.sheet.clip(&self.state).is_some_and(|clip| clip.is_finished(self.time))
+    }
+
+    /// Index of current frame in current clip.
+    pub fn frame(&self) -> usize {
+        self.sheet.clip(&self.state).map_or(0, |clip| clip.frame_at(self.time))
+    }
+
+    fn update(&mut self, sprite: &mut dyn Sprite, delta: f32) {
+        self.time += delta * self.speed;
+
+        let next = self.transitions.iter().find(|transition| {
+            transition.to != self.state
+                && transition.from.as_ref().is_none_or(|from| *from == self.state)
+                && match transition.trigger {
+                    Trigger::When(condition) => condition(sprite),
+                    Trigger::Finished => self.is_finished(),
+                }
+        });
+
+        if let Some(next) = next.map(|transition| transition.to.clone()) {
+            self.play(&next);
+        }
+
+        let Some(frame) = self
+            .sheet
+            .clip(&self.state)
+            .and_then(|clip| clip.frames.get(clip.frame_at(self.time)))
+        else {
+            return;
+        };
+
+        sprite.image = self.sheet.image();
+        sprite.image_rect = self.sheet.uv(frame.rect);
+    }
+}
+
+/// Advances animators of all sprites.
+pub(crate) fn animate_sprites(level: &mut dyn Level, delta: f32) {
+    for (mut sprite, animator) in level.components.query_mut::<Animator>() {
+        animator.update(&mut *sprite, delta);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::rc::Rc;
+
+    use gm::flat::{Point, Rect, Shape};
+    use refs::Weak;
+    use wgpu_wrapper::image::Image;
+
+    use crate::{
+        AnimationClip, Animator, Banner, LevelCreation, LevelEditor, PlayMode, Sprite, SpriteSheet,
+        level::test_level::TestLevel,
+    };
+
+    #[test]
+    fn animate_in_editor() {
+        let (_guard, mut level) = TestLevel::set();
+
+        let sheet = SpriteSheet::new(Weak::<Image>::default()).add_clip(
+            "run",
+            AnimationClip::uniform(
+                [Rect::new(0.0, 0.0, 10.0, 10.0), Rect::new(10.0, 0.0, 10.0, 10.0)],
+                0.1,
+                PlayMode::Loop,
+            ),
+        );
+
+        let sprite: Weak<dyn Sprite> = level.make_sprite::<Banner>(Shape::rect(1, 1), Point::default());
+        level.components.insert(sprite, Animator::new(Rc::new(sheet), "run"));
+
+        LevelEditor::set_enabled(true);
+        level.update_animations(0.15);
+        LevelEditor::set_enabled(false);
+
+        assert_eq!(level.components.get::<Animator>(sprite).unwrap().frame(), 1);
+
+        level.timestep.time_scale = 0.0;
+        level.update_animations(0.1);
+
+        assert_eq!(level.components.get::<Animator>(sprite).unwrap().frame(), 1);
+    }
+}
@@ -0,0 +1,8 @@
+mod animation_clip;
+mod animator;
+mod sprite_sheet;
+
+pub use animation_clip::{AnimationClip, Frame, PlayMode};
+pub use animator::Animator;
+pub(crate) use animator::animate_sprites;
+pub use sprite_sheet::SpriteSheet;
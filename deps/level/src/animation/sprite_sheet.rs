@@ -0,0 +1,82 @@
+use std::collections::HashMap;
+
+use gm::{
+    ToF32,
+    flat::{Rect, Size},
+};
+use refs::Weak;
+use wgpu_wrapper::image::{Image, ToImage};
+
+use crate::AnimationClip;
+
+/// Texture atlas with animation clips. Shared between sprites through
+/// [`crate::Animator`].
+#[derive(Clone)]
+pub struct SpriteSheet {
+    image: Weak<Image>,
+    /// In pixels.
+    size:  Size,
+    clips: HashMap<String, AnimationClip>,
+}
+
+impl SpriteSheet {
+    pub fn new(image: impl ToImage) -> Self {
+        let image = image.to_image();
+
+        let size = if image.is_ok() {
+            Size::new(image.size.width.to_f32(), image.size.height.to_f32())
+        } else {
+            Size::default()
+        };
+
+        Self {
+            image,
+            size,
+            clips: HashMap::new(),
+        }
+    }
+
+    pub fn image(&self) -> Weak<Image> {
+        self.image
+    }
+
+    pub fn add_clip(mut self, name: impl ToString, clip: AnimationClip) -> Self {
+        self.clips.insert(name.to_string(), clip);
+        self
+    }
+
+    pub fn clip(&self, name: &str) -> Option<&AnimationClip> {
+        self.clips.get(name)
+    }
+
+    /// Pixel rects of sheet cells of `cell` size. Cells are counted row by row
+    /// from top left corner.
+    pub fn cells(&self, cell: impl Into<Size>, indices: impl IntoIterator<Item = u32>) -> Vec<Rect> {
+        let cell = cell.into();
+        let columns = (self.size.width / cell.width).floor().max(1.0);
+
+        indices
+            .into_iter()
+            .map(|index| {
+                let index = index.to_f32();
+                let row = (index / columns).floor();
+                let column = index - row * columns;
+                Rect::new(column * cell.width, row * cell.height, cell.width, cell.height)
+            })
+            .collect()
+    }
+
+    /// Converts pixel rect to texture coordinates.
+    pub(crate) fn uv(&self, rect: Rect) -> Rect {
+        if self.size.width <= 0.0 || self.size.height <= 0.0 {
+            return Rect::new(0.0, 0.0, 1.0, 1.0);
+        }
+
+        Rect::new(
+            rect.x() / self.size.width,
+            rect.y() / self.size.height,
+            rect.width() / self.size.width,
+            rect.height() / self.size.height,
+        )
+    }
+}
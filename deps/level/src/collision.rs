@@ -0,0 +1,98 @@
+use gm::flat::Point;
+use refs::Weak;
+use serde::{Deserialize, Serialize};
+
+use crate::Sprite;
+
+/// Which sprites collide with each other. Two sprites collide if each one's
+/// `layers` intersects the other one's `mask`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CollisionGroups {
+    /// Bits of layers the sprite belongs to.
+    pub layers: u32,
+    /// Bits of layers the sprite collides with.
+    pub mask:   u32,
+}
+
+impl CollisionGroups {
+    pub const ALL: Self = Self::new(u32::MAX, u32::MAX);
+    pub const NONE: Self = Self::new(0, 0);
+
+    pub const fn new(layers: u32, mask: u32) -> Self {
+        Self { layers, mask }
+    }
+
+    pub const fn collides_with(&self, other: &Self) -> bool {
+        self.layers & other.mask != 0 && other.layers & self.mask != 0
+    }
+}
+
+impl Default for CollisionGroups {
+    fn default() -> Self {
+        Self::ALL
+    }
+}
+
+/// Single contact point between two sprites.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Contact {
+    /// In level space.
+    pub point:   Point,
+    /// Impulse applied at this point to push sprites apart during last physics
+    /// step.
+    pub impulse: f32,
+}
+
+/// Group of contact points sharing the same normal.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ContactManifold {
+    /// Points from sprite receiving the event towards the other sprite.
+    pub normal:   Point,
+    pub contacts: Vec<Contact>,
+}
+
+/// Passed to [`crate::SpriteData::on_collision`]. Sensor intersections have no
+/// manifolds.
+#[derive(Clone)]
+pub struct Collision {
+    /// The other sprite.
+    pub sprite:    Weak<dyn Sprite>,
+    pub manifolds: Vec<ContactManifold>,
+    /// Total impulse magnitude of all contacts.
+    pub impulse:   f32,
+}
+
+impl Collision {
+    /// Normal of the first manifold. Zero if there are no contacts.
+    pub fn normal(&self) -> Point {
+        self.manifolds.first().map(|manifold| manifold.normal).unwrap_or_default()
+    }
+
+    pub fn points(&self) -> impl Iterator<Item = Point> + '_ {
+        self.manifolds
+            .iter()
+            .flat_map(|manifold| manifold.contacts.iter().map(|contact| contact.point))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::CollisionGroups;
+
+    #[test]
+    fn collision_groups() {
+        const PLAYER: u32 = 0b001;
+        const ENEMY: u32 = 0b010;
+        const BULLET: u32 = 0b100;
+
+        let player = CollisionGroups::new(PLAYER, ENEMY | BULLET);
+        let enemy = CollisionGroups::new(ENEMY, PLAYER);
+        let bullet = CollisionGroups::new(BULLET, PLAYER | ENEMY);
+
+        assert!(player.collides_with(&enemy));
+        assert!(player.collides_with(&bullet));
+        assert!(!enemy.collides_with(&bullet));
+        assert!(!enemy.collides_with(&CollisionGroups::NONE));
+        assert!(CollisionGroups::ALL.collides_with(&CollisionGroups::default()));
+    }
+}
@@ -0,0 +1,314 @@
+use std::{
+    any::{Any, TypeId, type_name},
+    collections::HashMap,
+};
+
+use indexmap::IndexMap;
+use refs::Weak;
+
+use crate::Sprite;
+
+/// Data attached to level sprites. Any `Clone` type can be a component, sprite
+/// has at most one component of each type. Components of removed sprites are
+/// dropped. See [`crate::LevelBase::components`].
+#[derive(Default)]
+pub struct Components {
+    storages: HashMap<TypeId, Box<dyn Storage>>,
+}
+
+impl Components {
+    /// Returns previous component of this type.
+    pub fn insert<T: Clone + 'static>(&mut self, sprite: Weak<dyn Sprite>, component: T) -> Option<T> {
+        assert!(sprite.is_ok(), "Adding {} to removed sprite", type_name::<T>());
+
+        self.storages
+            .entry(TypeId::of::<T>())
+            .or_insert_with(|| Box::new(ComponentStorage::<T>::default()))
+            .as_any_mut()
+            .downcast_mut::<ComponentStorage<T>>()
+            .expect("Component storage type mismatch")
+            .components
+            .insert(sprite.addr(), (sprite, component))
+            .map(|(_, component)| component)
+    }
+
+    pub fn remove<T: Clone + 'static>(&mut self, sprite: Weak<dyn Sprite>) -> Option<T> {
+        self.storage_mut::<T>()?
+            .components
+            .shift_remove(&sprite.addr())
+            .map(|(_, component)| component)
+    }
+
+    pub fn get<T: Clone + 'static>(&self, sprite: Weak<dyn Sprite>) -> Option<&T> {
+        self.storage::<T>()?
+            .components
+            .get(&sprite.addr())
+            .map(|(_, component)| component)
+    }
+
+    pub fn get_mut<T: Clone + 'static>(&mut self, sprite: Weak<dyn Sprite>) -> Option<&mut T> {
+        self.storage_mut::<T>()?
+            .components
+            .get_mut(&sprite.addr())
+            .map(|(_, component)| component)
+    }
+
+    pub fn has<T: Clone + 'static>(&self, sprite: Weak<dyn Sprite>) -> bool {
+        self.get::<T>(sprite).is_some()
+    }
+
+    /// Number of sprites with component `T`.
+    pub fn count<T: Clone + 'static>(&self) -> usize {
+        self.storage::<T>().map_or(0, |storage| storage.components.len())
+    }
+
+    /// Sprites with component `T` in order the components were added.
+    pub fn query<T: Clone + 'static>(&self) -> impl Iterator<Item = (Weak<dyn Sprite>, &T)> {
+        self.storage::<T>()
+            .into_iter()
+            .flat_map(|storage| storage.components.values())
+            .map(|(sprite, component)| (*sprite, component))
+    }
+
+    /// See [`Components::query`].
+    pub fn query_mut<T: Clone + 'static>(&mut self) -> impl Iterator<Item = (Weak<dyn Sprite>, &mut T)> {
+        self.storage_mut::<T>()
+            .into_iter()
+            .flat_map(|storage| storage.components.values_mut())
+            .map(|(sprite, component)| (*sprite, component))
+    }
+
+    /// Sprites with both `A` and `B` components in order `A` components were
+    /// added.
+    pub fn query_pair_mut<A: Clone + 'static, B: Clone + 'static>(
+        &mut self,
+    ) -> Vec<(Weak<dyn Sprite>, &mut A, &mut B)> {
+        assert_ne!(
+            TypeId::of::<A>(),
+            TypeId::of::<B>(),
+            "Querying {} twice",
+            type_name::<A>()
+        );
+
+        let [Some(a), Some(b)] = self.storages.get_many_mut([&TypeId::of::<A>(), &TypeId::of::<B>()]) else {
+            return vec![];
+        };
+
+        let a = a
+            .as_any_mut()
+            .downcast_mut::<ComponentStorage<A>>()
+            .expect("Component storage type mismatch");
+        let b = b
+            .as_any_mut()
+            .downcast_mut::<ComponentStorage<B>>()
+            .expect("Component storage type mismatch");
+
+        let mut b: HashMap<_, _> = b
+            .components
+            .iter_mut()
+            .map(|(addr, (_, component))| (*addr, component))
+            .collect();
+
+        a.components
+            .iter_mut()
+            .filter_map(|(addr, (sprite, a))| b.remove(addr).map(|b| (*sprite, a, b)))
+            .collect()
+    }
+
+    /// Drops all components of the sprite.
+    pub(crate) fn remove_sprite(&mut self, sprite: usize) {
+        for storage in self.storages.values_mut() {
+            storage.remove(sprite);
+        }
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.storages.clear();
+    }
+
+    /// Moves components of sprites recreated by snapshot restore to the new
+    /// sprites and drops ones of removed sprites.
+    pub(crate) fn remap(&mut self, recreated: &HashMap<usize, Weak<dyn Sprite>>) {
+        for storage in self.storages.values_mut() {
+            storage.remap(recreated);
+        }
+    }
+
+    fn storage<T: Clone + 'static>(&self) -> Option<&ComponentStorage<T>> {
+        self.storages.get(&TypeId::of::<T>())?.as_any().downcast_ref()
+    }
+
+    fn storage_mut<T: Clone + 'static>(&mut self) -> Option<&mut ComponentStorage<T>> {
+        self.storages.get_mut(&TypeId::of::<T>())?.as_any_mut().downcast_mut()
+    }
+}
+
+impl Clone for Components {
+    fn clone(&self) -> Self {
+        Self {
+            storages: self.storages.iter().map(|(id, storage)| (*id, storage.clone_box())).collect(),
+        }
+    }
+}
+
+trait Storage {
+    fn remove(&mut self, sprite: usize);
+    fn remap(&mut self, recreated: &HashMap<usize, Weak<dyn Sprite>>);
+    fn clone_box(&self) -> Box<dyn Storage>;
+    fn as_any(&self) -> &dyn Any;
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+}
+
+/// Keyed by sprite address. Insertion ordered so systems visit sprites in the
+/// same order on every run.
+#[derive(Clone)]
+struct ComponentStorage<T> {
+    components: IndexMap<usize, (Weak<dyn Sprite>, T)>,
+}
+
+impl<T> Default for ComponentStorage<T> {
+    fn default() -> Self {
+        Self {
+            components: IndexMap::default(),
+        }
+    }
+}
+
+impl<T: Clone + 'static> Storage for ComponentStorage<T> {
+    fn remove(&mut self, sprite: usize) {
+        self.components.shift_remove(&sprite);
+    }
+
+    fn remap(&mut self, recreated: &HashMap<usize, Weak<dyn Sprite>>) {
+        self.components = self
+            .components
+            .drain(..)
+            .filter_map(|(addr, (sprite, component))| {
+                let sprite = recreated.get(&addr).copied().unwrap_or(sprite);
+                sprite.is_ok().then(|| (sprite.addr(), (sprite, component)))
+            })
+            .collect();
+    }
+
+    fn clone_box(&self) -> Box<dyn Storage> {
+        Box::new(self.clone())
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+
+    use gm::flat::{Point, Shape, Size};
+    use refs::{Own, Weak};
+
+    use crate::{Banner, Components, Sprite};
+
+    #[derive(Clone, Debug, PartialEq)]
+    struct Health(u32);
+
+    #[derive(Clone, Debug, PartialEq)]
+    struct Speed(f32);
+
+    fn banners(count: usize) -> Vec<Own<Banner>> {
+        (0..count)
+            .map(|_| Banner::make(Shape::Rect(Size::new(1.0, 1.0)), Point::default()))
+            .collect()
+    }
+
+    fn weak(banner: &Own<Banner>) -> Weak<dyn Sprite> {
+        banner.weak()
+    }
+
+    #[test]
+    fn insert_remove() {
+        let banners = banners(2);
+        let [a, b] = [weak(&banners[0]), weak(&banners[1])];
+
+        let mut components = Components::default();
+
+        assert_eq!(components.insert(a, Health(10)), None);
+        assert_eq!(components.insert(a, Health(20)), Some(Health(10)));
+        components.insert(b, Health(30));
+        components.insert(b, Speed(1.0));
+
+        assert_eq!(components.count::<Health>(), 2);
+        assert_eq!(components.get::<Health>(a), Some(&Health(20)));
+        assert!(!components.has::<Speed>(a));
+
+        components.get_mut::<Health>(a).unwrap().0 += 1;
+        assert_eq!(components.get::<Health>(a), Some(&Health(21)));
+
+        assert_eq!(components.remove::<Health>(a), Some(Health(21)));
+        assert_eq!(components.remove::<Health>(a), None);
+        assert_eq!(components.remove::<Speed>(a), None);
+
+        components.remove_sprite(b.addr());
+        assert_eq!(components.count::<Health>(), 0);
+        assert_eq!(components.count::<Speed>(), 0);
+    }
+
+    #[test]
+    fn query_pair_mut() {
+        let banners = banners(3);
+        let sprites: Vec<_> = banners.iter().map(weak).collect();
+
+        let mut components = Components::default();
+
+        for (sprite, health) in sprites.iter().zip([10, 20, 30]) {
+            components.insert(*sprite, Health(health));
+        }
+        components.insert(sprites[2], Speed(1.0));
+        components.insert(sprites[0], Speed(2.0));
+
+        for (_, health, speed) in components.query_pair_mut::<Health, Speed>() {
+            health.0 += 1;
+            speed.0 *= 2.0;
+        }
+
+        // Order of the first component type
+        let pairs: Vec<_> = components
+            .query_pair_mut::<Health, Speed>()
+            .into_iter()
+            .map(|(sprite, health, speed)| (sprite.addr(), health.0, speed.0))
+            .collect();
+        assert_eq!(pairs, vec![
+            (sprites[0].addr(), 11, 4.0),
+            (sprites[2].addr(), 31, 2.0)
+        ]);
+
+        assert_eq!(components.get::<Health>(sprites[1]), Some(&Health(20)));
+        assert!(components.query_pair_mut::<Health, String>().is_empty());
+    }
+
+    #[test]
+    fn remap() {
+        let old = banners(2);
+        let new = banners(1);
+        let [kept, recreated] = [weak(&old[0]), weak(&old[1])];
+        let replacement = weak(&new[0]);
+
+        let mut components = Components::default();
+        components.insert(kept, Health(1));
+        components.insert(recreated, Health(2));
+
+        components.remap(&HashMap::from([(recreated.addr(), replacement)]));
+
+        assert_eq!(components.get::<Health>(kept), Some(&Health(1)));
+        assert_eq!(components.get::<Health>(replacement), Some(&Health(2)));
+        assert!(!components.has::<Health>(recreated));
+
+        drop(old);
+        components.remap(&HashMap::new());
+        assert_eq!(components.count::<Health>(), 1);
+        assert_eq!(components.get::<Health>(replacement), Some(&Health(2)));
+    }
+}
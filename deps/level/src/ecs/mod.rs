@@ -0,0 +1,6 @@
+mod components;
+mod systems;
+
+pub use components::Components;
+pub use systems::SystemStage;
+pub(crate) use systems::Systems;
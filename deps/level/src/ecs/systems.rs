@@ -0,0 +1,67 @@
+use std::mem::take;
+
+use crate::{Level, LevelEditor, animation::animate_sprites};
+
+/// When system runs during level update. Systems of the same stage run in
+/// order they were added.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SystemStage {
+    /// Before physics steps of the frame. Set velocities and forces here.
+    PrePhysics,
+    /// After physics steps and before [`crate::LevelSetup::update`].
+    PostPhysics,
+}
+
+pub(crate) type System = Box<dyn FnMut(&mut dyn Level, f32)>;
+
+/// Logic working on [`crate::Components`] of level sprites. See
+/// [`crate::LevelBase::add_system`].
+#[derive(Default)]
+pub(crate) struct Systems {
+    systems: Vec<(SystemStage, System)>,
+}
+
+impl Systems {
+    pub(crate) fn add(&mut self, stage: SystemStage, system: System) {
+        self.systems.push((stage, system));
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.systems.clear();
+    }
+}
+
+impl dyn Level {
+    /// Runs systems of `stage` with [`crate::FixedTimestep::frame_delta`] of
+    /// level timestep. Called by `#[level]` update. Systems are paused while
+    /// [`LevelEditor`] is enabled.
+    pub fn run_systems(&mut self, stage: SystemStage, frame_time: f32) {
+        if LevelEditor::is_enabled() {
+            return;
+        }
+
+        let delta = self.timestep.frame_delta(frame_time);
+
+        // Systems get mutable level so they are taken out while running
+        let mut systems = take(&mut self.systems);
+
+        for (system_stage, system) in &mut systems.systems {
+            if *system_stage == stage {
+                system(self, delta);
+            }
+        }
+
+        // Keep systems added while running
+        systems.systems.append(&mut self.systems.systems);
+        self.systems = systems;
+    }
+
+    /// Advances [`crate::Animator`]s. Called by `#[level]` update after
+    /// [`SystemStage::PostPhysics`] systems so transitions see states they
+    /// set. Unlike systems keeps running while [`LevelEditor`] is enabled so
+    /// edited level stays animated.
+    pub fn update_animations(&mut self, frame_time: f32) {
+        let delta = self.timestep.frame_delta(frame_time);
+        animate_sprites(self, delta);
+    }
+}
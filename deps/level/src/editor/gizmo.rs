@@ -0,0 +1,146 @@
+use gm::flat::{Point, Size};
+
+/// Part of selected sprite editor touch grabbed.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Gizmo {
+    /// Sprite body. Drags the sprite.
+    Move,
+    /// Handle above the sprite.
+    Rotate,
+    /// Handle in top right corner. Scales sprite shape.
+    Scale,
+}
+
+impl Gizmo {
+    pub const HANDLES: [Self; 2] = [Self::Rotate, Self::Scale];
+
+    /// Position of handle for sprite with half extents `size` (see
+    /// [`crate::Sprite::render_size`]). Sprite position for [`Gizmo::Move`].
+    pub fn handle(self, position: Point, size: Size, rotation: f32, handle_size: f32) -> Point {
+        let local = match self {
+            Self::Move => Point::default(),
+            Self::Rotate => Point::new(0.0, size.height + handle_size * 3.0),
+            Self::Scale => Point::new(size.width, size.height),
+        };
+        position + rotate(local, rotation)
+    }
+
+    /// Handles have priority over sprite body.
+    pub(crate) fn at(
+        position: Point,
+        size: Size,
+        rotation: f32,
+        handle_size: f32,
+        point: Point,
+    ) -> Option<Self> {
+        for gizmo in Self::HANDLES {
+            let handle = gizmo.handle(position, size, rotation, handle_size);
+            let offset = point - handle;
+            if offset.x.abs() <= handle_size && offset.y.abs() <= handle_size {
+                return gizmo.into();
+            }
+        }
+
+        let local = rotate(point - position, -rotation);
+
+        if local.x.abs() <= size.width && local.y.abs() <= size.height {
+            return Self::Move.into();
+        }
+
+        None
+    }
+}
+
+/// How much the sprite at `center` rotates when its rotate handle is dragged
+/// from `from` to `to`.
+pub(crate) fn rotation_delta(center: Point, from: Point, to: Point) -> f32 {
+    center.angle_to(to) - center.angle_to(from)
+}
+
+/// Shape scale when scale handle of the sprite at `center` is dragged from
+/// `from` to `to`. Each axis is scaled in sprite local space.
+pub(crate) fn scale(center: Point, rotation: f32, from: Point, to: Point) -> Size {
+    const MIN: f32 = 0.05;
+
+    let from = rotate(from - center, -rotation);
+    let to = rotate(to - center, -rotation);
+
+    let axis = |from: f32, to: f32| {
+        if from.abs() < f32::EPSILON {
+            1.0
+        } else {
+            (to / from).max(MIN)
+        }
+    };
+
+    (axis(from.x, to.x), axis(from.y, to.y)).into()
+}
+
+pub(crate) fn rotate(point: Point, angle: f32) -> Point {
+    let (sin, cos) = angle.sin_cos();
+    Point::new(point.x * cos - point.y * sin, point.x * sin + point.y * cos)
+}
+
+#[cfg(test)]
+mod test {
+    use std::f32::consts::FRAC_PI_2;
+
+    use gm::flat::{Point, Size};
+
+    use crate::editor::{
+        Gizmo,
+        gizmo::{rotation_delta, scale},
+    };
+
+    fn assert_near(a: Point, b: Point) {
+        assert!((a - b).length() < 0.001, "{a:?} != {b:?}");
+    }
+
+    #[test]
+    fn handles() {
+        let position = Point::new(10.0, 10.0);
+        let size = Size::new(2.0, 1.0);
+
+        assert_near(
+            Gizmo::Scale.handle(position, size, 0.0, 0.5),
+            Point::new(12.0, 11.0),
+        );
+        assert_near(
+            Gizmo::Rotate.handle(position, size, 0.0, 0.5),
+            Point::new(10.0, 12.5),
+        );
+        assert_near(
+            Gizmo::Scale.handle(position, size, FRAC_PI_2, 0.5),
+            Point::new(9.0, 12.0),
+        );
+
+        let at = |point: (f32, f32), rotation: f32| Gizmo::at(position, size, rotation, 0.5, point.into());
+
+        assert_eq!(at((12.2, 11.2), 0.0), Some(Gizmo::Scale));
+        assert_eq!(at((10.0, 12.5), 0.0), Some(Gizmo::Rotate));
+        assert_eq!(at((11.5, 10.0), 0.0), Some(Gizmo::Move));
+        assert_eq!(at((10.0, 11.5), 0.0), None);
+        assert_eq!(at((10.0, 11.5), FRAC_PI_2), Some(Gizmo::Move));
+        assert_eq!(at((20.0, 20.0), 0.0), None);
+    }
+
+    #[test]
+    fn transforms() {
+        let center = Point::new(1.0, 1.0);
+
+        let delta = rotation_delta(center, Point::new(2.0, 1.0), Point::new(1.0, 2.0));
+        assert!((delta - FRAC_PI_2).abs() < 0.001);
+
+        assert_eq!(
+            scale(center, 0.0, Point::new(3.0, 2.0), Point::new(5.0, 1.5)),
+            Size::new(2.0, 0.5)
+        );
+
+        let rotated = scale(center, FRAC_PI_2, Point::new(0.0, 3.0), Point::new(-1.0, 5.0));
+        assert!((rotated.width - 2.0).abs() < 0.001);
+        assert!((rotated.height - 2.0).abs() < 0.001);
+
+        let flipped = scale(center, 0.0, Point::new(3.0, 2.0), Point::new(-3.0, 2.0));
+        assert!((flipped.width - 0.05).abs() < 0.001);
+    }
+}
@@ -0,0 +1,199 @@
+use std::fmt::{Debug, Formatter};
+
+use refs::Weak;
+
+use crate::{Sprite, SpriteDocument};
+
+/// Editable state of level sprites. Sprites are stored with their documents
+/// so undo updates them in place and their joints, components and
+/// subscriptions stay.
+#[derive(Clone, Default, PartialEq, Debug)]
+pub(crate) struct EditorSnapshot {
+    pub(crate) sprites: Vec<SpriteState>,
+}
+
+#[derive(Clone)]
+pub(crate) struct SpriteState {
+    pub(crate) sprite:   Weak<dyn Sprite>,
+    pub(crate) document: SpriteDocument,
+}
+
+impl Debug for SpriteState {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SpriteState")
+            .field("sprite", &self.sprite.addr())
+            .field("document", &self.document)
+            .finish()
+    }
+}
+
+impl PartialEq for SpriteState {
+    fn eq(&self, other: &Self) -> bool {
+        self.sprite.addr() == other.sprite.addr() && self.document == other.document
+    }
+}
+
+/// Level snapshots for editor undo and redo.
+pub(crate) struct History<T> {
+    undo: Vec<T>,
+    redo: Vec<T>,
+}
+
+impl<T> Default for History<T> {
+    fn default() -> Self {
+        Self {
+            undo: vec![],
+            redo: vec![],
+        }
+    }
+}
+
+impl<T: PartialEq> History<T> {
+    const LIMIT: usize = 100;
+
+    /// Stores state before an edit. Drops redo history.
+    pub(crate) fn record(&mut self, state: T) {
+        if self.undo.last() == Some(&state) {
+            return;
+        }
+
+        if self.undo.len() == Self::LIMIT {
+            self.undo.remove(0);
+        }
+
+        self.undo.push(state);
+        self.redo.clear();
+    }
+
+    /// Returns state to restore. `current` is kept for redo.
+    pub(crate) fn undo(&mut self, current: T) -> Option<T> {
+        let state = self.undo.pop()?;
+        self.redo.push(current);
+        Some(state)
+    }
+
+    pub(crate) fn redo(&mut self, current: T) -> Option<T> {
+        let state = self.redo.pop()?;
+        self.undo.push(current);
+        Some(state)
+    }
+
+    pub(crate) fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    pub(crate) fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+    }
+
+    /// All stored states. Used to point them to recreated sprites.
+    pub(crate) fn entries_mut(&mut self) -> impl Iterator<Item = &mut T> {
+        self.undo.iter_mut().chain(self.redo.iter_mut())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use gm::{
+        Color,
+        flat::{Point, Shape},
+    };
+    use refs::{Own, Weak};
+
+    use crate::{
+        Banner, LevelDocument, Sprite, SpriteDocument,
+        editor::history::{EditorSnapshot, History, SpriteState},
+    };
+
+    fn document(background: &str) -> LevelDocument {
+        LevelDocument {
+            background: Some(background.to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn undo_redo() {
+        let mut history = History::default();
+        assert!(!history.can_undo());
+        assert_eq!(history.undo(document("a")), None);
+
+        history.record(document("a"));
+        history.record(document("b"));
+        history.record(document("b"));
+
+        assert_eq!(history.undo(document("c")), Some(document("b")));
+        assert_eq!(history.undo(document("b")), Some(document("a")));
+        assert!(!history.can_undo());
+
+        assert_eq!(history.redo(document("a")), Some(document("b")));
+        assert_eq!(history.redo(document("b")), Some(document("c")));
+        assert!(!history.can_redo());
+
+        history.undo(document("c"));
+        assert!(history.can_redo());
+        history.record(document("d"));
+        assert!(!history.can_redo());
+    }
+
+    fn snapshot(sprites: &[&Own<Banner>]) -> EditorSnapshot {
+        EditorSnapshot {
+            sprites: sprites
+                .iter()
+                .map(|banner| {
+                    let sprite: Weak<dyn Sprite> = banner.weak();
+                    SpriteState {
+                        sprite,
+                        document: SpriteDocument {
+                            kind:       "Banner".into(),
+                            shape:      Shape::rect(1, 1),
+                            position:   Point::default(),
+                            rotation:   0.0,
+                            color:      Color::BLUE,
+                            image:      None,
+                            tag:        0,
+                            z_position: None,
+                            physics:    None,
+                        },
+                    }
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn sprite_snapshots() {
+        let a = Banner::make(Shape::rect(1, 1), Point::default());
+        let b = Banner::make(Shape::rect(1, 1), Point::default());
+
+        // Same documents of different sprites are different states
+        assert_ne!(snapshot(&[&a]), snapshot(&[&b]));
+
+        let mut history = History::default();
+        history.record(snapshot(&[&a]));
+        history.record(snapshot(&[&a]));
+        history.record(snapshot(&[&a, &b]));
+        history.undo(snapshot(&[&b]));
+
+        assert_eq!(history.entries_mut().count(), 2);
+
+        let replacement: Weak<dyn Sprite> = b.weak();
+
+        for entry in history.entries_mut() {
+            for state in &mut entry.sprites {
+                if state.sprite.addr() == a.addr() {
+                    state.sprite = replacement;
+                }
+            }
+        }
+
+        assert_eq!(history.undo(snapshot(&[])), Some(snapshot(&[&b])));
+        assert_eq!(history.redo(snapshot(&[&b])), Some(snapshot(&[])));
+        assert_eq!(history.redo(snapshot(&[])), Some(snapshot(&[&b])));
+    }
+}
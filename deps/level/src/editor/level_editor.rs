@@ -0,0 +1,467 @@
+use std::{
+    collections::{HashMap, HashSet},
+    ops::{Deref, DerefMut},
+    path::Path,
+};
+
+use anyhow::{Result, bail};
+use educe::Educe;
+use gm::flat::{Point, Shape};
+use rapier2d::na::Vector2;
+use refs::{MainLock, Own, Weak};
+
+use crate::{
+    LevelManager, Sprite, SpriteDocument, SpriteTemplates,
+    editor::{
+        Gizmo,
+        gizmo::{rotation_delta, scale},
+        history::{EditorSnapshot, History, SpriteState},
+    },
+    sprite_registry::SpriteRegistry,
+};
+
+static EDITOR: MainLock<LevelEditor> = MainLock::new();
+
+/// In-game editor of current level. When enabled level touches select and
+/// transform sprites instead of reaching the level and physics is paused.
+#[derive(Educe)]
+#[educe(Default)]
+pub struct LevelEditor {
+    enabled:  bool,
+    grid:     Option<f32>,
+    selected: Weak<dyn Sprite>,
+    drag:     Option<Drag>,
+    history:  History<EditorSnapshot>,
+    revision: u64,
+}
+
+struct Drag {
+    gizmo:    Gizmo,
+    from:     Point,
+    position: Point,
+    rotation: f32,
+    /// Sprite shape before the drag.
+    shape:    Shape,
+    /// Level state before the drag. Recorded to history on first move.
+    before:   Option<EditorSnapshot>,
+    moved:    bool,
+}
+
+impl LevelEditor {
+    /// Rotation step when grid snapping is on.
+    pub const ANGLE_STEP: f32 = std::f32::consts::PI / 12.0;
+
+    pub fn is_enabled() -> bool {
+        EDITOR.enabled
+    }
+
+    pub fn set_enabled(enabled: bool) {
+        if !enabled {
+            Self::select(Weak::default());
+        }
+        let editor = EDITOR.get_mut();
+        editor.enabled = enabled;
+        editor.drag = None;
+        editor.changed();
+    }
+
+    pub fn toggle() {
+        Self::set_enabled(!Self::is_enabled());
+    }
+
+    pub fn grid() -> Option<f32> {
+        EDITOR.grid
+    }
+
+    /// Cell size sprite positions snap to. Also snaps rotation to
+    /// [`Self::ANGLE_STEP`].
+    pub fn set_grid(grid: impl Into<Option<f32>>) {
+        EDITOR.get_mut().grid = grid.into().filter(|grid| *grid > 0.0);
+    }
+
+    pub fn snap(point: Point) -> Point {
+        let Some(grid) = Self::grid() else {
+            return point;
+        };
+        Point::new((point.x / grid).round() * grid, (point.y / grid).round() * grid)
+    }
+
+    pub fn snap_angle(angle: f32) -> f32 {
+        if Self::grid().is_none() {
+            return angle;
+        }
+        (angle / Self::ANGLE_STEP).round() * Self::ANGLE_STEP
+    }
+
+    /// Gizmo handle half side in level units. Stays the same on screen when
+    /// level is scaled.
+    pub fn handle_size() -> f32 {
+        0.5 / *LevelManager::scale()
+    }
+
+    pub fn selected() -> Weak<dyn Sprite> {
+        EDITOR.selected
+    }
+
+    /// Pass `Weak::default()` to clear selection. Triggers
+    /// [`crate::LevelBase::on_sprite_selected`].
+    pub fn select(sprite: Weak<dyn Sprite>) {
+        let editor = EDITOR.get_mut();
+
+        if editor.selected.addr() == sprite.addr() {
+            return;
+        }
+
+        if editor.selected.is_ok() {
+            editor.selected.set_selected(false);
+        }
+
+        editor.selected = sprite;
+
+        if editor.selected.is_ok() {
+            editor.selected.set_selected(true);
+        }
+
+        editor.changed();
+
+        if !LevelManager::no_level() {
+            LevelManager::level().on_sprite_selected.trigger(sprite);
+        }
+    }
+
+    /// Increased on every edit and selection change.
+    pub fn revision() -> u64 {
+        EDITOR.revision
+    }
+
+    pub fn can_undo() -> bool {
+        EDITOR.history.can_undo()
+    }
+
+    pub fn can_redo() -> bool {
+        EDITOR.history.can_redo()
+    }
+
+    /// Stores current level state so the next edit can be undone.
+    pub fn record() -> Result<()> {
+        let snapshot = Self::snapshot()?;
+        EDITOR.get_mut().history.record(snapshot);
+        Ok(())
+    }
+
+    pub fn undo() -> Result<()> {
+        let current = Self::snapshot()?;
+        let Some(snapshot) = EDITOR.get_mut().history.undo(current) else {
+            return Ok(());
+        };
+        Self::restore(&snapshot)
+    }
+
+    pub fn redo() -> Result<()> {
+        let current = Self::snapshot()?;
+        let Some(snapshot) = EDITOR.get_mut().history.redo(current) else {
+            return Ok(());
+        };
+        Self::restore(&snapshot)
+    }
+
+    pub fn delete_selected() -> Result<()> {
+        let mut sprite = Self::selected();
+        if !sprite.is_ok() {
+            return Ok(());
+        }
+        Self::record()?;
+        Self::select(Weak::default());
+        sprite.remove();
+        EDITOR.get_mut().changed();
+        Ok(())
+    }
+
+    /// Adds a copy of selected sprite next to it and selects the copy.
+    pub fn duplicate_selected() -> Result<()> {
+        let Some(mut document) = Self::selected_document()? else {
+            return Ok(());
+        };
+        Self::record()?;
+
+        let offset = Self::grid().unwrap_or(1.0);
+        document.position += Point::new(offset, -offset);
+        document.z_position = None;
+
+        let copy = SpriteRegistry::make(LevelManager::level_weak().deref_mut(), &document)?;
+        Self::select(copy);
+        Ok(())
+    }
+
+    pub fn selected_document() -> Result<Option<SpriteDocument>> {
+        let sprite = Self::selected();
+        if !sprite.is_ok() {
+            return Ok(None);
+        }
+        SpriteRegistry::document(sprite.deref()).map(Some)
+    }
+
+    /// Applies `document` to selected sprite. Use to change its shape or
+    /// physics properties. Sprite is recreated only when its kind or
+    /// collision detection changes.
+    pub fn update_selected(document: &SpriteDocument) -> Result<()> {
+        let mut selected = Self::selected();
+        if !selected.is_ok() {
+            bail!("No sprite selected");
+        }
+        Self::record()?;
+
+        if SpriteRegistry::update(selected.deref_mut(), document) {
+            EDITOR.get_mut().changed();
+            return Ok(());
+        }
+
+        Self::replace(selected, document)
+    }
+
+    pub fn save(path: impl AsRef<Path>) -> Result<()> {
+        LevelManager::save_level(path)
+    }
+
+    pub fn touch_began(pos: Point) -> bool {
+        if !Self::is_enabled() || LevelManager::no_level() {
+            return false;
+        }
+
+        let pos = LevelManager::convert_touch(pos);
+
+        let selected = Self::selected();
+
+        let gizmo = if selected.is_ok() {
+            Gizmo::at(
+                selected.position(),
+                selected.render_size(),
+                selected.rotation(),
+                Self::handle_size(),
+                pos,
+            )
+        } else {
+            None
+        };
+
+        let gizmo = if let Some(gizmo) = gizmo {
+            gizmo
+        } else {
+            let Some(sprite) = LevelManager::level().sprite_at(pos) else {
+                Self::select(Weak::default());
+                return true;
+            };
+            Self::select(sprite);
+            Gizmo::Move
+        };
+
+        let sprite = Self::selected();
+
+        EDITOR.get_mut().drag = Some(Drag {
+            gizmo,
+            from: pos,
+            position: sprite.position(),
+            rotation: sprite.rotation(),
+            shape: sprite.shape().clone(),
+            before: Self::snapshot().ok(),
+            moved: false,
+        });
+
+        true
+    }
+
+    pub fn touch_moved(pos: Point) -> bool {
+        if !Self::is_enabled() || LevelManager::no_level() {
+            return false;
+        }
+
+        let pos = LevelManager::convert_touch(pos);
+
+        let editor = EDITOR.get_mut();
+
+        let Some(drag) = &mut editor.drag else {
+            return false;
+        };
+
+        let mut sprite = editor.selected;
+
+        if !sprite.is_ok() {
+            editor.drag = None;
+            return false;
+        }
+
+        if !drag.moved {
+            drag.moved = true;
+            if let Some(before) = drag.before.take() {
+                editor.history.record(before);
+            }
+        }
+
+        // Snapping reads the editor again. Copy drag state so it isn't
+        // borrowed across these calls
+        let Drag {
+            gizmo,
+            from,
+            position,
+            rotation,
+            ..
+        } = *drag;
+
+        match gizmo {
+            Gizmo::Move => {
+                sprite.set_position(Self::snap(position + (pos - from)));
+            }
+            Gizmo::Rotate => {
+                let delta = rotation_delta(position, from, pos);
+                sprite.set_rotation(Self::snap_angle(rotation + delta));
+            }
+            Gizmo::Scale => {
+                let shape = drag.shape.scaled(scale(position, rotation, from, pos));
+                sprite.set_shape(shape);
+            }
+        }
+
+        EDITOR.get_mut().changed();
+
+        true
+    }
+
+    pub fn touch_ended(_pos: Point) -> bool {
+        if !Self::is_enabled() {
+            return false;
+        }
+
+        let Some(drag) = EDITOR.get_mut().drag.take() else {
+            return false;
+        };
+
+        let mut sprite = Self::selected();
+
+        if drag.moved && sprite.is_ok() {
+            stop(sprite.deref_mut());
+        }
+
+        true
+    }
+
+    /// Called when level changes.
+    pub(crate) fn reset() {
+        let editor = EDITOR.get_mut();
+        editor.selected = Weak::default();
+        editor.drag = None;
+        editor.history.clear();
+        editor.changed();
+    }
+
+    fn replace(sprite: Weak<dyn Sprite>, document: &SpriteDocument) -> Result<()> {
+        let new = Self::recreate(sprite, document)?;
+        EDITOR.get_mut().selected = Weak::default();
+        Self::select(new);
+        Ok(())
+    }
+
+    /// Makes a sprite from `document` in place of `sprite`. Components move
+    /// to the new sprite.
+    fn recreate(mut sprite: Weak<dyn Sprite>, document: &SpriteDocument) -> Result<Weak<dyn Sprite>> {
+        let mut level = LevelManager::level_weak();
+        let new = SpriteRegistry::make(level.deref_mut(), document)?;
+
+        if sprite.is_ok() {
+            level.components.remap(&HashMap::from([(sprite.addr(), new)]));
+            sprite.remove();
+        }
+
+        Ok(new)
+    }
+
+    fn snapshot() -> Result<EditorSnapshot> {
+        let sprites = LevelManager::level()
+            .sprites()
+            .iter()
+            .map(|sprite| {
+                Ok(SpriteState {
+                    sprite:   sprite.weak(),
+                    document: SpriteRegistry::document(sprite.deref())?,
+                })
+            })
+            .collect::<Result<_>>()?;
+
+        Ok(EditorSnapshot { sprites })
+    }
+
+    /// Brings sprites back to the snapshot in place. Sprites added after it
+    /// are removed. Removed ones and ones that can't be updated in place are
+    /// recreated from their documents and history is pointed to the new
+    /// sprites.
+    fn restore(snapshot: &EditorSnapshot) -> Result<()> {
+        let mut selected = Self::selected();
+
+        let editor = EDITOR.get_mut();
+        editor.drag = None;
+        editor.selected = Weak::default();
+
+        if selected.is_ok() {
+            selected.set_selected(false);
+        }
+
+        let mut level = LevelManager::level_weak();
+
+        let kept: HashSet<_> = snapshot.sprites.iter().map(|state| state.sprite.addr()).collect();
+
+        let added: Vec<_> = level
+            .sprites()
+            .iter()
+            .map(Own::addr)
+            .filter(|addr| !kept.contains(addr))
+            .collect();
+
+        for addr in added {
+            level.remove(addr);
+        }
+
+        let mut recreated = HashMap::new();
+
+        for state in &snapshot.sprites {
+            let mut sprite = state.sprite;
+
+            if sprite.is_ok() && SpriteRegistry::update(sprite.deref_mut(), &state.document) {
+                stop(sprite.deref_mut());
+                continue;
+            }
+
+            recreated.insert(sprite.addr(), Self::recreate(sprite, &state.document)?);
+        }
+
+        for entry in EDITOR.get_mut().history.entries_mut() {
+            for state in &mut entry.sprites {
+                if let Some(sprite) = recreated.get(&state.sprite.addr()) {
+                    state.sprite = *sprite;
+                }
+            }
+        }
+
+        let mut selected = recreated.get(&selected.addr()).copied().unwrap_or(selected);
+
+        if !selected.is_ok() {
+            selected = Weak::default();
+        }
+
+        Self::select(selected);
+        EDITOR.get_mut().changed();
+
+        Ok(())
+    }
+
+    fn changed(&mut self) {
+        self.revision += 1;
+    }
+}
+
+/// Drops velocity gained while the sprite was moved by the editor.
+fn stop(sprite: &mut dyn Sprite) {
+    if sprite.rigid_handle().is_some() {
+        let body = sprite.rigid_body_mut();
+        body.set_linvel(Vector2::zeros(), true);
+        body.set_angvel(0.0, true);
+    }
+}
@@ -0,0 +1,6 @@
+mod gizmo;
+mod history;
+mod level_editor;
+
+pub use gizmo::Gizmo;
+pub use level_editor::LevelEditor;
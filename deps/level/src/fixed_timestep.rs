@@ -0,0 +1,183 @@
+/// Splits variable frame time into fixed physics steps so simulation speed
+/// doesn't depend on frame rate. See [`crate::LevelBase::timestep`].
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct FixedTimestep {
+    /// Duration of one physics step in seconds.
+    pub step:          f32,
+    /// Physics pipeline runs this many times per step with `step / substeps`
+    /// delta. More substeps make fast objects and stacks more stable.
+    pub substeps:      u16,
+    /// Limits steps per frame so long frames don't stall the game trying to
+    /// catch up.
+    pub max_steps:     u32,
+    /// 0.5 for slow motion, 0 to pause. Applies in deterministic mode too.
+    pub time_scale:    f32,
+    /// Advance by one step of game time per frame ignoring real frame time.
+    /// Same inputs produce the same simulation. With `time_scale` 0.5 a step
+    /// is taken every other frame.
+    pub deterministic: bool,
+
+    accumulator: f32,
+    step_count:  u64,
+}
+
+impl FixedTimestep {
+    pub const DEFAULT: Self = Self {
+        step:          1.0 / 60.0,
+        substeps:      1,
+        max_steps:     5,
+        time_scale:    1.0,
+        deterministic: false,
+        accumulator:   0.0,
+        step_count:    0,
+    };
+
+    pub const fn step(mut self, step: f32) -> Self {
+        self.step = step;
+        self
+    }
+
+    pub const fn substeps(mut self, substeps: u16) -> Self {
+        self.substeps = substeps;
+        self
+    }
+
+    pub const fn deterministic(mut self, deterministic: bool) -> Self {
+        self.deterministic = deterministic;
+        self
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.time_scale <= 0.0
+    }
+
+    pub fn pause(&mut self) {
+        self.time_scale = 0.0;
+    }
+
+    pub fn resume(&mut self) {
+        self.time_scale = 1.0;
+    }
+
+    /// Delta time of single physics pipeline step.
+    pub fn substep(&self) -> f32 {
+        self.step / f32::from(self.substeps.max(1))
+    }
+
+    /// How far rendering is between previous and current physics step. 0 is
+    /// previous step, 1 is current one.
+    pub fn alpha(&self) -> f32 {
+        if self.deterministic {
+            return 1.0;
+        }
+        (self.accumulator / self.step).clamp(0.0, 1.0)
+    }
+
+    /// Steps simulated since level start. Input recordings are indexed by
+    /// it.
+    pub fn step_count(&self) -> u64 {
+        self.step_count
+    }
+
+    /// Game time passed during the frame. One step scaled by `time_scale` in
+    /// deterministic mode so it doesn't depend on real frame time either.
+    pub fn frame_delta(&self, frame_time: f32) -> f32 {
+        let frame_time = if self.deterministic { self.step } else { frame_time };
+        frame_time * self.time_scale
+    }
+
+    pub fn reset(&mut self) {
+        self.accumulator = 0.0;
+    }
+
+    /// Adds frame time and returns how many steps to simulate.
+    pub(crate) fn advance(&mut self, frame_time: f32) -> u32 {
+        if self.is_paused() || self.step <= 0.0 {
+            return 0;
+        }
+
+        self.accumulator += self.frame_delta(frame_time);
+
+        let mut steps = 0;
+
+        while self.accumulator >= self.step && steps < self.max_steps {
+            self.accumulator -= self.step;
+            steps += 1;
+        }
+
+        self.step_count += u64::from(steps);
+
+        if steps == self.max_steps {
+            // Drop time we couldn't catch up with
+            self.accumulator = self.accumulator.min(self.step);
+        }
+
+        steps
+    }
+}
+
+impl Default for FixedTimestep {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::FixedTimestep;
+
+    #[test]
+    fn advance() {
+        let mut timestep = FixedTimestep::DEFAULT.step(0.1);
+
+        assert_eq!(timestep.advance(0.05), 0);
+        assert!((timestep.alpha() - 0.5).abs() < 0.001);
+        assert_eq!(timestep.advance(0.06), 1);
+        assert_eq!(timestep.advance(0.25), 2);
+
+        timestep.time_scale = 0.5;
+        assert_eq!(timestep.advance(0.2), 1);
+
+        timestep.pause();
+        assert_eq!(timestep.advance(10.0), 0);
+
+        timestep.resume();
+        timestep.reset();
+        assert_eq!(timestep.advance(10.0), 5);
+        assert_eq!(timestep.advance(0.0), 1);
+        assert_eq!(timestep.advance(0.0), 0);
+        assert_eq!(timestep.step_count(), 10);
+    }
+
+    #[test]
+    fn deterministic() {
+        let mut timestep = FixedTimestep::DEFAULT.deterministic(true).substeps(4);
+
+        assert_eq!(timestep.advance(0.0), 1);
+        assert_eq!(timestep.advance(1.0), 1);
+        assert!((timestep.alpha() - 1.0).abs() < f32::EPSILON);
+        assert!((timestep.substep() - 1.0 / 240.0).abs() < f32::EPSILON);
+        assert!((timestep.frame_delta(1.0) - 1.0 / 60.0).abs() < f32::EPSILON);
+
+        timestep.time_scale = 0.5;
+        assert!((timestep.frame_delta(1.0) - 1.0 / 120.0).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn deterministic_time_scale() {
+        let mut timestep = FixedTimestep::DEFAULT.deterministic(true);
+        timestep.time_scale = 0.5;
+
+        // Frame time doesn't matter, every other frame takes a step
+        let steps = [0.0, 1.0, 0.5, 0.0, 10.0, 0.1].map(|frame| timestep.advance(frame));
+        assert_eq!(steps, [0, 1, 0, 1, 0, 1]);
+        assert_eq!(timestep.step_count(), 3);
+
+        timestep.time_scale = 2.0;
+        assert_eq!(timestep.advance(0.0), 2);
+
+        timestep.pause();
+        assert_eq!(timestep.advance(1.0), 0);
+        assert_eq!(timestep.step_count(), 5);
+    }
+}
@@ -0,0 +1,89 @@
+use anyhow::{Result, bail};
+use rapier2d::dynamics::{ImpulseJoint, ImpulseJointHandle, JointAxis};
+use refs::Weak;
+use vents::Event;
+
+use crate::{LevelManager, Motor, Sprite};
+
+/// Link between two sprites. Created with
+/// [`crate::LevelCreation::add_joint`]. Removed automatically when one of the
+/// sprites is removed.
+pub struct Joint {
+    pub(crate) handle:      ImpulseJointHandle,
+    pub(crate) a:           Weak<dyn Sprite>,
+    pub(crate) b:           Weak<dyn Sprite>,
+    pub(crate) break_force: Option<f32>,
+    pub(crate) motor_axis:  Option<JointAxis>,
+
+    /// Triggered when breakable joint breaks. Joint is removed right after.
+    pub on_break: Event,
+}
+
+impl Joint {
+    pub fn sprites(&self) -> (Weak<dyn Sprite>, Weak<dyn Sprite>) {
+        (self.a, self.b)
+    }
+
+    pub fn break_force(&self) -> Option<f32> {
+        self.break_force
+    }
+
+    pub fn set_break_force(&mut self, force: impl Into<Option<f32>>) {
+        self.break_force = force.into();
+    }
+
+    /// Magnitude of linear impulse applied to keep the joint together during
+    /// last physics step.
+    pub fn impulse(&self) -> f32 {
+        linear_impulse(self.joint())
+    }
+
+    /// Replaces motor settings of revolute or prismatic joint. Other joints
+    /// don't have motors and return an error.
+    pub fn set_motor(&mut self, motor: Motor) -> Result<()> {
+        let Some(axis) = self.motor_axis else {
+            bail!("This joint doesn't support motors");
+        };
+        motor.apply(&mut self.joint_mut().data, axis);
+        self.wake_up();
+        Ok(())
+    }
+
+    pub fn remove(&mut self) {
+        LevelManager::physics().remove_joint(self.handle);
+    }
+
+    fn joint(&self) -> &ImpulseJoint {
+        LevelManager::physics()
+            .impulse_joints
+            .get(self.handle)
+            .expect("Joint was removed")
+    }
+
+    fn joint_mut(&mut self) -> &mut ImpulseJoint {
+        LevelManager::physics()
+            .impulse_joints
+            .get_mut(self.handle)
+            .expect("Joint was removed")
+    }
+
+    /// Joint changes don't wake sleeping bodies by themselves.
+    fn wake_up(&self) {
+        let (body1, body2) = {
+            let joint = self.joint();
+            (joint.body1, joint.body2)
+        };
+
+        let bodies = &mut LevelManager::physics().sets.rigid_bodies;
+
+        for handle in [body1, body2] {
+            if let Some(body) = bodies.get_mut(handle) {
+                body.wake_up(true);
+            }
+        }
+    }
+}
+
+pub(crate) fn linear_impulse(joint: &ImpulseJoint) -> f32 {
+    joint.impulses.x.hypot(joint.impulses.y)
+}
@@ -0,0 +1,361 @@
+use gm::flat::Point;
+use rapier2d::{
+    dynamics::{
+        FixedJointBuilder, GenericJoint, JointAxis, PrismaticJointBuilder, RevoluteJointBuilder,
+        RopeJointBuilder, SpringJointBuilder,
+    },
+    na::{Point2, UnitVector2, Vector2},
+};
+
+/// Settings shared by all joints.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct JointSettings {
+    /// Anchor on first sprite relative to its center.
+    pub anchor_a:          Point,
+    /// Anchor on second sprite relative to its center.
+    pub anchor_b:          Point,
+    /// Let linked sprites collide with each other.
+    pub collide_connected: bool,
+    /// Joint breaks when force applied to keep it together exceeds this
+    /// value. See [`crate::Joint::on_break`].
+    pub break_force:       Option<f32>,
+}
+
+impl JointSettings {
+    pub const DEFAULT: Self = Self {
+        anchor_a:          Point::new(0.0, 0.0),
+        anchor_b:          Point::new(0.0, 0.0),
+        collide_connected: false,
+        break_force:       None,
+    };
+}
+
+impl Default for JointSettings {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+/// Drives revolute or prismatic joint. Angular for revolute, linear for
+/// prismatic.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Motor {
+    pub target_velocity: f32,
+    pub target_position: f32,
+    pub stiffness:       f32,
+    pub damping:         f32,
+    pub max_force:       f32,
+}
+
+impl Motor {
+    /// Keeps velocity at `target`. Higher `factor` reaches it faster.
+    pub const fn velocity(target: f32, factor: f32) -> Self {
+        Self {
+            target_velocity: target,
+            target_position: 0.0,
+            stiffness:       0.0,
+            damping:         factor,
+            max_force:       f32::MAX,
+        }
+    }
+
+    /// Moves joint to `target` like a spring.
+    pub const fn position(target: f32, stiffness: f32, damping: f32) -> Self {
+        Self {
+            target_velocity: 0.0,
+            target_position: target,
+            stiffness,
+            damping,
+            max_force: f32::MAX,
+        }
+    }
+
+    pub const fn max_force(mut self, max_force: f32) -> Self {
+        self.max_force = max_force;
+        self
+    }
+
+    pub(crate) fn apply(&self, joint: &mut GenericJoint, axis: JointAxis) {
+        joint.set_motor(
+            axis,
+            self.target_position,
+            self.target_velocity,
+            self.stiffness,
+            self.damping,
+        );
+        joint.set_motor_max_force(axis, self.max_force);
+    }
+}
+
+/// Describes a joint for [`crate::LevelCreation::add_joint`].
+pub trait JointBuilder {
+    fn settings(&self) -> &JointSettings;
+
+    fn settings_mut(&mut self) -> &mut JointSettings;
+
+    /// Rapier joint with anchors, limits and motor applied.
+    fn to_generic(&self) -> GenericJoint;
+
+    /// Axis driven by [`Motor`]. `None` if joint doesn't support motors.
+    fn motor_axis(&self) -> Option<JointAxis> {
+        None
+    }
+
+    fn anchors(mut self, a: impl Into<Point>, b: impl Into<Point>) -> Self
+    where Self: Sized {
+        let settings = self.settings_mut();
+        settings.anchor_a = a.into();
+        settings.anchor_b = b.into();
+        self
+    }
+
+    fn collide_connected(mut self, collide: bool) -> Self
+    where Self: Sized {
+        self.settings_mut().collide_connected = collide;
+        self
+    }
+
+    fn breakable(mut self, force: f32) -> Self
+    where Self: Sized {
+        self.settings_mut().break_force = Some(force);
+        self
+    }
+}
+
+/// Hinge. Sprites rotate freely around shared anchor.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct RevoluteJoint {
+    pub settings: JointSettings,
+    /// Min and max angle in radians.
+    pub limits:   Option<[f32; 2]>,
+    pub motor:    Option<Motor>,
+}
+
+impl RevoluteJoint {
+    pub const fn new() -> Self {
+        Self {
+            settings: JointSettings::DEFAULT,
+            limits:   None,
+            motor:    None,
+        }
+    }
+
+    pub const fn limits(mut self, min: f32, max: f32) -> Self {
+        self.limits = Some([min, max]);
+        self
+    }
+
+    pub const fn motor(mut self, motor: Motor) -> Self {
+        self.motor = Some(motor);
+        self
+    }
+}
+
+impl JointBuilder for RevoluteJoint {
+    fn settings(&self) -> &JointSettings {
+        &self.settings
+    }
+
+    fn settings_mut(&mut self) -> &mut JointSettings {
+        &mut self.settings
+    }
+
+    fn to_generic(&self) -> GenericJoint {
+        let mut builder = RevoluteJointBuilder::new()
+            .local_anchor1(point(self.settings.anchor_a))
+            .local_anchor2(point(self.settings.anchor_b))
+            .contacts_enabled(self.settings.collide_connected);
+
+        if let Some(limits) = self.limits {
+            builder = builder.limits(limits);
+        }
+
+        let mut joint: GenericJoint = builder.into();
+
+        if let Some(motor) = &self.motor {
+            motor.apply(&mut joint, JointAxis::AngX);
+        }
+
+        joint
+    }
+
+    fn motor_axis(&self) -> Option<JointAxis> {
+        Some(JointAxis::AngX)
+    }
+}
+
+/// Slider. Sprites move relative to each other only along `axis`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct PrismaticJoint {
+    pub settings: JointSettings,
+    /// Direction in first sprite space.
+    pub axis:     Point,
+    /// Min and max offset along the axis.
+    pub limits:   Option<[f32; 2]>,
+    pub motor:    Option<Motor>,
+}
+
+impl PrismaticJoint {
+    pub const fn new(axis: Point) -> Self {
+        Self {
+            settings: JointSettings::DEFAULT,
+            axis,
+            limits: None,
+            motor: None,
+        }
+    }
+
+    pub const fn limits(mut self, min: f32, max: f32) -> Self {
+        self.limits = Some([min, max]);
+        self
+    }
+
+    pub const fn motor(mut self, motor: Motor) -> Self {
+        self.motor = Some(motor);
+        self
+    }
+}
+
+impl JointBuilder for PrismaticJoint {
+    fn settings(&self) -> &JointSettings {
+        &self.settings
+    }
+
+    fn settings_mut(&mut self) -> &mut JointSettings {
+        &mut self.settings
+    }
+
+    fn to_generic(&self) -> GenericJoint {
+        let axis = UnitVector2::new_normalize(Vector2::new(self.axis.x, self.axis.y));
+
+        let mut builder = PrismaticJointBuilder::new(axis)
+            .local_anchor1(point(self.settings.anchor_a))
+            .local_anchor2(point(self.settings.anchor_b))
+            .contacts_enabled(self.settings.collide_connected);
+
+        if let Some(limits) = self.limits {
+            builder = builder.limits(limits);
+        }
+
+        let mut joint: GenericJoint = builder.into();
+
+        if let Some(motor) = &self.motor {
+            motor.apply(&mut joint, JointAxis::LinX);
+        }
+
+        joint
+    }
+
+    fn motor_axis(&self) -> Option<JointAxis> {
+        Some(JointAxis::LinX)
+    }
+}
+
+/// Glues sprites together keeping their relative position and rotation.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct FixedJoint {
+    pub settings: JointSettings,
+}
+
+impl FixedJoint {
+    pub const fn new() -> Self {
+        Self {
+            settings: JointSettings::DEFAULT,
+        }
+    }
+}
+
+impl JointBuilder for FixedJoint {
+    fn settings(&self) -> &JointSettings {
+        &self.settings
+    }
+
+    fn settings_mut(&mut self) -> &mut JointSettings {
+        &mut self.settings
+    }
+
+    fn to_generic(&self) -> GenericJoint {
+        FixedJointBuilder::new()
+            .local_anchor1(point(self.settings.anchor_a))
+            .local_anchor2(point(self.settings.anchor_b))
+            .contacts_enabled(self.settings.collide_connected)
+            .into()
+    }
+}
+
+/// Keeps anchors no further than `max_distance` from each other. Use chain
+/// of rope joints for ropes and chains.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct RopeJoint {
+    pub settings:     JointSettings,
+    pub max_distance: f32,
+}
+
+impl RopeJoint {
+    pub const fn new(max_distance: f32) -> Self {
+        Self {
+            settings: JointSettings::DEFAULT,
+            max_distance,
+        }
+    }
+}
+
+impl JointBuilder for RopeJoint {
+    fn settings(&self) -> &JointSettings {
+        &self.settings
+    }
+
+    fn settings_mut(&mut self) -> &mut JointSettings {
+        &mut self.settings
+    }
+
+    fn to_generic(&self) -> GenericJoint {
+        RopeJointBuilder::new(self.max_distance)
+            .local_anchor1(point(self.settings.anchor_a))
+            .local_anchor2(point(self.settings.anchor_b))
+            .contacts_enabled(self.settings.collide_connected)
+            .into()
+    }
+}
+
+/// Pulls anchors towards `rest_length` distance.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct SpringJoint {
+    pub settings:    JointSettings,
+    pub rest_length: f32,
+    pub stiffness:   f32,
+    pub damping:     f32,
+}
+
+impl SpringJoint {
+    pub const fn new(rest_length: f32, stiffness: f32, damping: f32) -> Self {
+        Self {
+            settings: JointSettings::DEFAULT,
+            rest_length,
+            stiffness,
+            damping,
+        }
+    }
+}
+
+impl JointBuilder for SpringJoint {
+    fn settings(&self) -> &JointSettings {
+        &self.settings
+    }
+
+    fn settings_mut(&mut self) -> &mut JointSettings {
+        &mut self.settings
+    }
+
+    fn to_generic(&self) -> GenericJoint {
+        SpringJointBuilder::new(self.rest_length, self.stiffness, self.damping)
+            .local_anchor1(point(self.settings.anchor_a))
+            .local_anchor2(point(self.settings.anchor_b))
+            .contacts_enabled(self.settings.collide_connected)
+            .into()
+    }
+}
+
+pub(crate) fn point(point: Point) -> Point2<f32> {
+    Point2::new(point.x, point.y)
+}
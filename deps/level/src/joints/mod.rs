@@ -0,0 +1,11 @@
+mod joint;
+mod joint_builder;
+mod ragdoll;
+
+pub use joint::Joint;
+pub(crate) use joint::linear_impulse;
+pub(crate) use joint_builder::point;
+pub use joint_builder::{
+    FixedJoint, JointBuilder, JointSettings, Motor, PrismaticJoint, RevoluteJoint, RopeJoint, SpringJoint,
+};
+pub use ragdoll::Ragdoll;
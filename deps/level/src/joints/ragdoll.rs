@@ -0,0 +1,95 @@
+use std::f32::consts::{FRAC_PI_2, PI};
+
+use gm::flat::{Point, Shape};
+use refs::Weak;
+
+use crate::{Body, Joint, JointBuilder, LevelCreation, RevoluteJoint, Sprite};
+
+/// Humanoid made of bodies linked with limited revolute joints. Created with
+/// [`crate::LevelCreation::add_ragdoll`].
+pub struct Ragdoll {
+    pub head:   Weak<Body>,
+    pub torso:  Weak<Body>,
+    /// Left and right.
+    pub arms:   [Weak<Body>; 2],
+    /// Left and right.
+    pub legs:   [Weak<Body>; 2],
+    pub joints: Vec<Weak<Joint>>,
+}
+
+impl Ragdoll {
+    pub(crate) fn make(level: &mut (impl LevelCreation + ?Sized), position: Point, height: f32) -> Self {
+        let h = height;
+        let at = |x: f32, y: f32| Point::new(position.x + x * h, position.y + y * h);
+
+        let torso = level.make_sprite::<Body>(Shape::rect(0.2 * h, 0.35 * h), at(0.0, 0.0));
+        let head = level.make_sprite::<Body>(Shape::Circle(0.1 * h), at(0.0, 0.275));
+
+        let arms = [-1.0, 1.0]
+            .map(|side| level.make_sprite::<Body>(Shape::rect(0.07 * h, 0.4 * h), at(side * 0.14, -0.025)));
+        let legs = [-1.0, 1.0]
+            .map(|side| level.make_sprite::<Body>(Shape::rect(0.08 * h, 0.45 * h), at(side * 0.05, -0.4)));
+
+        let mut joints = vec![
+            level.add_joint(
+                torso,
+                head,
+                RevoluteJoint::new()
+                    .limits(-0.5, 0.5)
+                    .anchors((0.0, 0.175 * h), (0.0, -0.1 * h)),
+            ),
+        ];
+
+        for (side, arm) in [-1.0, 1.0].into_iter().zip(arms) {
+            joints.push(
+                level.add_joint(
+                    torso,
+                    arm,
+                    RevoluteJoint::new()
+                        .limits(-PI, PI)
+                        .anchors((side * 0.14 * h, 0.155 * h), (0.0, 0.18 * h)),
+                ),
+            );
+        }
+
+        for (side, leg) in [-1.0, 1.0].into_iter().zip(legs) {
+            joints.push(
+                level.add_joint(
+                    torso,
+                    leg,
+                    RevoluteJoint::new()
+                        .limits(-FRAC_PI_2, FRAC_PI_2)
+                        .anchors((side * 0.05 * h, -0.175 * h), (0.0, 0.225 * h)),
+                ),
+            );
+        }
+
+        Self {
+            head,
+            torso,
+            arms,
+            legs,
+            joints,
+        }
+    }
+
+    pub fn parts(&self) -> [Weak<Body>; 6] {
+        [
+            self.head,
+            self.torso,
+            self.arms[0],
+            self.arms[1],
+            self.legs[0],
+            self.legs[1],
+        ]
+    }
+
+    /// Removes all parts. Joints are removed with them.
+    pub fn remove(self) {
+        for mut part in self.parts() {
+            if part.is_ok() {
+                part.remove();
+            }
+        }
+    }
+}
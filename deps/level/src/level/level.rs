@@ -7,16 +7,14 @@ use gm::{
 use refs::{AsAny, Own, Weak};
 
 use super::LevelInternal;
-use crate::{Body, LevelBase, LevelCreation, LevelManager, Sprite, SpriteTemplates};
+use crate::{Body, LevelBase, LevelCreation, LevelManager, RayHit, Sprite, SpriteFilter, SpriteTemplates};
 
 pub trait Level: AsAny + Deref<Target = LevelBase> + DerefMut + LevelInternal {
+    /// Touch began on the level. Triggers [`crate::LevelBase::on_tap`] with
+    /// position in level coordinates.
     fn add_touch(&mut self, pos: Point) -> bool {
         let pos = LevelManager::convert_touch(pos);
-
-        dbg!(pos);
-
-        self.add_box(pos);
-
+        self.on_tap.trigger(pos);
         true
     }
 
@@ -25,7 +23,13 @@ pub trait Level: AsAny + Deref<Target = LevelBase> + DerefMut + LevelInternal {
         bx.set_color(Color::random());
     }
 
+    /// Shape accurate when physics is enabled. Sprites without colliders are
+    /// checked by their bounds.
     fn sprite_at(&self, point: Point) -> Option<Weak<dyn Sprite>> {
+        if let Some(sprite) = self.sprites_at_point(point, &SpriteFilter::ALL).first() {
+            return (*sprite).into();
+        }
+
         for sprite in &self.sprites {
             if sprite.contains(point) {
                 return sprite.weak().into();
@@ -34,6 +38,52 @@ pub trait Level: AsAny + Deref<Target = LevelBase> + DerefMut + LevelInternal {
         None
     }
 
+    /// First sprite on segment from `from` to `to`. Requires physics.
+    fn cast_ray(&self, from: Point, to: Point, filter: &SpriteFilter) -> Option<RayHit> {
+        self.physics.as_ref()?.cast_ray(&self.sprites, from, to, filter)
+    }
+
+    /// All sprites on segment from `from` to `to` sorted by distance.
+    fn cast_ray_all(&self, from: Point, to: Point, filter: &SpriteFilter) -> Vec<RayHit> {
+        self.physics
+            .as_ref()
+            .map(|physics| physics.cast_ray_all(&self.sprites, from, to, filter))
+            .unwrap_or_default()
+    }
+
+    /// Sprites whose colliders contain `point`.
+    fn sprites_at_point(&self, point: Point, filter: &SpriteFilter) -> Vec<Weak<dyn Sprite>> {
+        self.physics
+            .as_ref()
+            .map(|physics| physics.sprites_at_point(&self.sprites, point, filter))
+            .unwrap_or_default()
+    }
+
+    /// Sprites intersecting axis aligned box from `min` to `max`.
+    fn sprites_in_aabb(&self, min: Point, max: Point, filter: &SpriteFilter) -> Vec<Weak<dyn Sprite>> {
+        let size = max - min;
+        self.sprites_in_shape(
+            &Shape::Rect((size.x.abs(), size.y.abs()).into()),
+            (min + max) / 2.0,
+            0.0,
+            filter,
+        )
+    }
+
+    /// Sprites intersecting `shape` placed at `position` with `rotation`.
+    fn sprites_in_shape(
+        &self,
+        shape: &Shape,
+        position: Point,
+        rotation: f32,
+        filter: &SpriteFilter,
+    ) -> Vec<Weak<dyn Sprite>> {
+        self.physics
+            .as_ref()
+            .map(|physics| physics.sprites_in_shape(&self.sprites, shape, position, rotation, filter))
+            .unwrap_or_default()
+    }
+
     fn sprites(&self) -> &[Own<dyn Sprite>] {
         &self.sprites
     }
@@ -1,13 +1,15 @@
-use std::ops::Deref;
-
 use educe::Educe;
 use gm::flat::Point;
+use rand::{SeedableRng, rngs::StdRng};
 use rapier2d::na::Vector2;
 use refs::{Own, Weak};
 use vents::Event;
 use wgpu_wrapper::image::Image;
 
-use crate::{Level, LevelManager, Sprite, level::level_physics::LevelPhysics};
+use crate::{
+    Components, FixedTimestep, Level, LevelEditor, LevelManager, Sprite, SystemStage, ecs::Systems,
+    level::level_physics::LevelPhysics,
+};
 
 #[derive(Educe)]
 #[educe(Default)]
@@ -25,6 +27,20 @@ pub struct LevelBase {
     pub(crate) last_z_pos: f32,
 
     pub(crate) physics: Option<LevelPhysics>,
+
+    /// Physics step, substeps, time scale and deterministic mode.
+    pub timestep: FixedTimestep,
+
+    /// Seeded with [`LevelManager::seed`] before setup. Use it instead of
+    /// global random so replays reproduce the level.
+    #[educe(Default = StdRng::seed_from_u64(0))]
+    pub(crate) rng: StdRng,
+
+    /// Health, AI, pickups and other data attached to sprites. Processed by
+    /// systems. See [`LevelBase::add_system`].
+    pub components: Components,
+
+    pub(crate) systems: Systems,
 }
 
 impl LevelBase {
@@ -33,27 +49,51 @@ impl LevelBase {
         self.physics = LevelPhysics::default().into();
     }
 
+    /// Simulates as many fixed steps as fit in `frame_time`. See
+    /// [`LevelBase::timestep`]. Paused while [`LevelEditor`] is enabled.
     pub fn update_physics(&mut self, frame_time: f32) {
-        if let Some(physics) = self.physics.as_mut() {
-            physics.update_physics(&self.sprites, frame_time);
+        if LevelEditor::is_enabled() {
+            return;
         }
+
+        let Some(physics) = self.physics.as_mut() else {
+            return;
+        };
+
+        for _ in 0..self.timestep.advance(frame_time) {
+            physics.update_physics(&self.sprites, self.timestep.substep(), self.timestep.substeps);
+        }
+    }
+
+    pub fn rng(&mut self) -> &mut StdRng {
+        &mut self.rng
+    }
+
+    /// Runs `system` every frame at `stage` with level and scaled frame time.
+    pub fn add_system(&mut self, stage: SystemStage, system: impl FnMut(&mut dyn Level, f32) + 'static) {
+        self.systems.add(stage, Box::new(system));
+    }
+
+    pub fn clear_systems(&mut self) {
+        self.systems.clear();
     }
 
     pub(crate) fn remove(&mut self, sprite: usize) {
         let index = self.sprites.iter().position(|a| a.addr() == sprite).unwrap();
 
-        let sprite = self.sprites[index].deref();
+        self.components.remove_sprite(sprite);
 
         if let Some(physics) = self.physics.as_mut() {
-            physics.remove(sprite);
+            physics.remove(&self.sprites[index]);
         }
         self.sprites.remove(index);
     }
 
     pub fn remove_all_sprites(&mut self) {
+        self.components.clear();
         if let Some(physics) = &mut self.physics {
             for sprite in self.sprites.drain(..) {
-                physics.remove(sprite.deref());
+                physics.remove(&sprite);
             }
         } else {
             self.sprites.clear();
@@ -1,12 +1,22 @@
 use gm::flat::{Point, Rect, Shape};
 use refs::{Own, Weak};
 
-use crate::{Banner, Level, LevelManager, Sprite};
+use crate::{Banner, Joint, JointBuilder, Level, LevelManager, Ragdoll, Sprite};
 
 pub trait LevelCreation {
     fn add_sprite<S: 'static + Sprite>(&mut self, sprite: Own<S>) -> Weak<S>;
     fn make_sprite<S: 'static + Sprite>(&mut self, _: Shape, _: impl Into<Point>) -> Weak<S>;
     fn make_rect(&mut self, rect: impl Into<Rect>) -> Weak<Banner>;
+    /// Links two sprites. At least one of them needs a rigid body. Sprites
+    /// without one are treated as fixed in place.
+    fn add_joint(
+        &mut self,
+        a: Weak<dyn Sprite>,
+        b: Weak<dyn Sprite>,
+        joint: impl JointBuilder,
+    ) -> Weak<Joint>;
+    /// Humanoid of `height` standing at `position` with torso center there.
+    fn add_ragdoll(&mut self, position: impl Into<Point>, height: f32) -> Ragdoll;
 }
 
 impl<T: ?Sized + Level> LevelCreation for T {
@@ -27,4 +37,20 @@ impl<T: ?Sized + Level> LevelCreation for T {
         let rect = rect.into();
         self.make_sprite::<Banner>(Shape::Rect(rect.size), rect.origin)
     }
+
+    fn add_joint(
+        &mut self,
+        a: Weak<dyn Sprite>,
+        b: Weak<dyn Sprite>,
+        joint: impl JointBuilder,
+    ) -> Weak<Joint> {
+        self.physics
+            .as_mut()
+            .expect("This level has no physics enabled")
+            .add_joint(a, b, &joint)
+    }
+
+    fn add_ragdoll(&mut self, position: impl Into<Point>, height: f32) -> Ragdoll {
+        Ragdoll::make(self, position.into(), height)
+    }
 }
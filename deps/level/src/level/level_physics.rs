@@ -1,20 +1,34 @@
-use std::collections::HashMap;
+use std::{cell::RefCell, collections::HashMap, ops::Deref};
 
 use educe::Educe;
+use gm::flat::Point;
 use rapier2d::{
-    dynamics::{CCDSolver, ImpulseJointSet, IntegrationParameters, IslandManager, MultibodyJointSet},
-    geometry::{BroadPhaseMultiSap, ColliderHandle, CollisionEvent, NarrowPhase},
-    na::Vector2,
-    pipeline::PhysicsPipeline,
+    dynamics::{
+        CCDSolver, ImpulseJointHandle, ImpulseJointSet, IntegrationParameters, IslandManager, JointAxis,
+        MultibodyJointSet, RigidBodyBuilder, RigidBodyHandle,
+    },
+    geometry::{BroadPhaseMultiSap, ColliderHandle, ContactPair, NarrowPhase},
+    na::{Isometry2, Vector2},
+    pipeline::{PhysicsPipeline, QueryPipeline},
 };
 use refs::{Own, Weak};
+use vents::Event;
 
-use crate::{Sprite, event_handler::EventHandler, sets::Sets};
+use crate::{
+    Collision, Contact, ContactManifold, Joint, JointBuilder, Sprite,
+    event_handler::EventHandler,
+    joints::{linear_impulse, point},
+    one_way_platforms::OneWayPlatforms,
+    sets::Sets,
+};
 
 #[derive(Educe)]
 #[educe(Default)]
 pub struct LevelPhysics {
     pub(crate) colliding_sprites: HashMap<ColliderHandle, Weak<dyn Sprite>>,
+    /// Sprites removed since last step. Stop events of their colliders arrive
+    /// after they are gone.
+    removed_sprites:              HashMap<ColliderHandle, Weak<dyn Sprite>>,
 
     pub(crate) sets: Sets,
 
@@ -25,20 +39,171 @@ pub struct LevelPhysics {
 
     physics_pipeline: PhysicsPipeline,
 
+    island_manager:            IslandManager,
+    broad_phase:               BroadPhaseMultiSap,
+    narrow_phase:              NarrowPhase,
+    pub(crate) impulse_joints: ImpulseJointSet,
+    multibody_joints:          MultibodyJointSet,
+    ccd_solver:                CCDSolver,
+
+    /// Updated lazily before each spatial query.
+    pub(crate) query_pipeline: RefCell<QueryPipeline>,
+
+    pub(crate) joints: HashMap<ImpulseJointHandle, Own<Joint>>,
+    /// Fixed body joints attach to when sprite has no rigid body.
+    ground:            Option<RigidBodyHandle>,
+
+    pub(crate) one_way_platforms: OneWayPlatforms,
+
+    pub(crate) previous_poses: HashMap<RigidBodyHandle, Isometry2<f32>>,
+
+    pub(crate) events: EventHandler,
+}
+
+/// Simulation state of [`LevelPhysics`]. See [`crate::LevelSnapshot`].
+#[derive(Clone)]
+pub(crate) struct PhysicsSnapshot {
+    sets:             Sets,
+    gravity:          Vector2<f32>,
     island_manager:   IslandManager,
     broad_phase:      BroadPhaseMultiSap,
     narrow_phase:     NarrowPhase,
     impulse_joints:   ImpulseJointSet,
     multibody_joints: MultibodyJointSet,
     ccd_solver:       CCDSolver,
+    joints:           Vec<JointSnapshot>,
+    ground:           Option<RigidBodyHandle>,
+}
 
-    pub(crate) events: EventHandler,
+#[derive(Clone)]
+struct JointSnapshot {
+    handle:      ImpulseJointHandle,
+    a:           Weak<dyn Sprite>,
+    b:           Weak<dyn Sprite>,
+    break_force: Option<f32>,
+    motor_axis:  Option<JointAxis>,
 }
 
 impl LevelPhysics {
-    pub fn update_physics(&mut self, sprites: &[Own<dyn Sprite>], frame_time: f32) {
-        self.integration_parameters.dt = frame_time;
+    pub(crate) fn snapshot(&self) -> PhysicsSnapshot {
+        PhysicsSnapshot {
+            sets:             self.sets.clone(),
+            gravity:          self.gravity,
+            island_manager:   self.island_manager.clone(),
+            broad_phase:      self.broad_phase.clone(),
+            narrow_phase:     self.narrow_phase.clone(),
+            impulse_joints:   self.impulse_joints.clone(),
+            multibody_joints: self.multibody_joints.clone(),
+            ccd_solver:       self.ccd_solver.clone(),
+            joints:           self
+                .joints
+                .values()
+                .map(|joint| JointSnapshot {
+                    handle:      joint.handle,
+                    a:           joint.a,
+                    b:           joint.b,
+                    break_force: joint.break_force,
+                    motor_axis:  joint.motor_axis,
+                })
+                .collect(),
+            ground:           self.ground,
+        }
+    }
+
+    /// Replaces simulation state. Sprites added after the snapshot must be
+    /// removed before this call.
+    pub(crate) fn restore(&mut self, snapshot: &PhysicsSnapshot) {
+        self.sets = snapshot.sets.clone();
+        self.gravity = snapshot.gravity;
+        self.island_manager = snapshot.island_manager.clone();
+        self.broad_phase = snapshot.broad_phase.clone();
+        self.narrow_phase = snapshot.narrow_phase.clone();
+        self.impulse_joints = snapshot.impulse_joints.clone();
+        self.multibody_joints = snapshot.multibody_joints.clone();
+        self.ccd_solver = snapshot.ccd_solver.clone();
+        self.ground = snapshot.ground;
+
+        self.query_pipeline = RefCell::default();
+        self.previous_poses.clear();
+
+        // Joints broken after the snapshot come back without their break
+        // subscribers
+        for joint in &snapshot.joints {
+            self.joints.entry(joint.handle).or_insert_with(|| {
+                Own::new(Joint {
+                    handle:      joint.handle,
+                    a:           joint.a,
+                    b:           joint.b,
+                    break_force: joint.break_force,
+                    motor_axis:  joint.motor_axis,
+                    on_break:    Event::default(),
+                })
+            });
+        }
+    }
 
+    /// Moves joints of sprites recreated by snapshot restore to the new
+    /// sprites and their bodies. Old bodies must still be in the world.
+    pub(crate) fn remap_joints(
+        &mut self,
+        sprites: &HashMap<usize, Weak<dyn Sprite>>,
+        bodies: &HashMap<RigidBodyHandle, RigidBodyHandle>,
+    ) {
+        let moved: Vec<_> = self
+            .joints
+            .values()
+            .filter(|joint| sprites.contains_key(&joint.a.addr()) || sprites.contains_key(&joint.b.addr()))
+            .map(|joint| joint.handle)
+            .collect();
+
+        for handle in moved {
+            let (Some(mut joint), Some(old)) =
+                (self.joints.remove(&handle), self.impulse_joints.remove(handle, true))
+            else {
+                continue;
+            };
+
+            let body1 = bodies.get(&old.body1).copied().unwrap_or(old.body1);
+            let body2 = bodies.get(&old.body2).copied().unwrap_or(old.body2);
+
+            joint.handle = self.impulse_joints.insert(body1, body2, old.data, true);
+            joint.a = sprites.get(&joint.a.addr()).copied().unwrap_or(joint.a);
+            joint.b = sprites.get(&joint.b.addr()).copied().unwrap_or(joint.b);
+
+            self.joints.insert(joint.handle, joint);
+        }
+    }
+
+    /// Drops joints whose rapier joint or one of the sprites is gone.
+    pub(crate) fn retain_joints(&mut self) {
+        let stale: Vec<_> = self
+            .joints
+            .values()
+            .filter(|joint| {
+                !joint.a.is_ok() || !joint.b.is_ok() || self.impulse_joints.get(joint.handle).is_none()
+            })
+            .map(|joint| joint.handle)
+            .collect();
+
+        for handle in stale {
+            self.remove_joint(handle);
+        }
+    }
+
+    /// Single fixed step made of `substeps` pipeline steps `dt` long.
+    pub fn update_physics(&mut self, sprites: &[Own<dyn Sprite>], dt: f32, substeps: u16) {
+        self.store_previous_poses();
+
+        self.integration_parameters.dt = dt;
+
+        for _ in 0..substeps.max(1) {
+            self.step();
+            self.handle_collisions(sprites);
+            self.break_joints(dt);
+        }
+    }
+
+    fn step(&mut self) {
         self.physics_pipeline.step(
             &self.gravity,
             &self.integration_parameters,
@@ -51,14 +216,109 @@ impl LevelPhysics {
             &mut self.multibody_joints,
             &mut self.ccd_solver,
             None,
-            &(),
+            &self.one_way_platforms,
             &self.events.handler,
         );
+    }
+
+    /// Poses before the step for render interpolation. See
+    /// [`Sprite::render_position`].
+    fn store_previous_poses(&mut self) {
+        self.previous_poses.clear();
+        self.previous_poses.extend(
+            self.sets
+                .rigid_bodies
+                .iter()
+                .filter(|(_, body)| body.is_dynamic() || body.is_kinematic())
+                .map(|(handle, body)| (handle, *body.position())),
+        );
+    }
+
+    /// Pose between previous and current step.
+    pub(crate) fn interpolated_pose(&self, handle: RigidBodyHandle, alpha: f32) -> Option<Isometry2<f32>> {
+        let previous = self.previous_poses.get(&handle)?;
+        let current = self.sets.rigid_bodies.get(handle)?.position();
+        Some(previous.lerp_slerp(current, alpha))
+    }
+
+    pub(crate) fn add_joint(
+        &mut self,
+        a: Weak<dyn Sprite>,
+        b: Weak<dyn Sprite>,
+        builder: &impl JointBuilder,
+    ) -> Weak<Joint> {
+        assert!(
+            a.rigid_handle().is_some() || b.rigid_handle().is_some(),
+            "At least one of joint sprites must have a rigid body"
+        );
+
+        let settings = builder.settings();
+        let mut data = builder.to_generic();
+
+        let body_a = a.rigid_handle().unwrap_or_else(|| {
+            data.set_local_anchor1(point(world_anchor(a.deref(), settings.anchor_a)));
+            self.ground()
+        });
+
+        let body_b = b.rigid_handle().unwrap_or_else(|| {
+            data.set_local_anchor2(point(world_anchor(b.deref(), settings.anchor_b)));
+            self.ground()
+        });
+
+        let handle = self.impulse_joints.insert(body_a, body_b, data, true);
+
+        let joint = Own::new(Joint {
+            handle,
+            a,
+            b,
+            break_force: settings.break_force,
+            motor_axis: builder.motor_axis(),
+            on_break: Event::default(),
+        });
+
+        let weak = joint.weak();
+        self.joints.insert(handle, joint);
+        weak
+    }
+
+    pub(crate) fn remove_joint(&mut self, handle: ImpulseJointHandle) {
+        self.impulse_joints.remove(handle, true);
+        self.joints.remove(&handle);
+    }
 
-        self.handle_collisions(sprites);
+    fn ground(&mut self) -> RigidBodyHandle {
+        *self
+            .ground
+            .get_or_insert_with(|| self.sets.rigid_bodies.insert(RigidBodyBuilder::fixed().build()))
     }
 
-    fn sprite_with_collider(
+    fn break_joints(&mut self, frame_time: f32) {
+        if frame_time <= 0.0 {
+            return;
+        }
+
+        let broken: Vec<_> = self
+            .joints
+            .values()
+            .filter(|joint| {
+                joint.break_force.is_some_and(|force| {
+                    self.impulse_joints
+                        .get(joint.handle)
+                        .is_some_and(|data| linear_impulse(data) / frame_time > force)
+                })
+            })
+            .map(|joint| joint.handle)
+            .collect();
+
+        for handle in broken {
+            self.impulse_joints.remove(handle, true);
+            if let Some(joint) = self.joints.remove(&handle) {
+                joint.on_break.trigger(());
+            }
+        }
+    }
+
+    pub(crate) fn sprite_with_collider(
         sprites: &[Own<dyn Sprite>],
         collider_handle: ColliderHandle,
     ) -> Option<Weak<dyn Sprite>> {
@@ -71,36 +331,117 @@ impl LevelPhysics {
             .map(Own::weak)
     }
 
-    fn handle_collisions(&self, sprites: &[Own<dyn Sprite>]) {
-        while let Ok(contact) = self.events.intersection.try_recv() {
-            let CollisionEvent::Started(a, b, _) = contact else {
+    fn handle_collisions(&mut self, sprites: &[Own<dyn Sprite>]) {
+        while let Ok(event) = self.events.intersection.try_recv() {
+            let (a, b) = (event.collider1(), event.collider2());
+
+            let sprite_a = self.sprite(sprites, a).or_else(|| self.removed_sprites.get(&a).copied());
+            let sprite_b = self.sprite(sprites, b).or_else(|| self.removed_sprites.get(&b).copied());
+
+            if !event.started() {
+                // Removed sprites still produce stop events. Other side still
+                // gets one.
+                end_collision(sprite_a, sprite_b);
+                end_collision(sprite_b, sprite_a);
+                continue;
+            }
+
+            let (Some(sprite_a), Some(sprite_b)) = (sprite_a, sprite_b) else {
                 continue;
             };
 
-            let a = self
-                .colliding_sprites
-                .get(&a)
-                .copied()
-                .unwrap_or_else(|| Self::sprite_with_collider(sprites, a).unwrap());
+            if sprite_a.is_ok() && sprite_b.is_ok() {
+                let pair = self.narrow_phase.contact_pair(a, b);
 
-            let b = self
-                .colliding_sprites
-                .get(&b)
-                .copied()
-                .unwrap_or_else(|| Self::sprite_with_collider(sprites, b).unwrap());
+                if sprite_a.collision_enabled {
+                    sprite_a.on_collision.trigger(self.collision(sprite_b, a, pair));
+                }
 
-            if a.collision_enabled {
-                a.on_collision.trigger(b);
+                if sprite_b.collision_enabled {
+                    sprite_b.on_collision.trigger(self.collision(sprite_a, b, pair));
+                }
             }
+        }
 
-            if b.collision_enabled {
-                b.on_collision.trigger(a);
-            }
+        self.removed_sprites.clear();
+    }
+
+    /// Contact details as seen from sprite with collider `this`.
+    fn collision(
+        &self,
+        other: Weak<dyn Sprite>,
+        this: ColliderHandle,
+        pair: Option<&ContactPair>,
+    ) -> Collision {
+        let Some(pair) = pair else {
+            return Collision {
+                sprite:    other,
+                manifolds: vec![],
+                impulse:   0.0,
+            };
+        };
+
+        let position = self.sets.colliders[pair.collider1].position();
+        // Manifold normals point from first collider to the second one
+        let direction: f32 = if pair.collider1 == this { 1.0 } else { -1.0 };
+
+        let manifolds = pair
+            .manifolds
+            .iter()
+            .map(|manifold| ContactManifold {
+                normal:   Point::new(manifold.data.normal.x, manifold.data.normal.y) * direction,
+                contacts: manifold
+                    .points
+                    .iter()
+                    .map(|contact| {
+                        let point = position * contact.local_p1;
+                        Contact {
+                            point:   Point::new(point.x, point.y),
+                            impulse: contact.data.impulse,
+                        }
+                    })
+                    .collect(),
+            })
+            .collect();
+
+        Collision {
+            sprite: other,
+            manifolds,
+            impulse: pair.total_impulse_magnitude(),
         }
     }
 
-    pub(crate) fn remove(&mut self, sprite: &dyn Sprite) {
+    pub(crate) fn remove(&mut self, sprite: &Own<dyn Sprite>) {
+        let address = sprite.addr();
+
+        let attached: Vec<_> = self
+            .joints
+            .values()
+            .filter(|joint| joint.a.addr() == address || joint.b.addr() == address)
+            .map(|joint| joint.handle)
+            .collect();
+
+        for handle in attached {
+            self.remove_joint(handle);
+        }
+
         if let Some(collider) = sprite.collider_handle() {
+            self.removed_sprites.insert(collider, sprite.weak());
+        }
+
+        self.remove_handles(sprite.rigid_handle(), sprite.collider_handle());
+    }
+
+    /// Removes body and collider from the world. Attached rapier joints are
+    /// removed with the body.
+    pub(crate) fn remove_handles(
+        &mut self,
+        rigid_body: Option<RigidBodyHandle>,
+        collider: Option<ColliderHandle>,
+    ) {
+        if let Some(collider) = collider {
+            self.colliding_sprites.remove(&collider);
+            self.one_way_platforms.colliders.remove(&collider);
             self.sets.colliders.remove(
                 collider,
                 &mut self.island_manager,
@@ -109,7 +450,8 @@ impl LevelPhysics {
             );
         }
 
-        if let Some(rigid_body) = sprite.rigid_handle() {
+        if let Some(rigid_body) = rigid_body {
+            self.previous_poses.remove(&rigid_body);
             self.sets.rigid_bodies.remove(
                 rigid_body,
                 &mut self.island_manager,
@@ -121,3 +463,180 @@ impl LevelPhysics {
         }
     }
 }
+
+/// Sprite anchor in level space. For sprites without rigid body.
+fn world_anchor(sprite: &dyn Sprite, anchor: Point) -> Point {
+    let position = sprite.position();
+    let isometry = Isometry2::new(Vector2::new(position.x, position.y), sprite.rotation());
+    let anchor = isometry * point(anchor);
+    Point::new(anchor.x, anchor.y)
+}
+
+/// Fires [`crate::SpriteData::on_collision_end`] of `this` unless it is
+/// removed. `other` may be removed already.
+fn end_collision(this: Option<Weak<dyn Sprite>>, other: Option<Weak<dyn Sprite>>) {
+    let (Some(this), Some(other)) = (this, other) else {
+        return;
+    };
+
+    if this.is_ok() && this.collision_enabled {
+        this.on_collision_end.trigger(other);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{
+        ops::{Deref, DerefMut},
+        sync::{
+            Arc,
+            atomic::{AtomicBool, Ordering},
+        },
+    };
+
+    use gm::flat::{Point, Shape};
+    use rapier2d::{
+        dynamics::{JointAxis, RigidBodyBuilder, RigidBodyHandle},
+        geometry::{ColliderBuilder, ColliderHandle},
+        na::Vector2,
+    };
+    use refs::{Own, Weak};
+
+    use super::LevelPhysics;
+    use crate::{FixedJoint, JointBuilder, Motor, RevoluteJoint, Sprite, SpriteData, joints::linear_impulse};
+
+    struct Part {
+        rigid_handle:    RigidBodyHandle,
+        collider_handle: ColliderHandle,
+        sprite:          SpriteData,
+    }
+
+    impl Part {
+        fn add(physics: &mut LevelPhysics, rigid_body: RigidBodyBuilder, y: f32) -> Own<dyn Sprite> {
+            let (rigid_handle, collider_handle) = physics.sets.insert(
+                rigid_body.translation(Vector2::new(0.0, y)).build(),
+                ColliderBuilder::ball(0.5).build(),
+            );
+
+            Own::new(Self {
+                rigid_handle,
+                collider_handle,
+                sprite: SpriteData::make(Shape::Circle(0.5), Point::new(0.0, y)),
+            })
+        }
+    }
+
+    impl Deref for Part {
+        type Target = SpriteData;
+
+        fn deref(&self) -> &SpriteData {
+            &self.sprite
+        }
+    }
+
+    impl DerefMut for Part {
+        fn deref_mut(&mut self) -> &mut SpriteData {
+            &mut self.sprite
+        }
+    }
+
+    impl Sprite for Part {
+        fn make(_: Shape, _: Point) -> Own<Self> {
+            unreachable!()
+        }
+
+        fn rigid_handle(&self) -> Option<RigidBodyHandle> {
+            Some(self.rigid_handle)
+        }
+
+        fn collider_handle(&self) -> Option<ColliderHandle> {
+            Some(self.collider_handle)
+        }
+    }
+
+    /// Fixed anchor with dynamic weight hanging 2 units below it.
+    fn pendulum(physics: &mut LevelPhysics) -> [Own<dyn Sprite>; 2] {
+        [
+            Part::add(physics, RigidBodyBuilder::fixed(), 0.0),
+            Part::add(physics, RigidBodyBuilder::dynamic(), -2.0),
+        ]
+    }
+
+    fn weak(sprite: &Own<dyn Sprite>) -> Weak<dyn Sprite> {
+        sprite.weak()
+    }
+
+    #[test]
+    fn add_joint() {
+        let mut physics = LevelPhysics::default();
+        let [anchor, weight] = pendulum(&mut physics);
+
+        let hinge = physics.add_joint(
+            weak(&anchor),
+            weak(&weight),
+            &RevoluteJoint::new().anchors((0, -1), (0, 1)).motor(Motor::velocity(1.0, 1.0)),
+        );
+        let fixed = physics.add_joint(weak(&anchor), weak(&weight), &FixedJoint::new());
+
+        assert_eq!(physics.joints.len(), 2);
+        assert_eq!(physics.impulse_joints.len(), 2);
+        assert_eq!(hinge.motor_axis, Some(JointAxis::AngX));
+        assert_eq!(fixed.motor_axis, None);
+        assert_eq!(hinge.sprites().0.addr(), anchor.addr());
+        assert_eq!(hinge.sprites().1.addr(), weight.addr());
+    }
+
+    #[test]
+    fn break_joints() {
+        let mut physics = LevelPhysics::default();
+        let [anchor, weight] = pendulum(&mut physics);
+        let other = Part::add(&mut physics, RigidBodyBuilder::dynamic(), -4.0);
+
+        // Each weight hangs on its own joint so both of them carry the load.
+        let weak_joint = physics.add_joint(
+            weak(&anchor),
+            weak(&weight),
+            &RevoluteJoint::new().anchors((0, -1), (0, 1)).breakable(0.001),
+        );
+        let strong = physics.add_joint(
+            weak(&anchor),
+            weak(&other),
+            &RevoluteJoint::new().anchors((0, -2), (0, 2)).breakable(1000.0),
+        );
+
+        let broken = Arc::new(AtomicBool::new(false));
+        let flag = broken.clone();
+        weak_joint.on_break.sub(move || flag.store(true, Ordering::Relaxed));
+
+        let sprites = [anchor, weight, other];
+
+        for _ in 0..10 {
+            physics.update_physics(&sprites, 1.0 / 60.0, 1);
+        }
+
+        assert!(broken.load(Ordering::Relaxed));
+        assert!(!weak_joint.is_ok());
+        assert!(strong.is_ok());
+        assert!(linear_impulse(physics.impulse_joints.get(strong.handle).unwrap()) > 0.0);
+        assert_eq!(physics.joints.len(), 1);
+        assert_eq!(physics.impulse_joints.len(), 1);
+    }
+
+    #[test]
+    fn remove_attached_joints() {
+        let mut physics = LevelPhysics::default();
+        let [anchor, weight] = pendulum(&mut physics);
+        let other = Part::add(&mut physics, RigidBodyBuilder::dynamic(), -4.0);
+
+        let joint = physics.add_joint(weak(&anchor), weak(&weight), &FixedJoint::new());
+        let kept = physics.add_joint(weak(&anchor), weak(&other), &FixedJoint::new());
+
+        physics.remove(&weight);
+
+        assert!(!joint.is_ok());
+        assert!(kept.is_ok());
+        assert_eq!(physics.joints.len(), 1);
+        assert_eq!(physics.impulse_joints.len(), 1);
+        assert!(physics.sets.rigid_bodies.get(weight.rigid_handle().unwrap()).is_none());
+    }
+}
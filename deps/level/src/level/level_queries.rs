@@ -0,0 +1,369 @@
+use std::cell::RefMut;
+
+use gm::flat::{Point, Shape};
+use rapier2d::{
+    geometry::{ColliderHandle, Group, InteractionGroups, Ray},
+    na::{Isometry2, Point2, Vector2},
+    pipeline::{QueryFilter, QueryPipeline},
+};
+use refs::{Own, Weak};
+
+use crate::{Sprite, ToCollider, level::level_physics::LevelPhysics};
+
+/// Which sprites spatial queries can hit. Only sprites with colliders are
+/// considered.
+#[derive(Copy, Clone)]
+pub struct SpriteFilter {
+    /// Collision groups bit mask. Sprite is hit if it is a member of any of
+    /// these groups.
+    pub groups:          u32,
+    pub exclude_sensors: bool,
+    /// Usually the sprite making the query.
+    pub exclude:         Option<Weak<dyn Sprite>>,
+}
+
+impl SpriteFilter {
+    pub const ALL: Self = Self {
+        groups:          u32::MAX,
+        exclude_sensors: false,
+        exclude:         None,
+    };
+
+    pub const fn groups(mut self, groups: u32) -> Self {
+        self.groups = groups;
+        self
+    }
+
+    pub const fn exclude_sensors(mut self) -> Self {
+        self.exclude_sensors = true;
+        self
+    }
+
+    pub fn exclude(mut self, sprite: Weak<dyn Sprite>) -> Self {
+        self.exclude = Some(sprite);
+        self
+    }
+
+    fn rapier(&self) -> QueryFilter<'static> {
+        let mut filter = QueryFilter::default().groups(InteractionGroups::new(
+            Group::ALL,
+            Group::from_bits_truncate(self.groups),
+        ));
+
+        if self.exclude_sensors {
+            filter = filter.exclude_sensors();
+        }
+
+        if let Some(sprite) = self.exclude.filter(Weak::is_ok) {
+            if let Some(collider) = sprite.collider_handle() {
+                filter = filter.exclude_collider(collider);
+            }
+            if let Some(rigid_body) = sprite.rigid_handle() {
+                filter = filter.exclude_rigid_body(rigid_body);
+            }
+        }
+
+        filter
+    }
+}
+
+impl Default for SpriteFilter {
+    fn default() -> Self {
+        Self::ALL
+    }
+}
+
+#[derive(Copy, Clone)]
+pub struct RayHit {
+    pub sprite:   Weak<dyn Sprite>,
+    pub point:    Point,
+    /// Surface normal at hit point.
+    pub normal:   Point,
+    /// From ray start to hit point.
+    pub distance: f32,
+}
+
+impl LevelPhysics {
+    /// First sprite on segment from `from` to `to`.
+    pub(crate) fn cast_ray(
+        &self,
+        sprites: &[Own<dyn Sprite>],
+        from: Point,
+        to: Point,
+        filter: &SpriteFilter,
+    ) -> Option<RayHit> {
+        let (ray, length) = ray(from, to)?;
+
+        let (collider, intersection) = self.queries().cast_ray_and_get_normal(
+            &self.sets.rigid_bodies,
+            &self.sets.colliders,
+            &ray,
+            length,
+            true,
+            filter.rapier(),
+        )?;
+
+        let point = ray.point_at(intersection.time_of_impact);
+
+        Some(RayHit {
+            sprite:   self.sprite(sprites, collider)?,
+            point:    Point::new(point.x, point.y),
+            normal:   Point::new(intersection.normal.x, intersection.normal.y),
+            distance: intersection.time_of_impact,
+        })
+    }
+
+    /// All sprites on segment from `from` to `to` sorted by distance.
+    pub(crate) fn cast_ray_all(
+        &self,
+        sprites: &[Own<dyn Sprite>],
+        from: Point,
+        to: Point,
+        filter: &SpriteFilter,
+    ) -> Vec<RayHit> {
+        let Some((ray, length)) = ray(from, to) else {
+            return vec![];
+        };
+
+        let mut hits = vec![];
+
+        self.queries().intersections_with_ray(
+            &self.sets.rigid_bodies,
+            &self.sets.colliders,
+            &ray,
+            length,
+            true,
+            filter.rapier(),
+            |collider, intersection| {
+                let point = ray.point_at(intersection.time_of_impact);
+                if let Some(sprite) = self.sprite(sprites, collider) {
+                    hits.push(RayHit {
+                        sprite,
+                        point: Point::new(point.x, point.y),
+                        normal: Point::new(intersection.normal.x, intersection.normal.y),
+                        distance: intersection.time_of_impact,
+                    });
+                }
+                true
+            },
+        );
+
+        hits.sort_by(|a, b| a.distance.total_cmp(&b.distance));
+        hits
+    }
+
+    pub(crate) fn sprites_at_point(
+        &self,
+        sprites: &[Own<dyn Sprite>],
+        point: Point,
+        filter: &SpriteFilter,
+    ) -> Vec<Weak<dyn Sprite>> {
+        let mut result = vec![];
+
+        self.queries().intersections_with_point(
+            &self.sets.rigid_bodies,
+            &self.sets.colliders,
+            &Point2::new(point.x, point.y),
+            filter.rapier(),
+            |collider| {
+                result.extend(self.sprite(sprites, collider));
+                true
+            },
+        );
+
+        result
+    }
+
+    pub(crate) fn sprites_in_shape(
+        &self,
+        sprites: &[Own<dyn Sprite>],
+        shape: &Shape,
+        position: Point,
+        rotation: f32,
+        filter: &SpriteFilter,
+    ) -> Vec<Weak<dyn Sprite>> {
+        let mut result = vec![];
+
+        let collider = shape.make_collider();
+
+        self.queries().intersections_with_shape(
+            &self.sets.rigid_bodies,
+            &self.sets.colliders,
+            &Isometry2::new(Vector2::new(position.x, position.y), rotation),
+            collider.shape.as_ref(),
+            filter.rapier(),
+            |collider| {
+                result.extend(self.sprite(sprites, collider));
+                true
+            },
+        );
+
+        result
+    }
+
+    /// Query pipeline updated with current colliders. Sprites may be added or
+    /// moved between physics steps.
+    fn queries(&self) -> RefMut<'_, QueryPipeline> {
+        let mut pipeline = self.query_pipeline.borrow_mut();
+        pipeline.update(&self.sets.colliders);
+        pipeline
+    }
+
+    pub(crate) fn sprite(
+        &self,
+        sprites: &[Own<dyn Sprite>],
+        collider: ColliderHandle,
+    ) -> Option<Weak<dyn Sprite>> {
+        self.colliding_sprites
+            .get(&collider)
+            .copied()
+            .or_else(|| Self::sprite_with_collider(sprites, collider))
+    }
+}
+
+fn ray(from: Point, to: Point) -> Option<(Ray, f32)> {
+    let direction = Vector2::new(to.x - from.x, to.y - from.y);
+    let length = direction.norm();
+
+    if length < f32::EPSILON {
+        return None;
+    }
+
+    Some((Ray::new(Point2::new(from.x, from.y), direction / length), length))
+}
+
+#[cfg(test)]
+mod test {
+    use gm::flat::{Point, Shape};
+    use refs::Weak;
+
+    use crate::{
+        CollisionGroups, Level, LevelCreation, Sensor, Sprite, SpriteFilter, SpriteTemplates, Wall,
+        level::test_level::TestLevel,
+    };
+
+    /// 2x2 walls centered at x 5, 10 and 15 on the x axis.
+    fn walls(level: &mut Weak<dyn Level>) -> [Weak<dyn Sprite>; 3] {
+        [5, 10, 15].map(|x| {
+            let wall: Weak<dyn Sprite> = level.make_sprite::<Wall>(Shape::rect(2, 2), (x, 0));
+            wall
+        })
+    }
+
+    fn addrs(sprites: impl IntoIterator<Item = Weak<dyn Sprite>>) -> Vec<usize> {
+        let mut addrs: Vec<_> = sprites.into_iter().map(|sprite| sprite.addr()).collect();
+        addrs.sort_unstable();
+        addrs
+    }
+
+    #[test]
+    fn cast_ray() {
+        let (_guard, mut level) = TestLevel::set();
+        let [first, second, third] = walls(&mut level);
+
+        let from = Point::new(0.0, 0.0);
+
+        let hit = level.cast_ray(from, (20, 0).into(), &SpriteFilter::ALL).unwrap();
+        assert_eq!(hit.sprite.addr(), first.addr());
+        assert!((hit.distance - 4.0).abs() < 0.001);
+        assert!((hit.point.x - 4.0).abs() < 0.001);
+        assert!((hit.normal.x + 1.0).abs() < 0.001);
+
+        let hits = level.cast_ray_all(from, (20, 0).into(), &SpriteFilter::ALL);
+        assert_eq!(hits.iter().map(|hit| hit.sprite.addr()).collect::<Vec<_>>(), [
+            first.addr(),
+            second.addr(),
+            third.addr()
+        ]);
+        assert!(hits.windows(2).all(|pair| pair[0].distance < pair[1].distance));
+
+        // Segment end limits the distance
+        assert!(level.cast_ray(from, (3, 0).into(), &SpriteFilter::ALL).is_none());
+        assert_eq!(
+            level.cast_ray_all(from, (8, 0).into(), &SpriteFilter::ALL).len(),
+            1
+        );
+        assert!(level.cast_ray(from, from, &SpriteFilter::ALL).is_none());
+
+        let hit = level.cast_ray(from, (20, 0).into(), &SpriteFilter::ALL.exclude(first)).unwrap();
+        assert_eq!(hit.sprite.addr(), second.addr());
+    }
+
+    #[test]
+    fn filter() {
+        let (_guard, mut level) = TestLevel::set();
+        let [mut first, mut second, third] = walls(&mut level);
+        let sensor: Weak<dyn Sprite> = level.make_sprite::<Sensor>(Shape::rect(2, 2), (2, 0));
+
+        // Third wall and sensor stay in all groups
+        first.set_collision_groups(CollisionGroups::new(0b01, u32::MAX));
+        second.set_collision_groups(CollisionGroups::new(0b10, u32::MAX));
+
+        let from = Point::new(0.0, 0.0);
+        let to = Point::new(20.0, 0.0);
+
+        assert_eq!(
+            addrs(level.cast_ray_all(from, to, &SpriteFilter::ALL).iter().map(|hit| hit.sprite)),
+            addrs([sensor, first, second, third])
+        );
+
+        let filter = SpriteFilter::ALL.groups(0b01).exclude_sensors();
+        assert_eq!(
+            addrs(level.cast_ray_all(from, to, &filter).iter().map(|hit| hit.sprite)),
+            addrs([first, third])
+        );
+
+        let filter = SpriteFilter::ALL.groups(0b10);
+        assert_eq!(
+            addrs(level.cast_ray_all(from, to, &filter).iter().map(|hit| hit.sprite)),
+            addrs([sensor, second, third])
+        );
+
+        let filter = filter.exclude_sensors().exclude(second);
+        assert_eq!(
+            addrs(level.cast_ray_all(from, to, &filter).iter().map(|hit| hit.sprite)),
+            addrs([third])
+        );
+
+        let hit = level
+            .cast_ray(from, to, &SpriteFilter::ALL.groups(0b10).exclude_sensors())
+            .unwrap();
+        assert_eq!(hit.sprite.addr(), second.addr());
+    }
+
+    #[test]
+    fn overlaps() {
+        let (_guard, mut level) = TestLevel::set();
+        let [first, second, third] = walls(&mut level);
+
+        assert_eq!(
+            addrs(level.sprites_at_point((10.0, 0.5).into(), &SpriteFilter::ALL)),
+            addrs([second])
+        );
+        assert!(level.sprites_at_point((7, 0).into(), &SpriteFilter::ALL).is_empty());
+
+        // From x 5 to 11 touches first two walls
+        let in_shape = level.sprites_in_shape(&Shape::rect(6, 2), (8, 0).into(), 0.0, &SpriteFilter::ALL);
+        assert_eq!(addrs(in_shape), addrs([first, second]));
+
+        let in_shape = level.sprites_in_shape(
+            &Shape::rect(6, 2),
+            (8, 0).into(),
+            0.0,
+            &SpriteFilter::ALL.exclude(first),
+        );
+        assert_eq!(addrs(in_shape), addrs([second]));
+
+        // Rotated by 90 degrees it is 2 wide and fits between the walls
+        let rotated = level.sprites_in_shape(
+            &Shape::rect(6, 2),
+            (7.5, 0).into(),
+            std::f32::consts::FRAC_PI_2,
+            &SpriteFilter::ALL,
+        );
+        assert!(rotated.is_empty());
+
+        let in_aabb = level.sprites_in_aabb((12, -1).into(), (20, 1).into(), &SpriteFilter::ALL);
+        assert_eq!(addrs(in_aabb), addrs([third]));
+    }
+}
@@ -0,0 +1,403 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    ops::{Deref, DerefMut},
+};
+
+use anyhow::Result;
+use gm::{Color, flat::Point};
+use rand::rngs::StdRng;
+use rapier2d::{dynamics::RigidBodyHandle, geometry::ColliderHandle};
+use refs::{Own, Weak};
+use wgpu_wrapper::image::Image;
+
+use crate::{
+    Components, FixedTimestep, LevelManager, Sprite, SpriteDocument,
+    level::level_physics::{LevelPhysics, PhysicsSnapshot},
+    sprite_registry::SpriteRegistry,
+};
+
+/// Complete state of current level: physics world, sprite data, components,
+/// timestep and random generator. Kept in memory. See
+/// [`LevelManager::snapshot`].
+#[derive(Clone)]
+pub struct LevelSnapshot {
+    physics:    Option<PhysicsSnapshot>,
+    sprites:    Vec<SpriteSnapshot>,
+    components: Components,
+    timestep:   FixedTimestep,
+    rng:        StdRng,
+}
+
+#[derive(Clone)]
+struct SpriteSnapshot {
+    sprite:     Weak<dyn Sprite>,
+    rigid_body: Option<RigidBodyHandle>,
+    collider:   Option<ColliderHandle>,
+    position:   Point,
+    rotation:   f32,
+    color:      Color,
+    tag:        u32,
+    z_position: f32,
+    image:      Weak<Image>,
+    /// Recreates the sprite if it was removed after the snapshot. `None` if
+    /// sprite kind is not registered.
+    document:   Option<SpriteDocument>,
+}
+
+impl LevelSnapshot {
+    pub fn step_count(&self) -> u64 {
+        self.timestep.step_count()
+    }
+}
+
+impl LevelManager {
+    pub fn snapshot() -> LevelSnapshot {
+        let level = Self::level();
+
+        LevelSnapshot {
+            physics:    level.physics.as_ref().map(LevelPhysics::snapshot),
+            sprites:    level
+                .sprites()
+                .iter()
+                .map(|sprite| SpriteSnapshot {
+                    sprite:     sprite.weak(),
+                    rigid_body: sprite.rigid_handle(),
+                    collider:   sprite.collider_handle(),
+                    position:   sprite.position,
+                    rotation:   sprite.rotation,
+                    color:      sprite.color,
+                    tag:        sprite.tag,
+                    z_position: sprite.z_position,
+                    image:      sprite.image,
+                    document:   SpriteRegistry::document(sprite.deref()).ok(),
+                })
+                .collect(),
+            components: level.components.clone(),
+            timestep:   level.timestep,
+            rng:        level.rng.clone(),
+        }
+    }
+
+    /// Brings level back to the snapshot. Sprites added after it are removed
+    /// and removed ones are recreated from their documents with their joints
+    /// and components.
+    pub fn restore_snapshot(snapshot: &LevelSnapshot) -> Result<()> {
+        let mut level = Self::level_weak();
+
+        // Sprite added after the snapshot may take address of a removed one
+        let added: Vec<_> = level
+            .sprites()
+            .iter()
+            .map(Own::addr)
+            .filter(|addr| {
+                !snapshot
+                    .sprites
+                    .iter()
+                    .any(|saved| saved.sprite.is_ok() && saved.sprite.addr() == *addr)
+            })
+            .collect();
+
+        for addr in added {
+            level.remove(addr);
+        }
+
+        if let (Some(physics), Some(saved)) = (level.physics.as_mut(), &snapshot.physics) {
+            physics.restore(saved);
+        }
+
+        let mut recreated = HashMap::new();
+        let mut bodies = HashMap::new();
+        let mut dead = vec![];
+
+        for saved in &snapshot.sprites {
+            if saved.sprite.is_ok() {
+                let mut sprite = saved.sprite;
+                sprite.position = saved.position;
+                sprite.rotation = saved.rotation;
+                sprite.color = saved.color;
+                sprite.tag = saved.tag;
+                sprite.z_position = saved.z_position;
+                sprite.image = saved.image;
+                continue;
+            }
+
+            // Restored world still has body of the dead sprite. It is removed after its
+            // joints are moved to the new sprite.
+            dead.push((saved.rigid_body, saved.collider));
+
+            let Some(document) = &saved.document else {
+                continue;
+            };
+
+            let old_body = saved.rigid_body.and_then(|handle| {
+                let body = level.physics.as_ref()?.sets.rigid_bodies.get(handle)?;
+                Some((handle, *body.linvel(), body.angvel()))
+            });
+
+            let mut sprite = SpriteRegistry::make(level.deref_mut(), document)?;
+
+            if let (Some((old, linvel, angvel)), Some(new)) = (old_body, sprite.rigid_handle()) {
+                let body = sprite.rigid_body_mut();
+                body.set_linvel(linvel, true);
+                body.set_angvel(angvel, true);
+                bodies.insert(old, new);
+            }
+
+            recreated.insert(saved.sprite.addr(), sprite);
+        }
+
+        if let Some(physics) = level.physics.as_mut() {
+            physics.remap_joints(&recreated, &bodies);
+
+            for (rigid_body, collider) in dead {
+                physics.remove_handles(rigid_body, collider);
+            }
+
+            physics.retain_joints();
+        }
+
+        level.components = snapshot.components.clone();
+        level.components.remap(&recreated);
+
+        level.timestep = snapshot.timestep;
+        level.timestep.reset();
+        level.rng = snapshot.rng.clone();
+
+        Ok(())
+    }
+}
+
+/// Keeps recent snapshots to rewind time. Call [`Rewind::capture`] every
+/// frame.
+pub struct Rewind {
+    /// Max stored snapshots. Oldest are dropped.
+    pub capacity: usize,
+    /// Physics steps between snapshots.
+    pub interval: u64,
+
+    snapshots:    VecDeque<LevelSnapshot>,
+    last_capture: Option<u64>,
+}
+
+impl Rewind {
+    pub fn new(capacity: usize, interval: u64) -> Self {
+        Self {
+            capacity,
+            interval,
+            snapshots: VecDeque::with_capacity(capacity),
+            last_capture: None,
+        }
+    }
+
+    /// Takes snapshot if `interval` steps passed since the last one.
+    pub fn capture(&mut self) {
+        let step = LevelManager::level().timestep.step_count();
+
+        if self.last_capture.is_some_and(|last| step < last + self.interval) {
+            return;
+        }
+
+        self.last_capture = Some(step);
+
+        if self.snapshots.len() >= self.capacity {
+            self.snapshots.pop_front();
+        }
+
+        self.snapshots.push_back(LevelManager::snapshot());
+    }
+
+    /// Restores the latest snapshot and drops it. Returns `false` when there
+    /// is nothing to rewind to.
+    pub fn rewind(&mut self) -> Result<bool> {
+        let Some(snapshot) = self.snapshots.pop_back() else {
+            return Ok(false);
+        };
+        LevelManager::restore_snapshot(&snapshot)?;
+        // Next capture waits for the interval so repeated rewinds go further
+        // back
+        self.last_capture = Some(snapshot.step_count());
+        Ok(true)
+    }
+
+    pub fn len(&self) -> usize {
+        self.snapshots.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.snapshots.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.snapshots.clear();
+        self.last_capture = None;
+    }
+}
+
+impl Default for Rewind {
+    /// 20 seconds at 60 steps per second.
+    fn default() -> Self {
+        Self::new(120, 10)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use gm::flat::{Point, Shape};
+    use refs::Weak;
+
+    use crate::{
+        Body, FixedTimestep, JointBuilder, Level, LevelCreation, LevelManager, RevoluteJoint, Rewind, Sprite,
+        Wall, level::test_level::TestLevel,
+    };
+
+    #[derive(Clone, Debug, PartialEq)]
+    struct Health(u32);
+
+    fn level() -> (impl Drop, Weak<dyn Level>) {
+        let (guard, mut level) = TestLevel::set();
+        level.timestep = FixedTimestep::DEFAULT.deterministic(true);
+        (guard, level)
+    }
+
+    fn run(level: &mut Weak<dyn Level>, steps: u32) {
+        for _ in 0..steps {
+            level.update_physics(1.0 / 60.0);
+        }
+    }
+
+    fn assert_near(a: Point, b: Point) {
+        assert!((a - b).length() < 0.001, "{a:?} != {b:?}");
+    }
+
+    fn sprite_at(level: &Weak<dyn Level>, position: Point) -> Weak<dyn Sprite> {
+        level
+            .sprites()
+            .iter()
+            .find(|sprite| (sprite.position() - position).length() < 0.001)
+            .expect("No sprite at position")
+            .weak()
+    }
+
+    #[test]
+    fn restore_sprites() {
+        let (_guard, mut level) = level();
+
+        level.make_sprite::<Wall>(Shape::rect(20, 1), (0, -10));
+        let mut a = level.make_sprite::<Body>(Shape::rect(1, 1), (0, 0));
+        let mut b = level.make_sprite::<Body>(Shape::rect(1, 1), (5, 0));
+
+        a.set_velocity((2, 0).into());
+        b.set_velocity((0, 3).into());
+        let b_sprite: Weak<dyn Sprite> = b;
+        level.components.insert(b_sprite, Health(5));
+
+        run(&mut level, 10);
+
+        let snapshot = LevelManager::snapshot();
+        let (a_position, a_velocity) = (a.position(), a.velocity());
+        let (b_position, b_velocity) = (b.position(), b.velocity());
+
+        run(&mut level, 20);
+        let added = level.make_sprite::<Body>(Shape::rect(1, 1), (10, 0));
+        b.remove();
+
+        assert_eq!(level.sprites().len(), 3);
+        assert_eq!(level.components.count::<Health>(), 0);
+
+        LevelManager::restore_snapshot(&snapshot).unwrap();
+
+        assert!(!added.is_ok());
+        assert_eq!(level.sprites().len(), 3);
+        assert_eq!(level.timestep.step_count(), 10);
+
+        assert_near(a.position(), a_position);
+        assert_near(a.velocity(), a_velocity);
+
+        let b = sprite_at(&level, b_position);
+        let velocity = b.rigid_body().linvel();
+        assert_near((velocity.x, velocity.y).into(), b_velocity);
+        assert_eq!(level.components.get::<Health>(b), Some(&Health(5)));
+
+        // Restored world keeps simulating both bodies
+        run(&mut level, 10);
+        assert!(a.position().x > a_position.x);
+        assert!(b.position().y != b_position.y);
+    }
+
+    #[test]
+    fn restore_joints() {
+        let (_guard, mut level) = level();
+
+        let anchor: Weak<dyn Sprite> = level.make_sprite::<Wall>(Shape::rect(1, 1), (0, 0));
+        let mut weight: Weak<dyn Sprite> = level.make_sprite::<Body>(Shape::rect(1, 1), (0, -2));
+        level.add_joint(anchor, weight, RevoluteJoint::new().anchors((0, -1), (0, 1)));
+
+        let other_anchor: Weak<dyn Sprite> = level.make_sprite::<Wall>(Shape::rect(1, 1), (5, 0));
+        let other_weight: Weak<dyn Sprite> = level.make_sprite::<Body>(Shape::rect(1, 1), (5, -2));
+        let kept = level.add_joint(
+            other_anchor,
+            other_weight,
+            RevoluteJoint::new().anchors((0, -1), (0, 1)),
+        );
+
+        let snapshot = LevelManager::snapshot();
+
+        weight.remove();
+        let added: Weak<dyn Sprite> = level.make_sprite::<Body>(Shape::rect(1, 1), (10, -2));
+        level.add_joint(other_anchor, added, RevoluteJoint::new());
+
+        assert_eq!(level.physics.as_ref().unwrap().joints.len(), 2);
+
+        LevelManager::restore_snapshot(&snapshot).unwrap();
+
+        let physics = level.physics.as_ref().unwrap();
+        assert_eq!(physics.joints.len(), 2);
+        assert_eq!(physics.impulse_joints.len(), 2);
+        assert!(kept.is_ok());
+
+        let weight = sprite_at(&level, (0, -2).into());
+
+        let joint = physics
+            .joints
+            .values()
+            .find(|joint| joint.b.addr() == weight.addr())
+            .expect("Joint was not moved to recreated sprite");
+        assert_eq!(joint.a.addr(), anchor.addr());
+        assert_eq!(
+            physics.impulse_joints.get(joint.handle).unwrap().body2,
+            weight.rigid_handle().unwrap()
+        );
+
+        // Weight still hangs on the joint instead of falling
+        run(&mut level, 60);
+        assert!((weight.position().y + 2.0).abs() < 0.1);
+    }
+
+    #[test]
+    fn rewind() {
+        let (_guard, mut level) = level();
+
+        let body = level.make_sprite::<Body>(Shape::rect(1, 1), (0, 0));
+
+        let mut rewind = Rewind::new(3, 10);
+        let mut positions = vec![];
+
+        for _ in 0..50 {
+            positions.push(body.position());
+            rewind.capture();
+            run(&mut level, 1);
+        }
+
+        // Snapshots at steps 20, 30 and 40 are left
+        assert_eq!(rewind.len(), 3);
+
+        for step in [40, 30, 20] {
+            assert!(rewind.rewind().unwrap());
+            assert_eq!(level.timestep.step_count(), step);
+            assert_near(body.position(), positions[usize::try_from(step).unwrap()]);
+        }
+
+        assert!(rewind.is_empty());
+        assert!(!rewind.rewind().unwrap());
+    }
+}
@@ -2,10 +2,16 @@ mod level;
 mod level_base;
 mod level_creation;
 mod level_physics;
+mod level_queries;
 mod level_setup;
+mod level_snapshot;
+#[cfg(test)]
+pub(crate) mod test_level;
 
 pub use level::*;
 pub use level_base::*;
 pub use level_creation::*;
 pub use level_physics::*;
+pub use level_queries::*;
 pub use level_setup::*;
+pub use level_snapshot::*;
@@ -0,0 +1,86 @@
+use std::{
+    any::Any,
+    ops::{Deref, DerefMut},
+    sync::{Mutex, MutexGuard},
+};
+
+use refs::{AsAny, Weak, set_current_thread_as_main, weak_from_ref};
+
+use crate::{Level, LevelBase, LevelInternal, LevelManager, LevelSetup, SystemStage};
+
+/// Tests using [`LevelManager`] share its global level so they run one at a
+/// time.
+static LOCK: Mutex<()> = Mutex::new(());
+
+/// Level for unit tests. Does the same as `#[level]` which can't be used
+/// inside this crate.
+#[derive(Default)]
+pub(crate) struct TestLevel {
+    base: LevelBase,
+}
+
+impl TestLevel {
+    /// Makes current thread main and sets empty level with physics. Level is
+    /// stopped when the guard is dropped.
+    pub(crate) fn set() -> (TestLevelGuard, Weak<dyn Level>) {
+        let lock = LOCK.lock().unwrap_or_else(std::sync::PoisonError::into_inner);
+        set_current_thread_as_main();
+
+        let mut level = LevelManager::set_level(TestLevel::default());
+        level.init_physics();
+
+        (TestLevelGuard { _lock: lock }, LevelManager::level_weak())
+    }
+}
+
+pub(crate) struct TestLevelGuard {
+    _lock: MutexGuard<'static, ()>,
+}
+
+impl Drop for TestLevelGuard {
+    fn drop(&mut self) {
+        LevelManager::stop_level();
+    }
+}
+
+impl Level for TestLevel {}
+
+impl LevelInternal for TestLevel {
+    fn __internal_setup(&self) {
+        weak_from_ref(self).setup();
+    }
+
+    fn __internal_update(&self, frame_time: f32) {
+        let mut level = weak_from_ref(self);
+        let mut systems: Weak<dyn Level> = level;
+        systems.run_systems(SystemStage::PrePhysics, frame_time);
+        level.update_physics(frame_time);
+        systems.run_systems(SystemStage::PostPhysics, frame_time);
+        systems.update_animations(frame_time);
+        level.update();
+    }
+}
+
+impl AsAny for TestLevel {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+impl Deref for TestLevel {
+    type Target = LevelBase;
+
+    fn deref(&self) -> &LevelBase {
+        &self.base
+    }
+}
+
+impl DerefMut for TestLevel {
+    fn deref_mut(&mut self) -> &mut LevelBase {
+        &mut self.base
+    }
+}
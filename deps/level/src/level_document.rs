@@ -0,0 +1,189 @@
+use std::{fs, path::Path};
+
+use anyhow::{Result, bail};
+use gm::{
+    Color,
+    flat::{Point, Shape},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::CollisionGroups;
+
+/// Serializable description of a level and its sprites. See
+/// [`crate::LevelManager::save_level`] and
+/// [`crate::LevelManager::load_level_from`].
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct LevelDocument {
+    pub version:    u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub background: Option<String>,
+    /// `None` if level has no physics.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gravity:    Option<Point>,
+    pub sprites:    Vec<SpriteDocument>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SpriteDocument {
+    /// Registered sprite kind. `Body`, `Wall`, `Sensor`, `Banner`, `Unit`,
+    /// `Player` or a custom one. See [`crate::LevelManager::register_sprite`].
+    pub kind:       String,
+    pub shape:      Shape,
+    pub position:   Point,
+    #[serde(default)]
+    pub rotation:   f32,
+    pub color:      Color,
+    /// Image name in `Images` folder.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub image:      Option<String>,
+    #[serde(default)]
+    pub tag:        u32,
+    /// `None` places sprite on top of previously added ones.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub z_position: Option<f32>,
+    /// Only for sprites with a collider.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub physics:    Option<PhysicsDocument>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PhysicsDocument {
+    pub friction:            f32,
+    pub restitution:         f32,
+    pub density:             f32,
+    #[serde(default)]
+    pub rotation_locked:     bool,
+    #[serde(default)]
+    pub collision_detection: bool,
+    #[serde(default)]
+    pub collision_groups:    CollisionGroups,
+    #[serde(default)]
+    pub one_way_platform:    bool,
+}
+
+impl LevelDocument {
+    pub const VERSION: u32 = 1;
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        Self::from_json(&fs::read_to_string(path)?)
+            .map_err(|err| anyhow::anyhow!("Failed to load level from {}: {err}", path.display()))
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, self.to_json()?)?;
+        Ok(())
+    }
+
+    pub fn from_json(json: &str) -> Result<Self> {
+        let document: Self = serde_json::from_str(json)?;
+
+        if document.version > Self::VERSION {
+            bail!(
+                "Level document version {} is newer than supported {}",
+                document.version,
+                Self::VERSION
+            );
+        }
+
+        Ok(document)
+    }
+
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    pub fn needs_physics(&self) -> bool {
+        self.gravity.is_some() || self.sprites.iter().any(|sprite| sprite.physics.is_some())
+    }
+}
+
+impl Default for LevelDocument {
+    fn default() -> Self {
+        Self {
+            version:    Self::VERSION,
+            background: None,
+            gravity:    None,
+            sprites:    vec![],
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use gm::{
+        Color,
+        flat::{Point, Shape},
+    };
+
+    use crate::{CollisionGroups, LevelDocument, PhysicsDocument, SpriteDocument};
+
+    #[test]
+    fn round_trip() -> anyhow::Result<()> {
+        let document = LevelDocument {
+            background: Some("sky.png".into()),
+            gravity: Some(Point::new(0.0, -9.81)),
+            sprites: vec![
+                SpriteDocument {
+                    kind:       "Wall".into(),
+                    shape:      Shape::rect(200, 5),
+                    position:   Point::new(0.0, -5.0),
+                    rotation:   0.0,
+                    color:      Color::BLUE,
+                    image:      None,
+                    tag:        0,
+                    z_position: None,
+                    physics:    Some(PhysicsDocument {
+                        friction:            0.5,
+                        restitution:         1.0,
+                        density:             1.0,
+                        rotation_locked:     false,
+                        collision_detection: false,
+                        collision_groups:    CollisionGroups::new(0b10, 0b01),
+                        one_way_platform:    true,
+                    }),
+                },
+                SpriteDocument {
+                    kind:       "Banner".into(),
+                    shape:      Shape::Polygon(vec![(0, 0).into(), (1, 0).into(), (0, 1).into()]),
+                    position:   Point::new(5.0, 10.0),
+                    rotation:   1.5,
+                    color:      Color::GREEN,
+                    image:      Some("wood-window.png".into()),
+                    tag:        7,
+                    z_position: Some(0.9),
+                    physics:    None,
+                },
+            ],
+            ..Default::default()
+        };
+
+        let json = document.to_json()?;
+        assert_eq!(LevelDocument::from_json(&json)?, document);
+        assert!(document.needs_physics());
+
+        let minimal = LevelDocument::from_json(
+            r#"{
+                "version": 1,
+                "sprites": [{
+                    "kind": "Body",
+                    "shape": { "Circle": 2.0 },
+                    "position": { "x": 1.0, "y": 2.0 },
+                    "color": { "r": 1.0, "g": 0.0, "b": 0.0, "a": 1.0 }
+                }]
+            }"#,
+        )?;
+
+        assert_eq!(minimal.sprites[0].shape, Shape::Circle(2.0));
+        assert_eq!(minimal.sprites[0].tag, 0);
+        assert!(!minimal.needs_physics());
+
+        assert!(LevelDocument::from_json(r#"{ "version": 99, "sprites": [] }"#).is_err());
+
+        Ok(())
+    }
+}
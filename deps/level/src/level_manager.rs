@@ -1,15 +1,25 @@
-use std::ops::{Deref, DerefMut};
+use std::{
+    ops::{Deref, DerefMut},
+    path::Path,
+};
 
+use anyhow::Result;
 use educe::Educe;
 use gm::{LossyConvert, Platform, flat::Point};
+use manage::data_manager::DataManager;
+use rand::{SeedableRng, rngs::StdRng};
 use rapier2d::{
     dynamics::{RigidBody, RigidBodyHandle},
     prelude::{Collider, ColliderHandle},
 };
 use refs::{MainLock, Own, Weak};
-use wgpu_wrapper::WGPUApp;
+use wgpu_wrapper::{WGPUApp, image::Image};
 
-use crate::{Level, level::LevelPhysics};
+use crate::{
+    Level, LevelDocument, LevelEditor, LevelTemplates, Sprite,
+    level::LevelPhysics,
+    sprite_registry::{SpriteRegistry, image_name},
+};
 
 static SELF: MainLock<LevelManager> = MainLock::new();
 
@@ -20,8 +30,9 @@ pub struct LevelManager {
     scale:      f32,
     camera_pos: Point,
 
-    #[educe(Default = 1.0 / 60.0)]
-    update_interval: f32,
+    /// Seeds [`crate::LevelBase::rng`] of next level.
+    #[educe(Default = rand::random())]
+    seed: u64,
 
     level: Option<Own<dyn Level>>,
 }
@@ -40,21 +51,82 @@ impl LevelManager {
             return;
         }
 
-        Self::level().__internal_update(*Self::update_interval());
+        Self::level().__internal_update(WGPUApp::current().frame_time());
     }
 }
 
 impl LevelManager {
     pub fn set_level<T: Level + 'static>(level: T) -> Weak<T> {
         let l = SELF.get_mut();
-        let level = Own::new(level);
+        let mut level = Own::new(level);
+        level.rng = StdRng::seed_from_u64(l.seed);
         let weak = level.weak();
+        LevelEditor::reset();
         l.level = Some(level);
         l.level.as_ref().unwrap().__internal_setup();
         weak
     }
 
+    /// Makes sprite type `S` savable and loadable as `kind` in level
+    /// documents. Built in sprites are registered by their type name.
+    pub fn register_sprite<S: Sprite + 'static>(kind: impl ToString) {
+        SpriteRegistry::register::<S>(kind);
+    }
+
+    /// Describes current level and its sprites.
+    pub fn level_document() -> Result<LevelDocument> {
+        let level = Self::level();
+
+        let sprites = level
+            .sprites()
+            .iter()
+            .map(|sprite| SpriteRegistry::document(sprite.deref()))
+            .collect::<Result<_>>()?;
+
+        Ok(LevelDocument {
+            version: LevelDocument::VERSION,
+            background: image_name(level.background),
+            gravity: level
+                .physics
+                .as_ref()
+                .map(|physics| Point::new(physics.gravity.x, physics.gravity.y)),
+            sprites,
+        })
+    }
+
+    pub fn save_level(path: impl AsRef<Path>) -> Result<()> {
+        Self::level_document()?.save(path)
+    }
+
+    /// Replaces sprites of current level with the ones from level document.
+    pub fn load_level_from(path: impl AsRef<Path>) -> Result<()> {
+        Self::load_document(&LevelDocument::load(path)?)
+    }
+
+    pub fn load_document(document: &LevelDocument) -> Result<()> {
+        let mut level = Self::level_weak();
+
+        level.remove_all_sprites();
+
+        if document.needs_physics() && level.physics.is_none() {
+            level.init_physics();
+        }
+
+        if let Some(gravity) = document.gravity {
+            level.set_gravity(gravity);
+        }
+
+        level.background = document.background.as_ref().map(Image::get).unwrap_or_default();
+
+        for sprite in &document.sprites {
+            SpriteRegistry::make(level.deref_mut(), sprite)?;
+        }
+
+        Ok(())
+    }
+
     pub fn stop_level() {
+        LevelEditor::reset();
         SELF.get_mut().level = None;
         *Self::scale() = 1.0;
         *Self::camera_pos() = (0, 0).into();
@@ -101,8 +173,14 @@ impl LevelManager {
         &mut SELF.get_mut().scale
     }
 
-    pub fn update_interval() -> &'static mut f32 {
-        &mut SELF.get_mut().update_interval
+    /// Same seed and input produce the same level. Applied in
+    /// [`LevelManager::set_level`].
+    pub fn seed() -> u64 {
+        SELF.seed
+    }
+
+    pub fn set_seed(seed: u64) {
+        SELF.get_mut().seed = seed;
     }
 
     pub fn camera_pos() -> &'static mut Point {
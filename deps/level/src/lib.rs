@@ -2,23 +2,46 @@
 #![feature(box_into_inner)]
 #![feature(arbitrary_self_types)]
 #![feature(specialization)]
+#![feature(map_many_mut)]
 
 extern crate core;
 
+mod animation;
+mod collision;
 mod control;
+mod ecs;
+mod editor;
 mod event_handler;
+mod fixed_timestep;
+mod joints;
 mod level;
+mod level_document;
 mod level_manager;
+mod one_way_platforms;
 mod sets;
 mod sprite_data;
+mod sprite_registry;
 mod to_collider;
 mod units;
 
+pub use animation::{AnimationClip, Animator, Frame, PlayMode, SpriteSheet};
+pub use collision::{Collision, CollisionGroups, Contact, ContactManifold};
 pub use control::Control;
-pub use level::{Level, LevelBase, LevelCreation, LevelInternal, LevelSetup, LevelTemplates};
+pub use ecs::{Components, SystemStage};
+pub use editor::{Gizmo, LevelEditor};
+pub use fixed_timestep::FixedTimestep;
+pub use joints::{
+    FixedJoint, Joint, JointBuilder, JointSettings, Motor, PrismaticJoint, Ragdoll, RevoluteJoint, RopeJoint,
+    SpringJoint,
+};
+pub use level::{
+    Level, LevelBase, LevelCreation, LevelInternal, LevelSetup, LevelSnapshot, LevelTemplates, RayHit,
+    Rewind, SpriteFilter,
+};
+pub use level_document::{LevelDocument, PhysicsDocument, SpriteDocument};
 pub use level_manager::LevelManager;
 pub use level_proc::level;
 pub use rapier2d::dynamics::CoefficientCombineRule;
-pub use sprite_data::SpriteData;
+pub use sprite_data::{Flip, SpriteData};
 pub use to_collider::ToCollider;
 pub use units::*;
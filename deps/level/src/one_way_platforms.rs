@@ -0,0 +1,33 @@
+use std::collections::HashSet;
+
+use rapier2d::{
+    geometry::ColliderHandle,
+    na::Vector2,
+    pipeline::PhysicsHooks,
+    prelude::ContactModificationContext,
+};
+
+/// Platforms sprites can jump through from below and stand on from above.
+#[derive(Default)]
+pub(crate) struct OneWayPlatforms {
+    pub(crate) colliders: HashSet<ColliderHandle>,
+}
+
+impl PhysicsHooks for OneWayPlatforms {
+    fn modify_solver_contacts(&self, context: &mut ContactModificationContext) {
+        // Contact normal points from first collider to the second one and is
+        // in local space of the first collider
+        let allowed_normal = if self.colliders.contains(&context.collider1) {
+            Vector2::y()
+        } else if self.colliders.contains(&context.collider2) {
+            let platform_up = context.colliders[context.collider2].rotation() * Vector2::y();
+            context.colliders[context.collider1]
+                .rotation()
+                .inverse_transform_vector(&-platform_up)
+        } else {
+            return;
+        };
+
+        context.update_as_oneway_platform(&allowed_normal, 0.1);
+    }
+}
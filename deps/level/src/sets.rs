@@ -4,7 +4,7 @@ use rapier2d::{
     prelude::{ColliderSet, RigidBodySet},
 };
 
-#[derive(Default)]
+#[derive(Clone, Default)]
 pub(crate) struct Sets {
     pub rigid_bodies: RigidBodySet,
     pub colliders:    ColliderSet,
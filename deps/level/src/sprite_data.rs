@@ -1,19 +1,27 @@
 use educe::Educe;
 use gm::{
     Color,
-    flat::{Point, PointsPath, Shape, Size},
+    flat::{Point, PointsPath, Rect, Shape, Size},
 };
 use refs::Weak;
 use vents::Event;
 use wgpu_wrapper::{VertexBuffer, image::Image};
 
-use crate::Sprite;
+use crate::{Collision, Sprite};
+
+/// Mirrors sprite image along its axes.
+#[derive(Copy, Clone, Default, Debug, PartialEq, Eq)]
+pub struct Flip {
+    pub x: bool,
+    pub y: bool,
+}
 
 #[derive(Educe)]
 #[educe(Default)]
 pub struct SpriteData {
     pub(crate) position: Point,
 
+    pub(crate) shape:       Shape,
     pub(crate) size:        Size,
     pub(crate) render_size: Size,
     pub(crate) rotation:    f32,
@@ -29,30 +37,64 @@ pub struct SpriteData {
     #[educe(Default = 0.85)]
     pub z_position: f32,
 
-    pub image:        Weak<Image>,
-    pub on_collision: Event<Weak<dyn Sprite>>,
+    pub image:            Weak<Image>,
+    /// Part of `image` to draw in texture coordinates. Whole image by default.
+    /// Set by [`crate::Animator`].
+    #[educe(Default = Rect::new(0.0, 0.0, 1.0, 1.0))]
+    pub image_rect:       Rect,
+    pub flip:             Flip,
+    /// Triggered when collision detection is enabled. See
+    /// [`crate::Sprite::enable_collision_detection`].
+    pub on_collision:     Event<Collision>,
+    pub on_collision_end: Event<Weak<dyn Sprite>>,
 
     pub vertex_buffer: Option<VertexBuffer>,
 }
 
 impl SpriteData {
     pub fn make(shape: Shape, position: Point) -> Self {
-        Self {
+        let mut data = Self {
             position,
-            size: shape.size(),
-            // Rapier rect size defined by half side
-            render_size: if shape.is_rect() {
-                shape.size() / 2.0
-            } else {
-                shape.size()
-            },
-            vertex_buffer: Self::shape_to_buffer(shape),
             ..Default::default()
+        };
+        data.reshape(shape);
+        data
+    }
+
+    pub fn shape(&self) -> &Shape {
+        &self.shape
+    }
+
+    /// `image_rect` with flipping applied.
+    pub fn uv(&self) -> Rect {
+        let mut uv = self.image_rect;
+        if self.flip.x {
+            uv.origin.x += uv.size.width;
+            uv.size.width = -uv.size.width;
+        }
+        if self.flip.y {
+            uv.origin.y += uv.size.height;
+            uv.size.height = -uv.size.height;
         }
+        uv
     }
 }
 
 impl SpriteData {
+    /// Updates drawing data. Collider is updated by
+    /// [`crate::SpriteTemplates::set_shape`].
+    pub(crate) fn reshape(&mut self, shape: Shape) {
+        self.size = shape.size();
+        // Rapier rect size defined by half side
+        self.render_size = if shape.is_rect() {
+            shape.size() / 2.0
+        } else {
+            shape.size()
+        };
+        self.vertex_buffer = Self::shape_to_buffer(shape.clone());
+        self.shape = shape;
+    }
+
     fn shape_to_buffer(shape: Shape) -> Option<VertexBuffer> {
         match shape {
             Shape::Circle(_) | Shape::Rect(_) => None,
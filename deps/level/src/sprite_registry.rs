@@ -0,0 +1,212 @@
+use std::collections::HashMap;
+
+use anyhow::{Result, anyhow};
+use educe::Educe;
+use manage::data_manager::DataManager;
+use rapier2d::dynamics::LockedAxes;
+use refs::{MainLock, Weak};
+use wgpu_wrapper::image::Image;
+
+use crate::{
+    Banner, Body, Level, LevelCreation, PhysicsDocument, Player, Sensor, Sprite, SpriteDocument,
+    SpriteTemplates, Unit, Wall,
+};
+
+static REGISTRY: MainLock<SpriteRegistry> = MainLock::new();
+
+type Make = fn(&mut dyn Level, &SpriteDocument) -> Weak<dyn Sprite>;
+
+/// Maps sprite kinds in level documents to Rust types.
+#[derive(Educe)]
+#[educe(Default)]
+pub(crate) struct SpriteRegistry {
+    #[educe(Default = builtin())]
+    kinds:      HashMap<String, Make>,
+    #[educe(Default = builtin_names())]
+    type_names: HashMap<&'static str, String>,
+}
+
+fn builtin() -> HashMap<String, Make> {
+    HashMap::from([
+        ("Body".to_string(), make::<Body> as Make),
+        ("Wall".to_string(), make::<Wall>),
+        ("Sensor".to_string(), make::<Sensor>),
+        ("Banner".to_string(), make::<Banner>),
+        ("Unit".to_string(), make::<Unit>),
+        ("Player".to_string(), make::<Player>),
+    ])
+}
+
+fn builtin_names() -> HashMap<&'static str, String> {
+    HashMap::from([
+        (type_name::<Body>(), "Body".to_string()),
+        (type_name::<Wall>(), "Wall".to_string()),
+        (type_name::<Sensor>(), "Sensor".to_string()),
+        (type_name::<Banner>(), "Banner".to_string()),
+        (type_name::<Unit>(), "Unit".to_string()),
+        (type_name::<Player>(), "Player".to_string()),
+    ])
+}
+
+fn type_name<S: Sprite>() -> &'static str {
+    std::any::type_name::<S>()
+}
+
+impl SpriteRegistry {
+    pub(crate) fn register<S: Sprite + 'static>(kind: impl ToString) {
+        let kind = kind.to_string();
+        let registry = REGISTRY.get_mut();
+        registry.kinds.insert(kind.clone(), make::<S>);
+        registry.type_names.insert(type_name::<S>(), kind);
+    }
+
+    pub(crate) fn kind_of(sprite: &dyn Sprite) -> Result<String> {
+        REGISTRY.type_names.get(sprite.type_name()).cloned().ok_or_else(|| {
+            anyhow!(
+                "Sprite type {} is not registered. Use LevelManager::register_sprite",
+                sprite.type_name()
+            )
+        })
+    }
+
+    pub(crate) fn document(sprite: &dyn Sprite) -> Result<SpriteDocument> {
+        let physics = sprite.collider_handle().map(|_| {
+            let collider = sprite.collider();
+            PhysicsDocument {
+                friction:            collider.friction(),
+                restitution:         collider.restitution(),
+                density:             collider.density(),
+                rotation_locked:     sprite
+                    .rigid_handle()
+                    .is_some_and(|_| sprite.rigid_body().locked_axes().contains(LockedAxes::ROTATION_LOCKED)),
+                collision_detection: sprite.collision_enabled,
+                collision_groups:    sprite.collision_groups(),
+                one_way_platform:    sprite.is_one_way_platform(),
+            }
+        });
+
+        Ok(SpriteDocument {
+            kind: Self::kind_of(sprite)?,
+            shape: sprite.shape().clone(),
+            position: sprite.position(),
+            rotation: sprite.rotation(),
+            color: *sprite.color(),
+            image: image_name(sprite.image),
+            tag: sprite.tag,
+            z_position: Some(sprite.z_position),
+            physics,
+        })
+    }
+
+    pub(crate) fn make(level: &mut dyn Level, document: &SpriteDocument) -> Result<Weak<dyn Sprite>> {
+        let make = REGISTRY
+            .kinds
+            .get(&document.kind)
+            .copied()
+            .ok_or_else(|| anyhow!("Unknown sprite kind: {}", document.kind))?;
+
+        Ok(make(level, document))
+    }
+
+    /// Applies `document` to existing sprite keeping its joints, components
+    /// and subscriptions. Returns `false` if sprite has to be recreated
+    /// because its kind or collision detection differs.
+    pub(crate) fn update(sprite: &mut dyn Sprite, document: &SpriteDocument) -> bool {
+        if Self::kind_of(sprite).ok().as_ref() != Some(&document.kind) {
+            return false;
+        }
+
+        let collision_detection =
+            document.physics.as_ref().is_some_and(|physics| physics.collision_detection);
+
+        if sprite.collider_handle().is_some() && sprite.collision_enabled != collision_detection {
+            return false;
+        }
+
+        if sprite.shape() != &document.shape {
+            sprite.set_shape(document.shape.clone());
+        }
+
+        sprite.set_position(document.position);
+        sprite.set_rotation(document.rotation);
+        sprite.set_image(document.image.as_ref().map(Image::get).unwrap_or_default());
+
+        apply(sprite, document);
+
+        if let Some(physics) = &document.physics {
+            if sprite.collider_handle().is_some() && !physics.one_way_platform {
+                sprite.set_one_way_platform(false);
+            }
+            if !physics.rotation_locked {
+                sprite.unlock_rotation();
+            }
+        }
+
+        true
+    }
+}
+
+fn make<S: Sprite + 'static>(level: &mut dyn Level, document: &SpriteDocument) -> Weak<dyn Sprite> {
+    let mut sprite = level.make_sprite::<S>(document.shape.clone(), document.position);
+
+    let collision_detection = document.physics.as_ref().is_some_and(|physics| physics.collision_detection);
+
+    if collision_detection && sprite.collider_handle().is_some() && !sprite.collision_enabled {
+        sprite.enable_collision_detection();
+    }
+
+    let mut weak = level.sprites.last().expect("Sprite was just added").weak();
+    apply(&mut *weak, document);
+    weak
+}
+
+fn apply(sprite: &mut dyn Sprite, document: &SpriteDocument) {
+    sprite.set_color(document.color);
+    sprite.tag = document.tag;
+
+    if let Some(image) = &document.image {
+        sprite.set_image(Image::get(image));
+    }
+
+    if let Some(z_position) = document.z_position {
+        sprite.z_position = z_position;
+    }
+
+    if document.rotation != 0.0 {
+        sprite.set_rotation(document.rotation);
+    }
+
+    if let Some(physics) = &document.physics {
+        apply_physics(sprite, physics);
+    }
+}
+
+fn apply_physics(sprite: &mut dyn Sprite, physics: &PhysicsDocument) {
+    if sprite.collider_handle().is_some() {
+        let collider = sprite.collider_mut();
+        collider.set_friction(physics.friction);
+        collider.set_restitution(physics.restitution);
+        collider.set_density(physics.density);
+        sprite.set_collision_groups(physics.collision_groups);
+
+        if physics.one_way_platform {
+            sprite.set_one_way_platform(true);
+        }
+    }
+
+    if physics.rotation_locked {
+        sprite.lock_rotations();
+    }
+}
+
+/// Name of image in image storage. `None` if image was not loaded by name.
+pub(crate) fn image_name(image: Weak<Image>) -> Option<String> {
+    if image.is_null() {
+        return None;
+    }
+
+    Image::storage()
+        .iter()
+        .find(|(_, stored)| stored.addr() == image.addr())
+        .map(|(name, _)| name.clone())
+}
@@ -25,12 +25,10 @@ impl Sprite for Player {
         self.weapon.position = self.unit.position();
         self.weapon.velocity = self.unit.body.velocity();
 
-        // if !self.image.is_empty() {
-        //     self.image().flipped = cursor.x < self.position().x;
-        // }
-        // if self.weapon.image.is_ok() {
-        //     self.weapon.image().flipped_y = cursor.x < self.position().x;
-        // }
+        // Face the cursor
+        let flip = cursor.x < self.position().x;
+        self.flip.x = flip;
+        self.weapon.flip.y = flip;
     }
 
     fn position(&self) -> Point {
@@ -45,6 +43,14 @@ impl Sprite for Player {
     fn rotation(&self) -> f32 {
         self.unit.rotation()
     }
+
+    fn render_position(&self) -> Point {
+        self.unit.render_position()
+    }
+
+    fn render_rotation(&self) -> f32 {
+        self.unit.render_rotation()
+    }
 }
 
 impl Deref for Player {
@@ -9,14 +9,15 @@ use gm::{
 };
 use rapier2d::{
     dynamics::RigidBodyHandle,
-    geometry::{Collider, ColliderHandle},
-    pipeline::ActiveEvents,
+    geometry::{Collider, ColliderHandle, Group, InteractionGroups},
+    na::Isometry2,
+    pipeline::{ActiveEvents, ActiveHooks},
     prelude::{CoefficientCombineRule, RigidBody, Rotation},
 };
 use refs::{Address, Own, weak_from_ref};
 use wgpu_wrapper::image::ToImage;
 
-use crate::{LevelManager, SpriteData};
+use crate::{CollisionGroups, LevelEditor, LevelManager, SpriteData, ToCollider};
 
 pub trait Sprite: Deref<Target = SpriteData> + DerefMut {
     fn make(shape: Shape, position: Point) -> Own<Self>
@@ -24,6 +25,12 @@ pub trait Sprite: Deref<Target = SpriteData> + DerefMut {
 
     fn update(&mut self) {}
 
+    /// Used to find sprite kind when level is saved. See
+    /// [`LevelManager::register_sprite`].
+    fn type_name(&self) -> &'static str {
+        type_name::<Self>()
+    }
+
     fn size(&self) -> Size {
         self.size
     }
@@ -49,6 +56,23 @@ pub trait Sprite: Deref<Target = SpriteData> + DerefMut {
         self.position
     }
 
+    /// Position interpolated between last two physics steps. Use it for
+    /// rendering so movement is smooth at any frame rate.
+    fn render_position(&self) -> Point {
+        let Some(pose) = self.rigid_handle().and_then(interpolated_pose) else {
+            return self.position();
+        };
+        Point::new(pose.translation.x, pose.translation.y)
+    }
+
+    /// See [`Sprite::render_position`].
+    fn render_rotation(&self) -> f32 {
+        let Some(pose) = self.rigid_handle().and_then(interpolated_pose) else {
+            return self.rotation();
+        };
+        pose.rotation.angle()
+    }
+
     fn set_x(&mut self, x: f32) {
         let mut pos = self.position();
         pos.x = x;
@@ -110,6 +134,20 @@ pub trait Sprite: Deref<Target = SpriteData> + DerefMut {
             .insert(weak.collider_handle().unwrap(), weak);
     }
 
+    /// [`CollisionGroups::ALL`] for sprites without collider.
+    fn collision_groups(&self) -> CollisionGroups {
+        if self.collider_handle().is_none() {
+            return CollisionGroups::ALL;
+        }
+        let groups = self.collider().collision_groups();
+        CollisionGroups::new(groups.memberships.bits(), groups.filter.bits())
+    }
+
+    fn is_one_way_platform(&self) -> bool {
+        self.collider_handle()
+            .is_some_and(|handle| LevelManager::physics().one_way_platforms.colliders.contains(&handle))
+    }
+
     fn contains(&self, point: Point) -> bool {
         let pos = self.position();
         let size = self.size();
@@ -153,6 +191,14 @@ pub trait Sprite: Deref<Target = SpriteData> + DerefMut {
     }
 }
 
+fn interpolated_pose(handle: RigidBodyHandle) -> Option<Isometry2<f32>> {
+    if LevelEditor::is_enabled() {
+        return None;
+    }
+    let level = LevelManager::level();
+    level.physics.as_ref()?.interpolated_pose(handle, level.timestep.alpha())
+}
+
 pub trait SpriteTemplates {
     fn set_color(&mut self, _: Color) -> &mut Self;
     fn set_selected(&mut self, _: bool) -> &mut Self;
@@ -161,6 +207,12 @@ pub trait SpriteTemplates {
     fn set_restitution(&mut self, _: f32, _: CoefficientCombineRule) -> &mut Self;
     fn set_position(&mut self, _: impl Into<Point>) -> &mut Self;
     fn set_rotation(&mut self, _: impl ToF32) -> &mut Self;
+    fn set_collision_groups(&mut self, _: CollisionGroups) -> &mut Self;
+    /// Sprites pass through one way platform from below and land on it from
+    /// above.
+    fn set_one_way_platform(&mut self, _: bool) -> &mut Self;
+    /// Resizes collider in place so joints and subscriptions stay.
+    fn set_shape(&mut self, _: Shape) -> &mut Self;
 }
 
 impl<T: ?Sized + Sprite> SpriteTemplates for T {
@@ -192,6 +244,9 @@ impl<T: ?Sized + Sprite> SpriteTemplates for T {
 
     fn set_position(&mut self, pos: impl Into<Point>) -> &mut Self {
         let pos = pos.into();
+        if let Some(handle) = self.rigid_handle() {
+            LevelManager::physics().previous_poses.remove(&handle);
+        }
         if self.collider_handle().is_some() {
             self.collider_mut().set_position([pos.x, pos.y].into());
         } else if self.rigid_handle().is_some() {
@@ -203,7 +258,8 @@ impl<T: ?Sized + Sprite> SpriteTemplates for T {
 
     fn set_rotation(&mut self, rotation: impl ToF32) -> &mut Self {
         let rotation = rotation.to_f32();
-        if self.rigid_handle().is_some() {
+        if let Some(handle) = self.rigid_handle() {
+            LevelManager::physics().previous_poses.remove(&handle);
             self.rigid_body_mut().set_rotation(Rotation::new(rotation), true);
         }
         if self.collider_handle().is_some() {
@@ -213,4 +269,36 @@ impl<T: ?Sized + Sprite> SpriteTemplates for T {
         }
         self
     }
+
+    fn set_collision_groups(&mut self, groups: CollisionGroups) -> &mut Self {
+        self.collider_mut().set_collision_groups(InteractionGroups::new(
+            Group::from_bits_truncate(groups.layers),
+            Group::from_bits_truncate(groups.mask),
+        ));
+        self
+    }
+
+    fn set_one_way_platform(&mut self, one_way: bool) -> &mut Self {
+        let handle = self.collider_handle().expect("This sprite doesn't have collider");
+        let platforms = &mut LevelManager::physics().one_way_platforms.colliders;
+
+        if one_way {
+            platforms.insert(handle);
+            self.collider_mut().set_active_hooks(ActiveHooks::MODIFY_SOLVER_CONTACTS);
+        } else {
+            platforms.remove(&handle);
+            self.collider_mut().set_active_hooks(ActiveHooks::empty());
+        }
+
+        self
+    }
+
+    fn set_shape(&mut self, shape: Shape) -> &mut Self {
+        if self.collider_handle().is_some() {
+            let collider = shape.make_collider();
+            self.collider_mut().set_shape(collider.shape);
+        }
+        self.reshape(shape);
+        self
+    }
 }
@@ -0,0 +1,65 @@
+use std::{
+    fs::{self, File},
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+
+/// Writes bytes into an opened file. Swappable so tests can simulate
+/// interrupted writes.
+pub trait FileWriter: Send + Sync {
+    fn write(&self, file: &mut File, data: &[u8]) -> io::Result<()>;
+}
+
+pub struct FsWriter;
+
+impl FileWriter for FsWriter {
+    fn write(&self, file: &mut File, data: &[u8]) -> io::Result<()> {
+        file.write_all(data)
+    }
+}
+
+pub(crate) fn tmp_path(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.file_name().expect("Invalid storage path").to_os_string();
+    name.push(suffix);
+    path.with_file_name(name)
+}
+
+/// Writes whole file to `tmp` and optionally syncs it to disk.
+/// Removes `tmp` if anything fails.
+pub(crate) fn write_tmp(tmp: &Path, data: &[u8], sync: bool, writer: &dyn FileWriter) -> io::Result<()> {
+    let result = File::create(tmp).and_then(|mut file| {
+        writer.write(&mut file, data)?;
+        if sync {
+            file.sync_all()?;
+        }
+        Ok(())
+    });
+
+    if result.is_err() {
+        _ = fs::remove_file(tmp);
+    }
+
+    result
+}
+
+/// Makes completed renames in directory survive power loss.
+pub(crate) fn sync_dir(dir: &Path) -> io::Result<()> {
+    if cfg!(unix) {
+        File::open(dir)?.sync_all()?;
+    }
+    Ok(())
+}
+
+/// Write to temp file then rename so readers never see partially written data.
+pub(crate) fn write_atomic(path: &Path, data: &[u8], sync: bool, writer: &dyn FileWriter) -> io::Result<()> {
+    let tmp = tmp_path(path, ".tmp");
+
+    write_tmp(&tmp, data, sync, writer)?;
+    fs::rename(&tmp, path)?;
+
+    if sync {
+        sync_dir(path.parent().expect("Invalid storage path"))?;
+    }
+
+    Ok(())
+}
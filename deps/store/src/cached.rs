@@ -0,0 +1,200 @@
+use std::{
+    cell::RefCell,
+    ffi::OsStr,
+    fs,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+};
+
+use log::error;
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+use vents::Event;
+
+use crate::{
+    OnDisk, StoreError, StoreResult,
+    format::{Format, Json},
+    storable::Storable,
+};
+
+/// Keeps stored value in memory so it can be read every frame without touching
+/// the disk. Triggers `changed` when the value is updated.
+pub struct Cached<T: Storable + Clone + PartialEq + Send + 'static, F: Format = Json> {
+    storage: OnDisk<T, F>,
+    value:   RefCell<Option<T>>,
+    dirty:   Arc<AtomicBool>,
+    watcher: RefCell<Option<RecommendedWatcher>>,
+
+    pub changed: Event<T>,
+}
+
+impl<T: Storable + Clone + PartialEq + Send + 'static, F: Format> Cached<T, F> {
+    pub fn new(storage: OnDisk<T, F>) -> Self {
+        Self {
+            storage,
+            value: RefCell::default(),
+            dirty: Arc::default(),
+            watcher: RefCell::default(),
+            changed: Event::default(),
+        }
+    }
+
+    pub fn get(&self) -> T {
+        self.try_get()
+            .unwrap_or_else(|err| panic!("Failed to load '{}': {err}", self.storage.name()))
+    }
+
+    pub fn set(&self, val: impl Into<T>) {
+        self.try_set(val)
+            .unwrap_or_else(|err| panic!("Failed to store '{}': {err}", self.storage.name()));
+    }
+
+    pub fn try_get(&self) -> StoreResult<T> {
+        self.refresh()?;
+
+        if let Some(value) = self.value.borrow().as_ref() {
+            return Ok(value.clone());
+        }
+
+        let value = self.storage.try_get()?;
+        self.value.replace(Some(value.clone()));
+        Ok(value)
+    }
+
+    pub fn try_set(&self, val: impl Into<T>) -> StoreResult<()> {
+        let val = val.into();
+
+        // Cached value may be stale after an external edit
+        self.refresh()?;
+
+        if self.value.borrow().as_ref() == Some(&val) {
+            return Ok(());
+        }
+
+        self.storage.try_set(val.clone())?;
+        self.value.replace(Some(val.clone()));
+        self.changed.trigger(val);
+
+        Ok(())
+    }
+
+    /// Watch storage directory so changes made by another process or window
+    /// are picked up on next access.
+    pub fn watch(&self) -> StoreResult<()> {
+        if self.watcher.borrow().is_some() {
+            return Ok(());
+        }
+
+        let dirty = self.dirty.clone();
+        let name = self.storage.name();
+
+        let mut watcher =
+            notify::recommended_watcher(move |event: notify::Result<notify::Event>| match event {
+                Ok(event) => {
+                    if event.paths.iter().any(|path| path.file_name() == Some(OsStr::new(name))) {
+                        dirty.store(true, Ordering::Relaxed);
+                    }
+                }
+                Err(err) => error!("Storage watcher error: {err}"),
+            })
+            .map_err(StoreError::Watch)?;
+
+        let path = self.storage.path();
+        let dir = path.parent().expect("Invalid storage path");
+        fs::create_dir_all(dir)?;
+
+        watcher.watch(dir, RecursiveMode::NonRecursive).map_err(StoreError::Watch)?;
+
+        self.watcher.replace(Some(watcher));
+
+        Ok(())
+    }
+
+    /// Reloads value if it was modified on disk. Triggers `changed` if reloaded
+    /// value is different.
+    pub fn refresh(&self) -> StoreResult<()> {
+        if !self.dirty.swap(false, Ordering::Relaxed) {
+            return Ok(());
+        }
+
+        let value = self.storage.try_get()?;
+
+        if self.value.borrow().as_ref() == Some(&value) {
+            return Ok(());
+        }
+
+        self.value.replace(Some(value.clone()));
+        self.changed.trigger(value);
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{
+        fs,
+        sync::{Arc, Mutex, atomic::Ordering},
+        thread::sleep,
+        time::{Duration, Instant},
+    };
+
+    use anyhow::Result;
+
+    use crate::{Cached, OnDisk, Paths};
+
+    #[test]
+    fn cached() -> Result<()> {
+        let _storage = Paths::isolated_storage();
+
+        let cached = Cached::new(OnDisk::<i32>::new("cached_test"));
+        cached.set(0);
+        cached.watch()?;
+
+        let received = Arc::new(Mutex::new(vec![]));
+
+        let capture = received.clone();
+        cached.changed.val(move |val| capture.lock().unwrap().push(val));
+
+        cached.set(5);
+        cached.set(5);
+        assert_eq!(cached.get(), 5);
+        assert_eq!(*received.lock().unwrap(), vec![5]);
+
+        // Simulate edit from another process.
+        OnDisk::<i32>::new("cached_test").set(10);
+        assert!(Paths::storage().join("cached_test").exists());
+
+        let start = Instant::now();
+        while cached.get() != 10 {
+            assert!(
+                start.elapsed() < Duration::from_secs(5),
+                "Change was not detected"
+            );
+            sleep(Duration::from_millis(10));
+        }
+
+        assert_eq!(*received.lock().unwrap(), vec![5, 10]);
+
+        // Writing the cached value back after an external edit is not a no-op.
+        OnDisk::<i32>::new("cached_test").set(20);
+
+        let start = Instant::now();
+        while !cached.dirty.load(Ordering::Relaxed) {
+            assert!(
+                start.elapsed() < Duration::from_secs(5),
+                "Change was not detected"
+            );
+            sleep(Duration::from_millis(10));
+        }
+
+        cached.set(10);
+        assert_eq!(OnDisk::<i32>::new("cached_test").get(), 10);
+        assert_eq!(*received.lock().unwrap(), vec![5, 10, 20, 10]);
+
+        fs::remove_file(Paths::storage().join("cached_test"))?;
+
+        Ok(())
+    }
+}
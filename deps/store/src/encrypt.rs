@@ -2,60 +2,208 @@ use aes_gcm::{
     Aes256Gcm,
     aead::{Aead, KeyInit, Nonce},
 };
+use argon2::Argon2;
+use rand::{RngCore, thread_rng};
+
+use crate::{StoreError, StoreResult};
 
 const AES_KEY_SIZE: usize = 32;
 const NONCE_SIZE: usize = 12;
-pub const KEY_SIZE: usize = AES_KEY_SIZE + NONCE_SIZE;
+const LEGACY_KEY_SIZE: usize = AES_KEY_SIZE + NONCE_SIZE;
+
+pub const SALT_SIZE: usize = 16;
+
+/// Envelope: `MAGIC | FORMAT_VERSION | nonce | ciphertext`.
+const MAGIC: &[u8; 4] = b"TEnc";
+const FORMAT_VERSION: u8 = 1;
+const HEADER_SIZE: usize = MAGIC.len() + 1 + NONCE_SIZE;
+
+#[derive(Debug)]
+pub(crate) enum DecryptError {
+    Authentication,
+    InvalidEnvelope(&'static str),
+}
 
-pub type EncryptionKey = [u8; KEY_SIZE];
+#[derive(Clone, PartialEq, Eq)]
+pub struct EncryptionKey {
+    key:          [u8; AES_KEY_SIZE],
+    /// Nonce which was stored in keys before values got per write nonces.
+    /// Only used to read values written in legacy format.
+    legacy_nonce: Option<[u8; NONCE_SIZE]>,
+}
 
-fn to_key_nonce(key: &EncryptionKey) -> (aes_gcm::Key<Aes256Gcm>, Nonce<Aes256Gcm>) {
-    let nonce = &key[AES_KEY_SIZE..];
-    let key = &key[..AES_KEY_SIZE];
+impl EncryptionKey {
+    pub const fn new(key: [u8; AES_KEY_SIZE]) -> Self {
+        Self {
+            key,
+            legacy_nonce: None,
+        }
+    }
 
-    (
-        *aes_gcm::Key::<Aes256Gcm>::from_slice(key),
-        *Nonce::<Aes256Gcm>::from_slice(nonce),
-    )
+    pub fn random() -> Self {
+        let mut key = [0; AES_KEY_SIZE];
+        thread_rng().fill_bytes(&mut key);
+        Self::new(key)
+    }
+
+    /// Derives key from a passphrase with Argon2. Salt must be at least 8 bytes
+    /// and stored alongside the data to derive the same key again.
+    pub fn from_password(password: &str, salt: &[u8]) -> StoreResult<Self> {
+        let mut key = [0; AES_KEY_SIZE];
+        Argon2::default()
+            .hash_password_into(password.as_bytes(), salt, &mut key)
+            .map_err(|err| StoreError::KeyDerivation(err.to_string()))?;
+        Ok(Self::new(key))
+    }
+
+    pub fn generate_salt() -> [u8; SALT_SIZE] {
+        let mut salt = [0; SALT_SIZE];
+        thread_rng().fill_bytes(&mut salt);
+        salt
+    }
+
+    fn cipher(&self) -> Aes256Gcm {
+        Aes256Gcm::new(aes_gcm::Key::<Aes256Gcm>::from_slice(&self.key))
+    }
+}
+
+/// Old keys packed a fixed nonce after the AES key.
+impl From<[u8; LEGACY_KEY_SIZE]> for EncryptionKey {
+    fn from(bytes: [u8; LEGACY_KEY_SIZE]) -> Self {
+        Self {
+            key:          bytes[..AES_KEY_SIZE].try_into().unwrap(),
+            legacy_nonce: Some(bytes[AES_KEY_SIZE..].try_into().unwrap()),
+        }
+    }
 }
 
 pub fn encrypt(data: &[u8], key: &EncryptionKey) -> Vec<u8> {
-    let (key, nonce) = to_key_nonce(key);
-    let cipher = Aes256Gcm::new(&key);
-    cipher.encrypt(&nonce, data).unwrap()
+    let mut nonce = [0; NONCE_SIZE];
+    thread_rng().fill_bytes(&mut nonce);
+
+    let encrypted = key
+        .cipher()
+        .encrypt(Nonce::<Aes256Gcm>::from_slice(&nonce), data)
+        .expect("Failed to encrypt data");
+
+    let mut result = Vec::with_capacity(HEADER_SIZE + encrypted.len());
+    result.extend_from_slice(MAGIC);
+    result.push(FORMAT_VERSION);
+    result.extend_from_slice(&nonce);
+    result.extend_from_slice(&encrypted);
+    result
 }
 
-pub fn decrypt(data: &[u8], key: &EncryptionKey) -> Vec<u8> {
-    let (key, nonce) = to_key_nonce(key);
-    let cipher = Aes256Gcm::new(&key);
-    cipher.decrypt(&nonce, data).unwrap()
+pub(crate) fn decrypt(data: &[u8], key: &EncryptionKey) -> Result<Vec<u8>, DecryptError> {
+    let Some(rest) = data.strip_prefix(MAGIC) else {
+        return decrypt_legacy(data, key);
+    };
+
+    let Some((&version, rest)) = rest.split_first() else {
+        return Err(DecryptError::InvalidEnvelope("missing format version"));
+    };
+
+    if version != FORMAT_VERSION {
+        return Err(DecryptError::InvalidEnvelope("unsupported format version"));
+    }
+
+    if rest.len() < NONCE_SIZE {
+        return Err(DecryptError::InvalidEnvelope("missing nonce"));
+    }
+
+    let (nonce, encrypted) = rest.split_at(NONCE_SIZE);
+
+    key.cipher()
+        .decrypt(Nonce::<Aes256Gcm>::from_slice(nonce), encrypted)
+        .map_err(|_| DecryptError::Authentication)
+}
+
+fn decrypt_legacy(data: &[u8], key: &EncryptionKey) -> Result<Vec<u8>, DecryptError> {
+    let Some(nonce) = &key.legacy_nonce else {
+        return Err(DecryptError::InvalidEnvelope(
+            "legacy format requires key with legacy nonce",
+        ));
+    };
+
+    key.cipher()
+        .decrypt(Nonce::<Aes256Gcm>::from_slice(nonce), data)
+        .map_err(|_| DecryptError::Authentication)
 }
 
 #[cfg(test)]
 mod test {
-
-    use rand::{RngCore, thread_rng};
+    use aes_gcm::{
+        Aes256Gcm,
+        aead::{Aead, Nonce},
+    };
 
     use crate::{
         EncryptionKey,
-        encrypt::{KEY_SIZE, decrypt, encrypt},
+        encrypt::{DecryptError, decrypt, encrypt},
     };
 
+    const DATA: &[u8] = b"SOKOLLL!! fjdsa fjasd;k flkdsa hfjklsda lfdkkadshksalkjaskjd jljljsdslkjsksj";
+
     #[test]
     fn test() {
-        let mut key: EncryptionKey = [0; KEY_SIZE];
-        thread_rng().fill_bytes(&mut key);
+        let key = EncryptionKey::random();
+
+        let encrypted = encrypt(DATA, &key);
+
+        let decrypted = decrypt(&encrypted, &key).unwrap();
+
+        assert_eq!(decrypted, DATA);
+    }
+
+    #[test]
+    fn unique_nonces() {
+        let key = EncryptionKey::random();
+        assert_ne!(encrypt(DATA, &key), encrypt(DATA, &key));
+    }
+
+    #[test]
+    fn tampered() {
+        let key = EncryptionKey::random();
+        let mut encrypted = encrypt(DATA, &key);
+
+        let last = encrypted.len() - 1;
+        encrypted[last] ^= 1;
+
+        assert!(matches!(
+            decrypt(&encrypted, &key),
+            Err(DecryptError::Authentication)
+        ));
+
+        assert!(matches!(
+            decrypt(&encrypt(DATA, &key), &EncryptionKey::random()),
+            Err(DecryptError::Authentication)
+        ));
+
+        assert!(matches!(
+            decrypt(b"TEnc", &key),
+            Err(DecryptError::InvalidEnvelope(_))
+        ));
+    }
+
+    #[test]
+    fn password() {
+        let salt = EncryptionKey::generate_salt();
+
+        let key = EncryptionKey::from_password("hunter2", &salt).unwrap();
 
-        let encrypted = encrypt(
-            b"SOKOLLL!! fjdsa fjasd;k flkdsa hfjklsda lfdkkadshksalkjaskjd jljljsdslkjsksj",
-            &key,
-        );
+        assert!(key == EncryptionKey::from_password("hunter2", &salt).unwrap());
+        assert!(key != EncryptionKey::from_password("hunter3", &salt).unwrap());
+        assert!(EncryptionKey::from_password("hunter2", b"short").is_err());
+    }
+
+    #[test]
+    fn legacy() {
+        let bytes: [u8; 44] = [7; 44];
+        let key = EncryptionKey::from(bytes);
 
-        let decrypted = decrypt(&encrypted, &key);
+        let cipher = key.cipher();
+        let legacy = cipher.encrypt(Nonce::<Aes256Gcm>::from_slice(&[7; 12]), DATA).unwrap();
 
-        assert_eq!(
-            decrypted,
-            b"SOKOLLL!! fjdsa fjasd;k flkdsa hfjklsda lfdkkadshksalkjaskjd jljljsdslkjsksj"
-        );
+        assert_eq!(decrypt(&legacy, &key).unwrap(), DATA);
     }
 }
@@ -0,0 +1,105 @@
+use std::{
+    error::Error,
+    fmt::{Display, Formatter},
+    io,
+    path::PathBuf,
+};
+
+pub type StoreResult<T> = Result<T, StoreError>;
+
+#[derive(Debug)]
+pub enum StoreError {
+    Io(io::Error),
+    Serialize(anyhow::Error),
+    Corrupted {
+        key:         String,
+        quarantined: Option<PathBuf>,
+        error:       anyhow::Error,
+    },
+    UnsupportedVersion {
+        key:     String,
+        version: u32,
+        current: u32,
+    },
+    Migration {
+        key:   String,
+        from:  u32,
+        error: anyhow::Error,
+    },
+    /// Wrong key or encrypted data was tampered with.
+    Authentication {
+        key: String,
+    },
+    InvalidEnvelope {
+        key:    String,
+        reason: &'static str,
+    },
+    KeyDerivation(String),
+    Watch(notify::Error),
+    /// Profile name is empty or not a single path component.
+    InvalidProfile(String),
+}
+
+impl Display for StoreError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(error) => write!(f, "Storage IO error: {error}"),
+            Self::Serialize(error) => write!(f, "Failed to serialize value: {error}"),
+            Self::Corrupted {
+                key,
+                quarantined,
+                error,
+            } => {
+                write!(f, "Stored value '{key}' is corrupted: {error}")?;
+                if let Some(path) = quarantined {
+                    write!(f, ". Moved to: {}", path.display())?;
+                }
+                Ok(())
+            }
+            Self::UnsupportedVersion {
+                key,
+                version,
+                current,
+            } => write!(
+                f,
+                "Stored value '{key}' has version {version} which is newer than supported {current}"
+            ),
+            Self::Migration { key, from, error } => {
+                write!(f, "Failed to migrate '{key}' from version {from}: {error}")
+            }
+            Self::Authentication { key } => write!(
+                f,
+                "Failed to authenticate encrypted value '{key}'. Wrong key or tampered data"
+            ),
+            Self::InvalidEnvelope { key, reason } => {
+                write!(f, "Invalid encrypted value '{key}': {reason}")
+            }
+            Self::KeyDerivation(error) => write!(f, "Failed to derive encryption key: {error}"),
+            Self::Watch(error) => write!(f, "Failed to watch storage directory: {error}"),
+            Self::InvalidProfile(name) => write!(f, "Invalid profile name: '{name}'"),
+        }
+    }
+}
+
+impl Error for StoreError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Io(error) => Some(error),
+            Self::Watch(error) => Some(error),
+            Self::Serialize(error) | Self::Corrupted { error, .. } | Self::Migration { error, .. } => {
+                Some(error.as_ref())
+            }
+            Self::UnsupportedVersion { .. }
+            | Self::Authentication { .. }
+            | Self::InvalidEnvelope { .. }
+            | Self::KeyDerivation(_)
+            | Self::InvalidProfile(_) => None,
+        }
+    }
+}
+
+impl From<io::Error> for StoreError {
+    fn from(error: io::Error) -> Self {
+        Self::Io(error)
+    }
+}
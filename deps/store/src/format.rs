@@ -0,0 +1,190 @@
+use std::io::{self, Read, Write};
+
+use flate2::{read::GzDecoder, write::GzEncoder};
+use serde::{Serialize, de::DeserializeOwned};
+
+/// Serialization format of stored files.
+pub trait Format: Send + Sync + 'static {
+    /// Data can be decoded without knowing its type. Migrations are only
+    /// possible for self-describing formats.
+    const SELF_DESCRIBING: bool;
+
+    fn encode<T: Serialize>(value: &T) -> anyhow::Result<Vec<u8>>;
+    fn decode<T: DeserializeOwned>(data: &[u8]) -> anyhow::Result<T>;
+}
+
+/// Pretty printed JSON. Default format.
+pub struct Json;
+
+/// Compact binary encoding for large data like levels and replays.
+pub struct Binary;
+
+/// Human editable format for config files.
+pub struct Ron;
+
+/// Human editable format for config files.
+pub struct Toml;
+
+impl Format for Json {
+    const SELF_DESCRIBING: bool = true;
+
+    fn encode<T: Serialize>(value: &T) -> anyhow::Result<Vec<u8>> {
+        Ok(serde_json::to_vec_pretty(value)?)
+    }
+
+    fn decode<T: DeserializeOwned>(data: &[u8]) -> anyhow::Result<T> {
+        Ok(serde_json::from_slice(data)?)
+    }
+}
+
+impl Format for Binary {
+    const SELF_DESCRIBING: bool = false;
+
+    fn encode<T: Serialize>(value: &T) -> anyhow::Result<Vec<u8>> {
+        Ok(postcard::to_stdvec(value)?)
+    }
+
+    fn decode<T: DeserializeOwned>(data: &[u8]) -> anyhow::Result<T> {
+        Ok(postcard::from_bytes(data)?)
+    }
+}
+
+impl Format for Ron {
+    const SELF_DESCRIBING: bool = true;
+
+    fn encode<T: Serialize>(value: &T) -> anyhow::Result<Vec<u8>> {
+        Ok(ron::ser::to_string_pretty(value, ron::ser::PrettyConfig::default())?.into_bytes())
+    }
+
+    fn decode<T: DeserializeOwned>(data: &[u8]) -> anyhow::Result<T> {
+        Ok(ron::de::from_bytes(data)?)
+    }
+}
+
+impl Format for Toml {
+    const SELF_DESCRIBING: bool = true;
+
+    fn encode<T: Serialize>(value: &T) -> anyhow::Result<Vec<u8>> {
+        Ok(toml::to_string_pretty(value)?.into_bytes())
+    }
+
+    fn decode<T: DeserializeOwned>(data: &[u8]) -> anyhow::Result<T> {
+        Ok(toml::from_str(std::str::from_utf8(data)?)?)
+    }
+}
+
+const ZSTD_MAGIC: &[u8] = &[0x28, 0xB5, 0x2F, 0xFD];
+const GZIP_MAGIC: &[u8] = &[0x1F, 0x8B];
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum Compression {
+    #[default]
+    None,
+    Zstd,
+    /// Deflate in gzip framing.
+    Deflate,
+}
+
+impl Compression {
+    pub(crate) fn compress(self, data: Vec<u8>) -> io::Result<Vec<u8>> {
+        match self {
+            Self::None => Ok(data),
+            Self::Zstd => zstd::encode_all(data.as_slice(), 0),
+            Self::Deflate => {
+                let mut encoder = GzEncoder::new(vec![], flate2::Compression::default());
+                encoder.write_all(&data)?;
+                encoder.finish()
+            }
+        }
+    }
+
+    /// Data without compression header is returned as is so files written
+    /// before compression was enabled stay readable.
+    pub(crate) fn decompress(self, data: Vec<u8>) -> io::Result<Vec<u8>> {
+        match self {
+            Self::Zstd if data.starts_with(ZSTD_MAGIC) => zstd::decode_all(data.as_slice()),
+            Self::Deflate if data.starts_with(GZIP_MAGIC) => {
+                let mut result = vec![];
+                GzDecoder::new(data.as_slice()).read_to_end(&mut result)?;
+                Ok(result)
+            }
+            _ => Ok(data),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs;
+
+    use anyhow::Result;
+    use serde::{Deserialize, Serialize};
+
+    use crate::{Binary, Compression, Format, Json, OnDisk, Paths, Ron, Toml};
+
+    #[derive(Debug, PartialEq, Default, Serialize, Deserialize, Clone)]
+    struct Level {
+        name:    String,
+        size:    (u32, u32),
+        sprites: Vec<f32>,
+        hidden:  bool,
+    }
+
+    fn level() -> Level {
+        Level {
+            name:    "Level 1".to_string(),
+            size:    (100, 200),
+            sprites: (0..100).map(|i| i as f32 * 0.5).collect(),
+            hidden:  true,
+        }
+    }
+
+    static JSON: OnDisk<Level, Json> = OnDisk::new("format_json_test");
+    static BINARY: OnDisk<Level, Binary> = OnDisk::new("format_binary_test");
+    static RON: OnDisk<Level, Ron> = OnDisk::new("format_ron_test");
+    static TOML: OnDisk<Level, Toml> = OnDisk::new("format_toml_test");
+    static ZSTD: OnDisk<Level, Binary> = OnDisk::new("format_zstd_test").compressed(Compression::Zstd);
+    static DEFLATE: OnDisk<Level, Json> = OnDisk::new("format_deflate_test").compressed(Compression::Deflate);
+
+    fn round_trip<F: Format>(storage: &OnDisk<Level, F>, name: &str) -> Result<u64> {
+        storage.try_set(level())?;
+        assert_eq!(storage.try_get()?, level());
+        Ok(fs::metadata(Paths::storage().join(name))?.len())
+    }
+
+    #[test]
+    fn formats() -> Result<()> {
+        let _storage = Paths::isolated_storage();
+
+        let json = round_trip(&JSON, "format_json_test")?;
+        let binary = round_trip(&BINARY, "format_binary_test")?;
+        round_trip(&RON, "format_ron_test")?;
+        round_trip(&TOML, "format_toml_test")?;
+        let zstd = round_trip(&ZSTD, "format_zstd_test")?;
+        let deflate = round_trip(&DEFLATE, "format_deflate_test")?;
+
+        assert!(binary < json);
+        assert!(zstd < binary);
+        assert!(deflate < json);
+
+        Ok(())
+    }
+
+    #[test]
+    fn uncompressed_is_readable() -> Result<()> {
+        static LEGACY: OnDisk<Level, Json> =
+            OnDisk::new("format_uncompressed_test").compressed(Compression::Zstd);
+
+        let _storage = Paths::isolated_storage();
+
+        fs::create_dir_all(Paths::storage())?;
+        fs::write(
+            Paths::storage().join("format_uncompressed_test"),
+            serde_json::to_vec(&level())?,
+        )?;
+
+        assert_eq!(LEGACY.try_get()?, level());
+
+        Ok(())
+    }
+}
@@ -1,10 +1,24 @@
+mod atomic;
+mod cached;
 mod encrypt;
+mod error;
+mod format;
 mod on_disk;
 mod on_disk_encrypted;
 mod paths;
+mod profile;
+mod scope;
 mod storable;
+mod transaction;
 
+pub use atomic::{FileWriter, FsWriter};
+pub use cached::Cached;
 pub use encrypt::EncryptionKey;
-pub use on_disk::OnDisk;
+pub use error::{StoreError, StoreResult};
+pub use format::{Binary, Compression, Format, Json, Ron, Toml};
+pub use on_disk::{Migration, OnDisk};
 pub use on_disk_encrypted::OnDiskEncrypted;
-pub use paths::Paths;
+pub use paths::{IsolatedStorage, Paths};
+pub use profile::Profile;
+pub use scope::StorageScope;
+pub use transaction::Transaction;
@@ -2,52 +2,251 @@ use std::{
     fmt::{Debug, Formatter},
     fs,
     marker::PhantomData,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
 };
 
-use crate::{Paths, storable::Storable};
+use log::error;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
 
-fn set_value<T: serde::ser::Serialize>(value: T, key: &str) {
-    let json = serde_json::to_string_pretty(&value).expect("Failed to serialize data");
-    let dir = Paths::storage();
-    _ = fs::create_dir_all(&dir);
-    fs::write(dir.join(key), json).expect("Failed to write to file");
-}
+use crate::{
+    StoreError, StoreResult,
+    atomic::{FileWriter, FsWriter, write_atomic},
+    format::{Compression, Format, Json},
+    scope::StorageScope,
+    storable::Storable,
+    transaction::recover_once,
+};
 
-fn get_value<T: Storable>(key: &str) -> T {
-    let dir = Paths::storage();
-    let path = dir.join(key);
+/// Upgrades JSON of a stored value by one version.
+/// Migration at index `n` converts version `n` to version `n + 1`.
+pub type Migration = fn(Value) -> anyhow::Result<Value>;
+
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Versioned<T> {
+    store_version: u32,
+    value:         T,
+}
 
-    fs::create_dir_all(&dir).unwrap();
+fn quarantine(path: &Path, key: &str) -> Option<PathBuf> {
+    let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+    let quarantined = path.with_file_name(format!("{key}.corrupt-{timestamp}"));
 
-    if !path.exists() {
-        let new = T::default();
-        set_value(&new, key);
-        return new;
+    match fs::rename(path, &quarantined) {
+        Ok(()) => Some(quarantined),
+        Err(err) => {
+            error!("Failed to quarantine corrupted '{key}': {err}");
+            None
+        }
     }
-    let json = fs::read_to_string(path).expect("Failed to read file");
-    serde_json::from_str(&json).expect("Failet to parse json")
 }
 
-pub struct OnDisk<T: Storable> {
-    name: &'static str,
-    _p:   PhantomData<T>,
+fn current_version(migrations: &[Migration]) -> u32 {
+    migrations.len().try_into().expect("Too many migrations")
+}
+
+pub struct OnDisk<T: Storable, F: Format = Json> {
+    name:                &'static str,
+    migrations:          &'static [Migration],
+    fallback_to_default: bool,
+    sync:                bool,
+    compression:         Compression,
+    scope:               StorageScope,
+    writer:              &'static dyn FileWriter,
+    _p:                  PhantomData<(T, F)>,
 }
 
-impl<T: Storable> OnDisk<T> {
+impl<T: Storable, F: Format> OnDisk<T, F> {
     pub const fn new(name: &'static str) -> Self {
         Self {
             name,
+            migrations: &[],
+            fallback_to_default: false,
+            sync: false,
+            compression: Compression::None,
+            scope: StorageScope::GLOBAL,
+            writer: &FsWriter,
             _p: PhantomData,
         }
     }
 
+    /// Current schema version is the number of migrations.
+    pub const fn with_migrations(self, migrations: &'static [Migration]) -> Self {
+        assert!(
+            F::SELF_DESCRIBING,
+            "Migrations are only supported for self-describing formats"
+        );
+        Self { migrations, ..self }
+    }
+
+    pub const fn compressed(self, compression: Compression) -> Self {
+        Self { compression, ..self }
+    }
+
+    pub const fn in_scope(self, scope: StorageScope) -> Self {
+        Self { scope, ..self }
+    }
+
+    /// Return `T::default()` instead of an error when stored file is corrupted.
+    pub const fn fallback_to_default(self) -> Self {
+        Self {
+            fallback_to_default: true,
+            ..self
+        }
+    }
+
+    /// Flush every write to disk before returning.
+    pub const fn with_fsync(self) -> Self {
+        Self { sync: true, ..self }
+    }
+
+    pub const fn with_writer(self, writer: &'static dyn FileWriter) -> Self {
+        Self { writer, ..self }
+    }
+
+    pub(crate) fn name(&self) -> &'static str {
+        self.name
+    }
+
+    pub fn version(&self) -> u32 {
+        current_version(self.migrations)
+    }
+
     pub fn set(&self, val: impl Into<T>) {
-        let val = val.into();
-        set_value(val, self.name);
+        self.try_set(val)
+            .unwrap_or_else(|err| panic!("Failed to store '{}': {err}", self.name));
     }
 
     pub fn get(&self) -> T {
-        get_value(self.name)
+        self.try_get()
+            .unwrap_or_else(|err| panic!("Failed to load '{}': {err}", self.name))
+    }
+
+    pub fn try_set(&self, val: impl Into<T>) -> StoreResult<()> {
+        self.write(&val.into())
+    }
+
+    pub(crate) fn encode(&self, value: &T) -> StoreResult<Vec<u8>> {
+        let data = F::encode(&Versioned {
+            store_version: self.version(),
+            value,
+        })
+        .map_err(StoreError::Serialize)?;
+
+        Ok(self.compression.compress(data)?)
+    }
+
+    fn decode(&self, data: Vec<u8>) -> StoreResult<(T, bool)> {
+        let corrupted = |error| StoreError::Corrupted {
+            key: self.name.to_string(),
+            quarantined: None,
+            error,
+        };
+
+        let data = self.compression.decompress(data).map_err(|err| corrupted(err.into()))?;
+
+        let current = self.version();
+
+        let (version, mut value) = match F::decode::<Versioned<T>>(&data) {
+            Ok(versioned) if versioned.store_version == current => return Ok((versioned.value, false)),
+            Ok(versioned) if !F::SELF_DESCRIBING => (versioned.store_version, Value::Null),
+            Err(error) if !F::SELF_DESCRIBING => return Err(corrupted(error)),
+            _ => {
+                let value: Value = F::decode(&data).map_err(corrupted)?;
+
+                // Files written before versioning was introduced contain bare value and are
+                // treated as version 0.
+                match serde_json::from_value::<Versioned<Value>>(value.clone()) {
+                    Ok(versioned) => (versioned.store_version, versioned.value),
+                    Err(_) => (0, value),
+                }
+            }
+        };
+
+        if version > current {
+            return Err(StoreError::UnsupportedVersion {
+                key: self.name.to_string(),
+                version,
+                current,
+            });
+        }
+
+        for (from, migration) in self.migrations.iter().enumerate().skip(version as usize) {
+            value = migration(value).map_err(|error| StoreError::Migration {
+                key: self.name.to_string(),
+                from: from.try_into().unwrap(),
+                error,
+            })?;
+        }
+
+        let value = serde_json::from_value(value).map_err(|err| corrupted(err.into()))?;
+
+        Ok((value, version < current))
+    }
+
+    pub(crate) fn sync(&self) -> bool {
+        self.sync
+    }
+
+    /// Path relative to storage root.
+    pub(crate) fn key(&self) -> PathBuf {
+        self.scope.relative_dir().join(self.name)
+    }
+
+    pub fn path(&self) -> PathBuf {
+        self.scope.dir().join(self.name)
+    }
+
+    fn write(&self, value: &T) -> StoreResult<()> {
+        let data = self.encode(value)?;
+        let path = self.path();
+        fs::create_dir_all(path.parent().expect("Invalid storage path"))?;
+        write_atomic(&path, &data, self.sync, self.writer)?;
+        Ok(())
+    }
+
+    pub fn try_get(&self) -> StoreResult<T> {
+        recover_once()?;
+
+        let path = self.path();
+
+        if !path.exists() {
+            let new = T::default();
+            self.write(&new)?;
+            return Ok(new);
+        }
+
+        let data = fs::read(&path)?;
+
+        match self.decode(data) {
+            Ok((value, migrated)) => {
+                if migrated {
+                    self.write(&value)?;
+                }
+                Ok(value)
+            }
+            Err(StoreError::Corrupted { key, error, .. }) => {
+                let quarantined = quarantine(&path, &key);
+
+                let error = StoreError::Corrupted {
+                    key,
+                    quarantined,
+                    error,
+                };
+
+                if !self.fallback_to_default {
+                    return Err(error);
+                }
+
+                error!("{error}. Falling back to default");
+                let new = T::default();
+                self.write(&new)?;
+                Ok(new)
+            }
+            Err(err) => Err(err),
+        }
     }
 
     pub fn reset(&self) {
@@ -55,7 +254,7 @@ impl<T: Storable> OnDisk<T> {
     }
 }
 
-impl<T: Storable + Debug> Debug for OnDisk<T> {
+impl<T: Storable + Debug, F: Format> Debug for OnDisk<T, F> {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         self.get().fmt(f)
     }
@@ -64,12 +263,18 @@ impl<T: Storable + Debug> Debug for OnDisk<T> {
 #[cfg(test)]
 mod test {
 
+    use std::{
+        fs::{self, File},
+        io::{self, Write},
+    };
+
     use anyhow::Result;
     use fake::{Fake, Faker};
     use serde::{Deserialize, Serialize};
+    use serde_json::json;
     use tokio::spawn;
 
-    use crate::{OnDisk, Paths};
+    use crate::{FileWriter, OnDisk, Paths, StoreError};
 
     #[derive(Debug, PartialEq, Default, Serialize, Deserialize, Clone)]
     struct Data {
@@ -85,6 +290,8 @@ mod test {
 
     #[tokio::test]
     async fn stored() -> Result<()> {
+        let _storage = Paths::isolated_storage();
+
         check_send(&STORED);
         check_sync(&STORED);
         check_send(&STORED_STRUCT);
@@ -123,6 +330,135 @@ mod test {
         Ok(())
     }
 
+    static CORRUPTED: OnDisk<Data> = OnDisk::new("corrupted_test");
+    static CORRUPTED_FALLBACK: OnDisk<Data> = OnDisk::new("corrupted_fallback_test").fallback_to_default();
+
+    static MIGRATED: OnDisk<Data> = OnDisk::new("migrated_test").with_migrations(&[
+        |value| Ok(json!({ "number": value, "string": "" })),
+        |mut value| {
+            value["string"] = json!("migrated");
+            Ok(value)
+        },
+    ]);
+
+    struct FailingWriter;
+
+    impl FileWriter for FailingWriter {
+        fn write(&self, file: &mut File, data: &[u8]) -> io::Result<()> {
+            file.write_all(&data[..data.len() / 2])?;
+            Err(io::Error::other("Interrupted"))
+        }
+    }
+
+    static INTERRUPTED: OnDisk<Data> = OnDisk::new("interrupted_test");
+    static INTERRUPTED_FAILING: OnDisk<Data> = OnDisk::new("interrupted_test").with_writer(&FailingWriter);
+
+    fn write_raw(key: &str, contents: &str) -> Result<()> {
+        fs::create_dir_all(Paths::storage())?;
+        fs::write(Paths::storage().join(key), contents)?;
+        Ok(())
+    }
+
+    #[test]
+    fn corrupted() -> Result<()> {
+        let _storage = Paths::isolated_storage();
+
+        write_raw("corrupted_test", "{ not json")?;
+
+        let Err(StoreError::Corrupted {
+            quarantined: Some(quarantined),
+            ..
+        }) = CORRUPTED.try_get()
+        else {
+            panic!("Expected corrupted error");
+        };
+
+        assert_eq!(fs::read_to_string(&quarantined)?, "{ not json");
+        fs::remove_file(quarantined)?;
+
+        assert_eq!(CORRUPTED.try_get()?, Data::default());
+
+        Ok(())
+    }
+
+    #[test]
+    fn corrupted_fallback() -> Result<()> {
+        let _storage = Paths::isolated_storage();
+
+        write_raw("corrupted_fallback_test", "[1, 2, 3]")?;
+
+        assert_eq!(CORRUPTED_FALLBACK.get(), Data::default());
+
+        for entry in fs::read_dir(Paths::storage())? {
+            let entry = entry?;
+            if entry
+                .file_name()
+                .to_string_lossy()
+                .starts_with("corrupted_fallback_test.corrupt")
+            {
+                fs::remove_file(entry.path())?;
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn migrations() -> Result<()> {
+        let _storage = Paths::isolated_storage();
+
+        assert_eq!(MIGRATED.version(), 2);
+
+        write_raw("migrated_test", "42")?;
+
+        assert_eq!(MIGRATED.try_get()?, Data {
+            number: 42,
+            string: "migrated".to_string(),
+        });
+
+        let stored: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(Paths::storage().join("migrated_test"))?)?;
+        assert_eq!(stored["store_version"], 2);
+
+        write_raw(
+            "migrated_test",
+            r#"{ "store_version": 3, "value": { "number": 1, "string": "" } }"#,
+        )?;
+
+        assert!(matches!(
+            MIGRATED.try_get(),
+            Err(StoreError::UnsupportedVersion {
+                version: 3,
+                current: 2,
+                ..
+            })
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn interrupted_write() -> Result<()> {
+        let _storage = Paths::isolated_storage();
+
+        let data = Data {
+            number: 5,
+            string: "safe".to_string(),
+        };
+
+        INTERRUPTED.set(data.clone());
+
+        assert!(matches!(
+            INTERRUPTED_FAILING.try_set(Data::default()),
+            Err(StoreError::Io(_))
+        ));
+
+        assert_eq!(INTERRUPTED.get(), data);
+        assert!(!Paths::storage().join("interrupted_test.tmp").exists());
+
+        Ok(())
+    }
+
     #[test]
     fn paths() {
         assert!(Paths::executable_name().starts_with("store"));
@@ -1,10 +1,10 @@
-use std::{marker::PhantomData, str::from_utf8};
+use std::marker::PhantomData;
 
-use serde_json::{from_str, to_string};
+use serde_json::{from_slice, to_vec};
 
 use crate::{
-    OnDisk,
-    encrypt::{EncryptionKey, decrypt, encrypt},
+    OnDisk, StoreError, StoreResult,
+    encrypt::{DecryptError, EncryptionKey, decrypt, encrypt},
     storable::Storable,
 };
 
@@ -22,19 +22,43 @@ impl<T: Storable> OnDiskEncrypted<T> {
     }
 
     pub fn set(&self, val: impl Into<T>, key: &EncryptionKey) {
+        self.try_set(val, key)
+            .unwrap_or_else(|err| panic!("Failed to store '{}': {err}", self.inner.name()));
+    }
+
+    pub fn try_set(&self, val: impl Into<T>, key: &EncryptionKey) -> StoreResult<()> {
         let val = val.into();
-        let string = to_string(&val).unwrap();
-        let encrypted = encrypt(string.as_bytes(), key);
-        self.inner.set(encrypted);
+        let data = to_vec(&val).map_err(|err| StoreError::Serialize(err.into()))?;
+        self.inner.try_set(encrypt(&data, key))
     }
 
-    pub fn get(&self, key: &EncryptionKey) -> T {
-        let encrypted = self.inner.get();
+    pub fn get(&self, key: &EncryptionKey) -> StoreResult<T> {
+        let encrypted = self.inner.try_get()?;
         if encrypted.is_empty() {
-            return T::default();
+            return Ok(T::default());
         }
-        let string = decrypt(encrypted.as_slice(), key);
-        from_str(from_utf8(&string).unwrap()).unwrap()
+
+        let name = self.inner.name().to_string();
+
+        let data = decrypt(&encrypted, key).map_err(|err| match err {
+            DecryptError::Authentication => StoreError::Authentication { key: name.clone() },
+            DecryptError::InvalidEnvelope(reason) => StoreError::InvalidEnvelope {
+                key: name.clone(),
+                reason,
+            },
+        })?;
+
+        from_slice(&data).map_err(|error| StoreError::Corrupted {
+            key:         name,
+            quarantined: None,
+            error:       error.into(),
+        })
+    }
+
+    /// Re-encrypts stored value with a new key.
+    pub fn rotate_key(&self, old: &EncryptionKey, new: &EncryptionKey) -> StoreResult<()> {
+        let value = self.get(old)?;
+        self.try_set(value, new)
     }
 
     pub fn reset(&self, key: &EncryptionKey) {
@@ -49,7 +73,7 @@ mod test {
     use serde::{Deserialize, Serialize};
     use tokio::spawn;
 
-    use crate::{EncryptionKey, on_disk_encrypted::OnDiskEncrypted};
+    use crate::{EncryptionKey, Paths, StoreError, on_disk_encrypted::OnDiskEncrypted};
 
     #[derive(Debug, PartialEq, Default, Serialize, Deserialize, Clone)]
     struct Data {
@@ -59,17 +83,19 @@ mod test {
 
     static STORED: OnDiskEncrypted<i32> = OnDiskEncrypted::new("stored_i32_encrypted_test");
     static STORED_STRUCT: OnDiskEncrypted<Data> = OnDiskEncrypted::new("stored_struct_encrypted_test");
+    static ROTATED: OnDiskEncrypted<Data> = OnDiskEncrypted::new("rotated_encrypted_test");
 
-    static KEY: EncryptionKey = [
-        1, 2, 3, 4, 5, 6, 7, 8, 9, 8, 7, 6, 5, 4, 3, 2, 1, 2, 5, 5, 5, 5, 5, 5, 5, 4, 3, 2, 1, 2, 3, 4, 5, 6,
-        7, 8, 9, 0, 9, 87, 6, 5, 3, 3,
-    ];
+    static KEY: EncryptionKey = EncryptionKey::new([
+        1, 2, 3, 4, 5, 6, 7, 8, 9, 8, 7, 6, 5, 4, 3, 2, 1, 2, 5, 5, 5, 5, 5, 5, 5, 4, 3, 2, 1, 2, 3, 4,
+    ]);
 
     fn check_send<T: Send>(_send: &T) {}
     fn check_sync<T: Sync>(_sync: &T) {}
 
     #[tokio::test]
     async fn encrypted_stored() -> Result<()> {
+        let _storage = Paths::isolated_storage();
+
         check_send(&STORED);
         check_sync(&STORED);
         check_send(&STORED_STRUCT);
@@ -77,7 +103,7 @@ mod test {
 
         STORED.set(10, &KEY);
         STORED.reset(&KEY);
-        assert_eq!(STORED.get(&KEY), i32::default());
+        assert_eq!(STORED.get(&KEY)?, i32::default());
 
         for _ in 0..10 {
             let rand: i32 = Faker.fake();
@@ -88,7 +114,7 @@ mod test {
             .await?;
 
             spawn(async move {
-                assert_eq!(STORED.get(&KEY), rand);
+                assert_eq!(STORED.get(&KEY).unwrap(), rand);
             })
             .await?;
         }
@@ -100,10 +126,34 @@ mod test {
 
         STORED_STRUCT.set(data.clone(), &KEY);
 
-        let loaded_data = STORED_STRUCT.get(&KEY);
+        let loaded_data = STORED_STRUCT.get(&KEY)?;
 
         assert_eq!(data, loaded_data);
 
         Ok(())
     }
+
+    #[test]
+    fn rotate_key() -> Result<()> {
+        let _storage = Paths::isolated_storage();
+
+        let data = Data {
+            number: 5,
+            string: "rotated".to_string(),
+        };
+
+        let old = EncryptionKey::from_password("old", &EncryptionKey::generate_salt())?;
+        let new = EncryptionKey::random();
+
+        ROTATED.set(data.clone(), &old);
+        ROTATED.rotate_key(&old, &new)?;
+
+        assert_eq!(ROTATED.get(&new)?, data);
+        assert!(matches!(
+            ROTATED.get(&old),
+            Err(StoreError::Authentication { .. })
+        ));
+
+        Ok(())
+    }
 }
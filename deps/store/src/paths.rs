@@ -1,15 +1,72 @@
-use std::{path::PathBuf, process::Command, sync::Mutex};
+use std::{
+    cell::Cell,
+    fs,
+    path::PathBuf,
+    process::Command,
+    sync::{
+        Mutex, MutexGuard, PoisonError,
+        atomic::{AtomicUsize, Ordering},
+    },
+};
 
 use dirs::home_dir;
 use gm::Platform;
 use log::warn;
 
 static STORAGE_PATH: Mutex<Option<String>> = Mutex::new(None);
+static ISOLATED_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+static ISOLATED_STORAGE: Mutex<Option<Isolated>> = Mutex::new(None);
+/// Held by the outermost [`IsolatedStorage`] so only one thread isolates
+/// storage at a time.
+static ISOLATION: Mutex<()> = Mutex::new(());
+
+thread_local! {
+    static ISOLATION_DEPTH: Cell<usize> = const { Cell::new(0) };
+}
+
+/// Storage override of the whole process.
+pub(crate) struct Isolated {
+    dir:                PathBuf,
+    /// Current profile is kept per isolated storage so tests don't switch
+    /// profile of the real storage.
+    pub(crate) profile: Option<String>,
+}
+
+/// Redirects storage and current profile of the process to a temporary
+/// directory until dropped. Spawned threads and watcher callbacks use it too.
+///
+/// Creating it on another thread blocks until the current one is dropped, so
+/// parallel tests using it run one at a time. It can be nested on the same
+/// thread.
+pub struct IsolatedStorage {
+    dir:      PathBuf,
+    previous: Option<Isolated>,
+    _lock:    Option<MutexGuard<'static, ()>>,
+}
+
+impl IsolatedStorage {
+    pub fn dir(&self) -> &PathBuf {
+        &self.dir
+    }
+}
+
+impl Drop for IsolatedStorage {
+    fn drop(&mut self) {
+        *isolated() = self.previous.take();
+        ISOLATION_DEPTH.set(ISOLATION_DEPTH.get() - 1);
+        _ = fs::remove_dir_all(&self.dir);
+    }
+}
 
 pub struct Paths;
 
 impl Paths {
     pub fn storage() -> PathBuf {
+        if let Some(isolated) = isolated().as_ref() {
+            return isolated.dir.clone();
+        }
+
         let home = if Platform::IOS {
             dirs::document_dir()
         } else if Platform::ANDROID {
@@ -56,7 +113,42 @@ impl Paths {
         Ok(PathBuf::from(git_root))
     }
 
+    pub fn isolated_storage() -> IsolatedStorage {
+        let lock =
+            (ISOLATION_DEPTH.get() == 0).then(|| ISOLATION.lock().unwrap_or_else(PoisonError::into_inner));
+        ISOLATION_DEPTH.set(ISOLATION_DEPTH.get() + 1);
+
+        let dir = std::env::temp_dir().join(format!(
+            "{}-storage-{}-{}",
+            Self::executable_name(),
+            std::process::id(),
+            ISOLATED_COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+
+        let previous = isolated().replace(Isolated {
+            dir:     dir.clone(),
+            profile: None,
+        });
+
+        IsolatedStorage {
+            dir,
+            previous,
+            _lock: lock,
+        }
+    }
+
     pub fn set_storage_path(path: String) {
         STORAGE_PATH.lock().unwrap().replace(path);
     }
+
+    /// Calls `f` with storage override if there is one.
+    pub(crate) fn with_isolated<R>(f: impl FnOnce(&mut Isolated) -> R) -> Option<R> {
+        isolated().as_mut().map(f)
+    }
+}
+
+/// Ignores poisoning. Override is still restored when a test holding it
+/// panics.
+fn isolated() -> MutexGuard<'static, Option<Isolated>> {
+    ISOLATED_STORAGE.lock().unwrap_or_else(PoisonError::into_inner)
 }
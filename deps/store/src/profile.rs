@@ -0,0 +1,282 @@
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Component, Path, PathBuf},
+};
+
+use anyhow::anyhow;
+use serde::{Deserialize, Serialize};
+use walkdir::WalkDir;
+
+use crate::{
+    Paths, StoreError, StoreResult,
+    atomic::{FsWriter, write_atomic},
+    format::{Binary, Compression, Format},
+    scope::{PROFILES_DIR, current_profile, is_key, set_current_profile},
+};
+
+const ARCHIVE_VERSION: u32 = 1;
+
+#[derive(Serialize, Deserialize)]
+struct Archive {
+    version: u32,
+    /// Path relative to profile directory and file contents.
+    files:   BTreeMap<String, Vec<u8>>,
+}
+
+/// Separate set of values for a user or a save slot. Values with profile
+/// `StorageScope` are read from and written to the current profile.
+pub struct Profile;
+
+impl Profile {
+    pub fn current() -> String {
+        current_profile()
+    }
+
+    pub fn set_current(name: impl Into<String>) -> StoreResult<()> {
+        let name = name.into();
+        Self::validate(&name)?;
+        set_current_profile(name);
+        Ok(())
+    }
+
+    pub fn list() -> StoreResult<Vec<String>> {
+        let dir = Paths::storage().join(PROFILES_DIR);
+
+        if !dir.exists() {
+            return Ok(vec![]);
+        }
+
+        let mut profiles = vec![];
+
+        for entry in fs::read_dir(dir)? {
+            let entry = entry?;
+            if entry.file_type()?.is_dir() {
+                profiles.push(entry.file_name().to_string_lossy().into_owned());
+            }
+        }
+
+        profiles.sort();
+
+        Ok(profiles)
+    }
+
+    /// Keys of all values of the profile relative to its directory including
+    /// namespace. E.g. `settings/volume`.
+    pub fn keys(name: &str) -> StoreResult<Vec<String>> {
+        let dir = Self::dir(name)?;
+
+        if !dir.exists() {
+            return Ok(vec![]);
+        }
+
+        let mut keys = vec![];
+
+        for entry in WalkDir::new(&dir) {
+            let entry = entry.map_err(|err| StoreError::Io(err.into()))?;
+
+            if !entry.file_type().is_file() || !is_key(entry.path()) {
+                continue;
+            }
+
+            let key = entry.path().strip_prefix(&dir).expect("Entry outside profile directory");
+            keys.push(key.to_string_lossy().replace('\\', "/"));
+        }
+
+        keys.sort();
+
+        Ok(keys)
+    }
+
+    /// Packs all values of the profile into a single file.
+    pub fn export(name: &str, archive: impl AsRef<Path>) -> StoreResult<()> {
+        let dir = Self::dir(name)?;
+
+        let mut files = BTreeMap::new();
+
+        for key in Self::keys(name)? {
+            files.insert(key.clone(), fs::read(dir.join(&key))?);
+        }
+
+        let data = Binary::encode(&Archive {
+            version: ARCHIVE_VERSION,
+            files,
+        })
+        .map_err(StoreError::Serialize)?;
+
+        write_atomic(
+            archive.as_ref(),
+            &Compression::Zstd.compress(data)?,
+            true,
+            &FsWriter,
+        )?;
+
+        Ok(())
+    }
+
+    /// Replaces values of the profile with values from exported archive.
+    pub fn import(name: &str, archive: impl AsRef<Path>) -> StoreResult<()> {
+        let archive_path = archive.as_ref();
+
+        let corrupted = |error| StoreError::Corrupted {
+            key: archive_path.display().to_string(),
+            quarantined: None,
+            error,
+        };
+
+        let data = Compression::Zstd
+            .decompress(fs::read(archive_path)?)
+            .map_err(|err| corrupted(err.into()))?;
+        let archive: Archive = Binary::decode(&data).map_err(corrupted)?;
+
+        if archive.version != ARCHIVE_VERSION {
+            return Err(StoreError::UnsupportedVersion {
+                key:     archive_path.display().to_string(),
+                version: archive.version,
+                current: ARCHIVE_VERSION,
+            });
+        }
+
+        if let Some(key) = archive.files.keys().find(|key| {
+            Path::new(key)
+                .components()
+                .any(|component| !matches!(component, Component::Normal(_)))
+        }) {
+            return Err(corrupted(anyhow!("Invalid key in archive: {key}")));
+        }
+
+        Self::delete(name)?;
+
+        let dir = Self::dir(name)?;
+
+        for (key, data) in archive.files {
+            let path = dir.join(key);
+            fs::create_dir_all(path.parent().expect("Invalid key path"))?;
+            write_atomic(&path, &data, false, &FsWriter)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn delete(name: &str) -> StoreResult<()> {
+        let dir = Self::dir(name)?;
+
+        if dir.exists() {
+            fs::remove_dir_all(dir)?;
+        }
+
+        Ok(())
+    }
+
+    fn dir(name: &str) -> StoreResult<PathBuf> {
+        Self::validate(name)?;
+        Ok(Paths::storage().join(PROFILES_DIR).join(name))
+    }
+
+    /// Name must be a single normal path component so profile operations
+    /// never reach outside of its directory.
+    fn validate(name: &str) -> StoreResult<()> {
+        let mut components = Path::new(name).components();
+
+        let valid = !name.contains(['/', '\\'])
+            && matches!(components.next(), Some(Component::Normal(_)))
+            && components.next().is_none();
+
+        if valid {
+            Ok(())
+        } else {
+            Err(StoreError::InvalidProfile(name.to_string()))
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use anyhow::Result;
+
+    use crate::{OnDisk, Paths, Profile, StorageScope, StoreError};
+
+    static SETTINGS: StorageScope = StorageScope::namespace("settings");
+    static SLOT: StorageScope = StorageScope::namespace("slot").in_profile();
+
+    static VOLUME: OnDisk<u32> = OnDisk::new("volume").in_scope(SETTINGS);
+    static LEVEL: OnDisk<u32> = OnDisk::new("level").in_scope(SLOT);
+    static COINS: OnDisk<u32> = OnDisk::new("coins").in_scope(StorageScope::PROFILE);
+
+    #[test]
+    fn profiles() -> Result<()> {
+        let storage = Paths::isolated_storage();
+        assert_eq!(Paths::storage(), *storage.dir());
+
+        VOLUME.set(5u32);
+        assert!(storage.dir().join("settings/volume").exists());
+
+        for name in ["", ".", "..", "a/b", "a\\b", "/"] {
+            assert!(matches!(
+                Profile::set_current(name),
+                Err(StoreError::InvalidProfile(_))
+            ));
+            assert!(Profile::delete(name).is_err());
+            assert!(Profile::keys(name).is_err());
+        }
+        assert_eq!(Profile::current(), "default");
+
+        Profile::set_current("first")?;
+        LEVEL.set(1u32);
+        COINS.set(100u32);
+
+        Profile::set_current("second")?;
+        assert_eq!(LEVEL.get(), 0);
+        LEVEL.set(2u32);
+
+        assert_eq!(Profile::list()?, vec!["first", "second"]);
+        assert_eq!(Profile::keys("first")?, vec!["coins", "slot/level"]);
+        assert_eq!(SLOT.keys()?, vec!["level"]);
+        assert_eq!(SETTINGS.keys()?, vec!["volume"]);
+
+        let archive = storage.dir().join("first.archive");
+        Profile::export("first", &archive)?;
+        Profile::delete("first")?;
+        assert_eq!(Profile::list()?, vec!["second"]);
+
+        Profile::import("restored", &archive)?;
+        Profile::set_current("restored")?;
+        assert_eq!(LEVEL.get(), 1);
+        assert_eq!(COINS.get(), 100);
+
+        SLOT.delete_all()?;
+        assert!(SLOT.keys()?.is_empty());
+        assert_eq!(VOLUME.get(), 5);
+
+        Ok(())
+    }
+
+    #[test]
+    fn isolated() {
+        let first = Paths::isolated_storage();
+        VOLUME.set(1u32);
+
+        {
+            let _second = Paths::isolated_storage();
+            assert_eq!(VOLUME.get(), 0);
+        }
+
+        assert_eq!(Paths::storage(), *first.dir());
+        assert_eq!(VOLUME.get(), 1);
+
+        // Other threads use the same isolated storage
+        Profile::set_current("isolated").unwrap();
+        let dir = first.dir().clone();
+        std::thread::spawn(move || {
+            assert_eq!(Paths::storage(), dir);
+            assert_eq!(Profile::current(), "isolated");
+            assert_eq!(VOLUME.get(), 1);
+        })
+        .join()
+        .unwrap();
+
+        let dir = first.dir().clone();
+        drop(first);
+        assert!(!dir.exists());
+    }
+}
@@ -0,0 +1,124 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+use crate::{Paths, StoreResult};
+
+pub(crate) const PROFILES_DIR: &str = "profiles";
+pub(crate) const DEFAULT_PROFILE: &str = "default";
+
+static PROFILE: Mutex<Option<String>> = Mutex::new(None);
+
+/// Directory where `OnDisk` values are placed inside the storage root.
+///
+/// Global scope is the storage root itself. Namespaced scopes are its
+/// subdirectories. Profile scopes are placed inside directory of current
+/// profile so every save slot or user gets separate values.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct StorageScope {
+    namespace: Option<&'static str>,
+    profile:   bool,
+}
+
+impl StorageScope {
+    pub const GLOBAL: Self = Self {
+        namespace: None,
+        profile:   false,
+    };
+
+    pub const PROFILE: Self = Self {
+        namespace: None,
+        profile:   true,
+    };
+
+    pub const fn namespace(namespace: &'static str) -> Self {
+        Self {
+            namespace: Some(namespace),
+            profile:   false,
+        }
+    }
+
+    pub const fn in_profile(self) -> Self {
+        Self {
+            profile: true,
+            ..self
+        }
+    }
+
+    /// Path relative to storage root.
+    pub fn relative_dir(&self) -> PathBuf {
+        let mut dir = PathBuf::new();
+
+        if self.profile {
+            dir.push(PROFILES_DIR);
+            dir.push(current_profile());
+        }
+
+        if let Some(namespace) = self.namespace {
+            dir.push(namespace);
+        }
+
+        dir
+    }
+
+    pub fn dir(&self) -> PathBuf {
+        Paths::storage().join(self.relative_dir())
+    }
+
+    /// Names of values stored in this scope.
+    pub fn keys(&self) -> StoreResult<Vec<String>> {
+        let dir = self.dir();
+
+        if !dir.exists() {
+            return Ok(vec![]);
+        }
+
+        let mut keys = vec![];
+
+        for entry in fs::read_dir(dir)? {
+            let entry = entry?;
+            if entry.file_type()?.is_file() && is_key(&entry.path()) {
+                keys.push(entry.file_name().to_string_lossy().into_owned());
+            }
+        }
+
+        keys.sort();
+
+        Ok(keys)
+    }
+
+    /// Removes every value stored in this scope.
+    pub fn delete_all(&self) -> StoreResult<()> {
+        for key in self.keys()? {
+            fs::remove_file(self.dir().join(key))?;
+        }
+        Ok(())
+    }
+}
+
+pub(crate) fn current_profile() -> String {
+    Paths::with_isolated(|isolated| isolated.profile.clone())
+        .unwrap_or_else(|| PROFILE.lock().unwrap().clone())
+        .unwrap_or_else(|| DEFAULT_PROFILE.to_string())
+}
+
+pub(crate) fn set_current_profile(name: String) {
+    if Paths::with_isolated(|isolated| isolated.profile = Some(name.clone())).is_none() {
+        PROFILE.lock().unwrap().replace(name);
+    }
+}
+
+/// Skips temporary, staged and quarantined files which live next to values.
+pub(crate) fn is_key(path: &Path) -> bool {
+    let Some(name) = path.file_name() else {
+        return false;
+    };
+    let name = name.to_string_lossy();
+
+    !name.starts_with('.')
+        && !name.ends_with(".tmp")
+        && !name.ends_with(".staged")
+        && !name.contains(".corrupt-")
+}
@@ -0,0 +1,319 @@
+use std::{
+    collections::BTreeSet,
+    fs,
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+use log::error;
+use serde::{Deserialize, Serialize};
+use walkdir::WalkDir;
+
+use crate::{
+    OnDisk, Paths, StoreError, StoreResult,
+    atomic::{FileWriter, FsWriter, sync_dir, tmp_path, write_atomic, write_tmp},
+    format::Format,
+    storable::Storable,
+};
+
+const JOURNAL: &str = ".journal";
+const STAGED_SUFFIX: &str = ".staged";
+
+static LOCK: Mutex<()> = Mutex::new(());
+/// Storage roots recovered in this process.
+static RECOVERED: Mutex<BTreeSet<PathBuf>> = Mutex::new(BTreeSet::new());
+
+#[derive(Serialize, Deserialize)]
+struct Journal {
+    keys: Vec<String>,
+}
+
+struct Entry {
+    /// Path relative to storage root.
+    key:  String,
+    data: Vec<u8>,
+}
+
+/// Commits several `OnDisk` values together. Either all of them are updated or
+/// none, even if the process dies in the middle of the commit.
+///
+/// Values are first staged next to their files, then the journal listing them
+/// is written. Once the journal is on disk the transaction is committed and
+/// staged files are moved in place. Interrupted commits are finished on next
+/// launch.
+pub struct Transaction {
+    entries: Vec<Entry>,
+    sync:    bool,
+    writer:  &'static dyn FileWriter,
+}
+
+impl Transaction {
+    pub fn new() -> Self {
+        Self {
+            entries: vec![],
+            sync:    false,
+            writer:  &FsWriter,
+        }
+    }
+
+    pub fn with_writer(self, writer: &'static dyn FileWriter) -> Self {
+        Self { writer, ..self }
+    }
+
+    pub fn set<T: Storable, F: Format>(
+        &mut self,
+        storage: &OnDisk<T, F>,
+        val: impl Into<T>,
+    ) -> StoreResult<&mut Self> {
+        let data = storage.encode(&val.into())?;
+
+        let key = storage.key().to_string_lossy().into_owned();
+
+        self.sync |= storage.sync();
+        self.entries.retain(|entry| entry.key != key);
+        self.entries.push(Entry { key, data });
+
+        Ok(self)
+    }
+
+    pub fn commit(self) -> StoreResult<()> {
+        let _lock = LOCK.lock().unwrap();
+
+        let dir = Paths::storage();
+        fs::create_dir_all(&dir)?;
+
+        let staged: Vec<PathBuf> = self.entries.iter().map(|entry| staged_path(&dir, &entry.key)).collect();
+
+        let result = self.stage(&dir, &staged);
+
+        if result.is_err() {
+            for path in &staged {
+                _ = fs::remove_file(path);
+            }
+            return result;
+        }
+
+        apply(
+            &dir,
+            self.entries.iter().map(|entry| entry.key.as_str()),
+            self.sync,
+        )
+    }
+
+    /// Finishes a commit interrupted after its journal was written and discards
+    /// values staged by commits which didn't reach that point.
+    pub fn recover() -> StoreResult<()> {
+        let _lock = LOCK.lock().unwrap();
+        Self::recover_locked(&Paths::storage())
+    }
+
+    /// [`Transaction::recover`] of `dir` for callers already holding `LOCK`.
+    fn recover_locked(dir: &Path) -> StoreResult<()> {
+        if !dir.exists() {
+            return Ok(());
+        }
+
+        let journal_path = dir.join(JOURNAL);
+
+        if journal_path.exists() {
+            match serde_json::from_slice::<Journal>(&fs::read(&journal_path)?) {
+                Ok(journal) => return apply(dir, journal.keys.iter().map(String::as_str), true),
+                Err(err) => {
+                    error!("Failed to parse storage journal: {err}. Discarding staged values");
+                    fs::remove_file(&journal_path)?;
+                }
+            }
+        }
+
+        for entry in WalkDir::new(dir) {
+            let entry = entry.map_err(|err| StoreError::Io(err.into()))?;
+            if entry.file_type().is_file() && entry.path().to_string_lossy().ends_with(STAGED_SUFFIX) {
+                fs::remove_file(entry.path())?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn stage(&self, dir: &Path, staged: &[PathBuf]) -> StoreResult<()> {
+        for (entry, path) in self.entries.iter().zip(staged) {
+            fs::create_dir_all(path.parent().expect("Invalid storage path"))?;
+            write_tmp(path, &entry.data, self.sync, self.writer)?;
+        }
+
+        let journal = Journal {
+            keys: self.entries.iter().map(|entry| entry.key.clone()).collect(),
+        };
+
+        let journal = serde_json::to_vec(&journal).map_err(|err| StoreError::Serialize(err.into()))?;
+
+        write_atomic(&dir.join(JOURNAL), &journal, self.sync, self.writer)?;
+
+        Ok(())
+    }
+}
+
+impl Default for Transaction {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn staged_path(dir: &Path, key: &str) -> PathBuf {
+    tmp_path(&dir.join(key), STAGED_SUFFIX)
+}
+
+fn apply<'a>(dir: &Path, keys: impl Iterator<Item = &'a str>, sync: bool) -> StoreResult<()> {
+    for key in keys {
+        let staged = staged_path(dir, key);
+        if !staged.exists() {
+            continue;
+        }
+
+        let path = dir.join(key);
+        fs::rename(staged, &path)?;
+
+        if sync {
+            sync_dir(path.parent().expect("Invalid storage path"))?;
+        }
+    }
+
+    fs::remove_file(dir.join(JOURNAL))?;
+
+    Ok(())
+}
+
+/// Runs recovery before the first read from current storage root in this
+/// process. Each root, like an isolated test storage, is recovered on its own.
+pub(crate) fn recover_once() -> StoreResult<()> {
+    let dir = Paths::storage();
+    let mut recovered = RECOVERED.lock().unwrap();
+
+    if !recovered.contains(&dir) {
+        let _lock = LOCK.lock().unwrap();
+        Transaction::recover_locked(&dir)?;
+        recovered.insert(dir);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use std::{
+        fs::{self, File},
+        io::{self, Write},
+    };
+
+    use anyhow::Result;
+    use serde::{Deserialize, Serialize};
+
+    use crate::{
+        FileWriter, OnDisk, Paths, Transaction,
+        transaction::{JOURNAL, staged_path},
+    };
+
+    #[derive(Debug, PartialEq, Default, Serialize, Deserialize, Clone)]
+    struct Settings {
+        volume: u32,
+    }
+
+    struct FailingWriter;
+
+    impl FileWriter for FailingWriter {
+        fn write(&self, file: &mut File, data: &[u8]) -> io::Result<()> {
+            file.write_all(&data[..data.len() / 2])?;
+            Err(io::Error::other("Interrupted"))
+        }
+    }
+
+    static SETTINGS: OnDisk<Settings> = OnDisk::new("transaction_settings_test").with_fsync();
+    static PROGRESS: OnDisk<u32> = OnDisk::new("transaction_progress_test");
+
+    static RECOVERED_SETTINGS: OnDisk<Settings> = OnDisk::new("recovered_settings_test");
+    static RECOVERED_PROGRESS: OnDisk<u32> = OnDisk::new("recovered_progress_test");
+
+    #[test]
+    fn transaction() -> Result<()> {
+        let _storage = Paths::isolated_storage();
+
+        SETTINGS.set(Settings { volume: 1 });
+        PROGRESS.set(1u32);
+
+        let mut tx = Transaction::new();
+        tx.set(&SETTINGS, Settings { volume: 5 })?.set(&PROGRESS, 10u32)?;
+        tx.commit()?;
+
+        assert_eq!(SETTINGS.get(), Settings { volume: 5 });
+        assert_eq!(PROGRESS.get(), 10);
+
+        let mut tx = Transaction::new().with_writer(&FailingWriter);
+        tx.set(&SETTINGS, Settings { volume: 20 })?.set(&PROGRESS, 20u32)?;
+        assert!(tx.commit().is_err());
+
+        assert_eq!(SETTINGS.get(), Settings { volume: 5 });
+        assert_eq!(PROGRESS.get(), 10);
+
+        let dir = Paths::storage();
+        assert!(!staged_path(&dir, SETTINGS.name()).exists());
+        assert!(!staged_path(&dir, PROGRESS.name()).exists());
+
+        Ok(())
+    }
+
+    /// Simulates process killed right after the journal was written.
+    fn interrupt(volume: u32) -> Result<()> {
+        let dir = Paths::storage();
+        fs::create_dir_all(&dir)?;
+        fs::write(
+            staged_path(&dir, RECOVERED_SETTINGS.name()),
+            RECOVERED_SETTINGS.encode(&Settings { volume })?,
+        )?;
+        fs::write(
+            staged_path(&dir, RECOVERED_PROGRESS.name()),
+            RECOVERED_PROGRESS.encode(&volume)?,
+        )?;
+        fs::write(
+            dir.join(JOURNAL),
+            r#"{ "keys": ["recovered_settings_test", "recovered_progress_test"] }"#,
+        )?;
+        Ok(())
+    }
+
+    #[test]
+    fn recover_interrupted_commit() -> Result<()> {
+        let _storage = Paths::isolated_storage();
+
+        RECOVERED_SETTINGS.set(Settings { volume: 1 });
+        RECOVERED_PROGRESS.set(1u32);
+
+        interrupt(7)?;
+        Transaction::recover()?;
+
+        assert_eq!(RECOVERED_SETTINGS.get(), Settings { volume: 7 });
+        assert_eq!(RECOVERED_PROGRESS.get(), 7);
+        assert!(!Paths::storage().join(JOURNAL).exists());
+
+        Ok(())
+    }
+
+    #[test]
+    fn recover_each_root() -> Result<()> {
+        let _first = Paths::isolated_storage();
+        interrupt(7)?;
+
+        // First read from a root recovers it
+        assert_eq!(RECOVERED_SETTINGS.get(), Settings { volume: 7 });
+
+        {
+            let _second = Paths::isolated_storage();
+            interrupt(8)?;
+            assert_eq!(RECOVERED_PROGRESS.get(), 8);
+            assert!(!Paths::storage().join(JOURNAL).exists());
+        }
+
+        assert_eq!(RECOVERED_PROGRESS.get(), 7);
+
+        Ok(())
+    }
+}
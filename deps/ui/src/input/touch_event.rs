@@ -1,9 +1,10 @@
 use std::{fmt::Display, str::FromStr};
 
 use anyhow::bail;
+use serde::{Deserialize, Serialize};
 use wgpu_wrapper::ElementState;
 
-#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum TouchEvent {
     Began,
     Moved,
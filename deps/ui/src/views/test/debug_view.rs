@@ -1,5 +1,6 @@
 use std::{collections::HashMap, fmt::Display};
 
+use dispatch::dispatch_stats;
 use gm::{Color, Platform};
 use refs::{MainLock, Own, Weak, dump_ref_stats};
 
@@ -32,6 +33,7 @@ pub struct DebugView {
     #[init]
     fps_label:          Label,
     frame_drawn_label:  Label,
+    dispatch_label:     Label,
     screen_scale_label: Label,
     touch_enabled:      Label,
     exit:               Button,
@@ -54,7 +56,7 @@ impl DebugView {
         if Platform::MOBILE {
             container.set_y(400);
         }
-        container.set_size((200, 280));
+        container.set_size((200, 300));
     }
 
     pub fn disable() {
@@ -128,6 +130,13 @@ impl ViewCallbacks for DebugView {
 
         self.frame_drawn_label.set_text(format!("Frames: {}", UIManager::frame_drawn()));
 
+        let dispatch = dispatch_stats();
+        self.dispatch_label.set_text(format!(
+            "Dispatch: {} {:.1}ms",
+            dispatch.total_queued(),
+            dispatch.time.as_secs_f32() * 1000.0
+        ));
+
         let screen_scale = UIManager::display_scale();
         self.screen_scale_label.set_text(format!("Scale: {screen_scale}"));
 
@@ -1,6 +1,7 @@
-use std::{fs::read, path::Path};
+use std::path::Path;
 
 use anyhow::Result;
+use asset_pack::read;
 use gm::flat::Size;
 use log::error;
 use manage::{data_manager::DataManager, managed, resource_loader::ResourceLoader};
@@ -10,7 +11,10 @@ use wgpu::{
     BindingResource, BindingType, SamplerBindingType, ShaderStages, TextureSampleType, TextureViewDimension,
 };
 
-use crate::{WGPUApp, image::Texture};
+use crate::{
+    WGPUApp,
+    image::{DecodedImage, Texture},
+};
 
 #[derive(Debug)]
 pub struct Image {
@@ -68,6 +72,16 @@ impl Image {
         })
     }
 
+    /// Uploads image decoded on another thread. Must be called on main thread.
+    pub fn from_decoded(decoded: &DecodedImage, name: &str) -> Result<Weak<Image>> {
+        Self::from_raw_data(&decoded.data, name, decoded.size, decoded.channels)
+    }
+
+    /// Shown in place of images which are not loaded yet or failed to load.
+    pub fn placeholder() -> Weak<Image> {
+        Image::load(DEFAULT_IMAGE_DATA, "placeholder")
+    }
+
     pub fn is_monochrome(&self) -> bool {
         self.channels == 1
     }
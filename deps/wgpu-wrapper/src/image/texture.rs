@@ -9,6 +9,34 @@ use wgpu::{
 
 use crate::WGPUApp;
 
+/// Image decoded on CPU. Doesn't touch GPU so it can be created on any
+/// thread and uploaded later with [`crate::image::Image::from_decoded`].
+#[derive(Debug)]
+pub struct DecodedImage {
+    pub data:     Vec<u8>,
+    pub size:     Size<u32>,
+    pub channels: u8,
+}
+
+impl DecodedImage {
+    pub fn decode(bytes: &[u8]) -> Result<Self> {
+        let img = image::load_from_memory(bytes)?;
+        let (width, height) = img.dimensions();
+
+        let (data, channels) = if img.color().channel_count() == 1 {
+            (img.to_luma8().into_raw(), 1)
+        } else {
+            (img.to_rgba8().into_raw(), 4)
+        };
+
+        Ok(Self {
+            data,
+            size: (width, height).into(),
+            channels,
+        })
+    }
+}
+
 #[derive(Debug)]
 pub struct Texture {
     pub texture:  wgpu::Texture,
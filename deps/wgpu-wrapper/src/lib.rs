@@ -15,8 +15,12 @@ pub use app::*;
 pub use bytemuck::cast_slice;
 pub use image_proc::include_images;
 pub use render::{
-    flat::RectPipeline, image_drawer::image_vertices_with_shrink, path_data::PathData,
-    sprite_drawer::shader_data::SpriteRenderView, wgpu_drawer::WGPUDrawer,
+    flat::RectPipeline,
+    image_drawer::image_vertices_with_shrink,
+    path_data::PathData,
+    shader::{SHADERS_ROOT, reload_shader},
+    sprite_drawer::shader_data::SpriteRenderView,
+    wgpu_drawer::WGPUDrawer,
 };
 pub use screenshot::*;
 pub use text::*;
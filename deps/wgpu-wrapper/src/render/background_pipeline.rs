@@ -13,7 +13,7 @@ use wgpu::{
 use crate::{
     WGPUApp,
     image::Image,
-    render::{uniform::make_uniform_layout, vertex_layout::VertexLayout},
+    render::{shader::shader_module, uniform::make_uniform_layout, vertex_layout::VertexLayout},
     utils::{BufferHelper, DeviceHelper},
 };
 
@@ -66,7 +66,10 @@ pub struct BackgroundPipeline {
 impl Default for BackgroundPipeline {
     fn default() -> Self {
         let device = WGPUApp::device();
-        let shader = device.create_shader_module(wgpu::include_wgsl!("shaders/background.wgsl"));
+        let shader = shader_module(
+            "shaders/background.wgsl",
+            wgpu::include_wgsl!("shaders/background.wgsl"),
+        );
 
         let vertex_layout = make_uniform_layout("background_drawer_vertex_layout", ShaderStages::VERTEX);
 
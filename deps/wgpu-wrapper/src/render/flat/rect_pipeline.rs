@@ -13,6 +13,7 @@ use crate::{
     BufferUsages, PolygonMode, WGPUApp,
     render::{
         flat::{rect_instance::RectInstance, rect_view::RectView},
+        shader::shader_module,
         uniform::{UniformBind, make_uniform_layout},
         vec_buffer::VecBuffer,
         vertex_layout::VertexLayout,
@@ -43,7 +44,7 @@ impl Default for RectPipeline {
     fn default() -> Self {
         let device = WGPUApp::device();
 
-        let shader = device.create_shader_module(include_wgsl!("rect.wgsl"));
+        let shader = shader_module("flat/rect.wgsl", include_wgsl!("rect.wgsl"));
 
         let sprite_view_layout = make_uniform_layout("rect_view_layout", ShaderStages::VERTEX_FRAGMENT);
 
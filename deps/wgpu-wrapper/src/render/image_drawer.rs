@@ -12,6 +12,7 @@ use crate::{
     WGPUApp,
     image::Image,
     render::{
+        shader::shader_module,
         uniform::{cached_float_bind, make_uniform_layout},
         vertex_layout::VertexLayout,
     },
@@ -53,7 +54,10 @@ pub struct ImageDrawer {
 impl Default for ImageDrawer {
     fn default() -> Self {
         let device = WGPUApp::device();
-        let shader = device.create_shader_module(wgpu::include_wgsl!("shaders/ui_image.wgsl"));
+        let shader = shader_module(
+            "shaders/ui_image.wgsl",
+            wgpu::include_wgsl!("shaders/ui_image.wgsl"),
+        );
 
         let vertex_layout = make_uniform_layout("image_drawer_vertex_layout", ShaderStages::VERTEX);
 
@@ -5,6 +5,7 @@ pub mod old_rect_drawer;
 pub mod path_data;
 pub mod path_drawer;
 pub mod scene_drawer;
+pub mod shader;
 pub mod sprite_drawer;
 pub mod uniform;
 mod vec_buffer;
@@ -12,6 +12,7 @@ use wgpu::{
 use crate::{
     WGPUApp,
     render::{
+        shader::shader_module,
         uniform::{cached_color_bind, cached_float_bind, make_uniform_layout},
         vertex_layout::VertexLayout,
     },
@@ -39,7 +40,7 @@ impl Default for OldRectDrawer {
     fn default() -> Self {
         let device = WGPUApp::device();
 
-        let shader = device.create_shader_module(include_wgsl!("shaders/rect.wgsl"));
+        let shader = shader_module("shaders/rect.wgsl", include_wgsl!("shaders/rect.wgsl"));
 
         let vertex_layout = make_uniform_layout("old_rect_vertext_layout", ShaderStages::VERTEX);
         let fragment_layout = make_uniform_layout("old_rect_vertext_layout", ShaderStages::FRAGMENT);
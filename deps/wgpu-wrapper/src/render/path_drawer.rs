@@ -10,6 +10,7 @@ use wgpu::{
 use crate::{
     WGPUApp,
     render::{
+        shader::shader_module,
         uniform::{cached_float_bind, make_uniform_layout},
         vertex_layout::VertexLayout,
     },
@@ -28,7 +29,7 @@ impl Default for PathDrawer {
     fn default() -> Self {
         let device = WGPUApp::device();
 
-        let shader = device.create_shader_module(include_wgsl!("shaders/path.wgsl"));
+        let shader = shader_module("shaders/path.wgsl", include_wgsl!("shaders/path.wgsl"));
 
         let color_size_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
             label:   Some("path_bind_group_layout"),
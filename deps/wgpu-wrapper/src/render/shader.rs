@@ -0,0 +1,64 @@
+use std::{
+    collections::HashMap,
+    pin::pin,
+    task::{Context, Poll, Waker},
+};
+
+use anyhow::{Result, bail};
+use refs::MainLock;
+use wgpu::{ErrorFilter, ShaderModule, ShaderModuleDescriptor, ShaderSource};
+
+use crate::{WGPUApp, WGPUDrawer};
+
+/// Folder with shader sources. Shader names are paths relative to it.
+/// Only exists on the machine the engine was built on so it is used only
+/// for hot reload during development.
+pub const SHADERS_ROOT: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/src/render");
+
+/// Shader sources loaded from disk at runtime. Used instead of embedded ones.
+static RELOADED: MainLock<HashMap<String, String>> = MainLock::new();
+
+pub(crate) fn shader_module(name: &str, embedded: ShaderModuleDescriptor) -> ShaderModule {
+    let device = WGPUApp::device();
+
+    let Some(source) = RELOADED.get_mut().get(name) else {
+        return device.create_shader_module(embedded);
+    };
+
+    device.create_shader_module(ShaderModuleDescriptor {
+        label:  name.into(),
+        source: ShaderSource::Wgsl(source.as_str().into()),
+    })
+}
+
+/// Recompiles all pipelines with new shader source. If it doesn't compile
+/// old pipelines are kept and the error is returned.
+pub fn reload_shader(name: &str, source: String) -> Result<()> {
+    let device = WGPUApp::device();
+
+    let previous = RELOADED.get_mut().insert(name.to_string(), source);
+
+    device.push_error_scope(ErrorFilter::Validation);
+    let drawer = WGPUDrawer::default();
+    let error = poll_ready(device.pop_error_scope()).flatten();
+
+    if let Some(error) = error {
+        match previous {
+            Some(previous) => RELOADED.get_mut().insert(name.to_string(), previous),
+            None => RELOADED.get_mut().remove(name),
+        };
+        bail!("Failed to compile shader {name}: {error}");
+    }
+
+    *WGPUApp::drawer() = drawer;
+
+    Ok(())
+}
+
+/// Error scope futures are resolved right away on native backends.
+fn poll_ready<F: Future>(future: F) -> Option<F::Output> {
+    match pin!(future).poll(&mut Context::from_waker(Waker::noop())) {
+        Poll::Ready(output) => Some(output),
+        Poll::Pending => None,
+    }
+}
@@ -15,8 +15,9 @@ struct SpriteBox {
     @location(2) size:       vec2<f32>,
     @location(3) position:   vec2<f32>,
     @location(4) color:      vec4<f32>,
-    @location(5) rotation:   f32,
-    @location(6) z_position: f32,
+    @location(5) uv:         vec4<f32>,
+    @location(6) rotation:   f32,
+    @location(7) z_position: f32,
 }
 
 @group(0) @binding(0)
@@ -67,7 +68,7 @@ fn v_main(
 
     var out: VertexOutput;
     out.pos   = out_pos;
-    out.uv = model.uv;
+    out.uv = instance.uv.xy + model.uv * instance.uv.zw;
     return out;
 }
 
@@ -10,6 +10,7 @@ use wgpu::{
 use crate::{
     WGPUApp,
     render::{
+        shader::shader_module,
         sprite_drawer::shader_data::{
             FULL_SCREEN_VERTEX_RANGE, FULL_SCREEN_VERTICES, SpriteBox, SpriteRenderView,
         },
@@ -35,7 +36,7 @@ impl Default for BoxPipeline {
     fn default() -> Self {
         let device = WGPUApp::device();
 
-        let shader = device.create_shader_module(include_wgsl!("../shaders/sprite.wgsl"));
+        let shader = shader_module("shaders/sprite.wgsl", include_wgsl!("../shaders/sprite.wgsl"));
 
         let sprite_view_layout = make_uniform_layout("sprite_view_layout", ShaderStages::VERTEX_FRAGMENT);
 
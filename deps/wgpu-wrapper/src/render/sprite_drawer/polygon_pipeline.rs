@@ -8,6 +8,7 @@ use wgpu::{
 use crate::{
     WGPUApp,
     render::{
+        shader::shader_module,
         sprite_drawer::shader_data::SpriteRenderView,
         uniform::{UniformBind, make_bind, make_uniform_layout},
         vertex_layout::VertexLayout,
@@ -42,7 +43,7 @@ impl Default for PolygonPipeline {
     fn default() -> Self {
         let device = WGPUApp::device();
 
-        let shader = device.create_shader_module(include_wgsl!("../shaders/polygon.wgsl"));
+        let shader = shader_module("shaders/polygon.wgsl", include_wgsl!("../shaders/polygon.wgsl"));
 
         let view_layout = make_uniform_layout("polygon_sprite_view_layout", ShaderStages::VERTEX);
         let polygon_view_layout = make_uniform_layout("polygon_view_layout", ShaderStages::VERTEX_FRAGMENT);
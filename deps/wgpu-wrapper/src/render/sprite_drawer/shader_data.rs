@@ -4,7 +4,7 @@ use bytemuck::{Pod, Zeroable};
 use educe::Educe;
 use gm::{
     Color, checked_usize_to_u32,
-    flat::{Point, Size},
+    flat::{Point, Rect, Size},
 };
 use wgpu::{BufferAddress, VertexBufferLayout, VertexStepMode};
 
@@ -50,3 +50,26 @@ impl VertexLayout for SpriteBox {
         attributes:   Self::ATTRIBS,
     };
 }
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone, Zeroable, Pod)]
+pub(super) struct TexturedSpriteBox {
+    pub size:       Size,
+    pub position:   Point,
+    pub color:      Color,
+    /// Drawn part of the texture. Negative size flips it.
+    pub uv:         Rect,
+    pub rotation:   f32,
+    pub z_position: f32,
+}
+
+impl VertexLayout for TexturedSpriteBox {
+    const ATTRIBS: &'static [wgpu::VertexAttribute] = &wgpu::vertex_attr_array![
+        2 => Float32x2, 3 => Float32x2, 4 => Float32x4, 5 => Float32x4, 6 => Float32, 7 => Float32
+    ];
+    const VERTEX_LAYOUT: VertexBufferLayout<'static> = VertexBufferLayout {
+        array_stride: size_of::<Self>() as BufferAddress,
+        step_mode:    VertexStepMode::Instance,
+        attributes:   Self::ATTRIBS,
+    };
+}
@@ -2,7 +2,7 @@ use std::ops::Range;
 
 use gm::{
     Color, checked_usize_to_u32,
-    flat::{Point, Size, Vertex2D},
+    flat::{Point, Rect, Size, Vertex2D},
 };
 use indexmap::IndexMap;
 use refs::Weak;
@@ -12,7 +12,8 @@ use crate::{
     WGPUApp,
     image::Image,
     render::{
-        sprite_drawer::shader_data::{SpriteBox, SpriteRenderView},
+        shader::shader_module,
+        sprite_drawer::shader_data::{SpriteRenderView, TexturedSpriteBox},
         uniform::{UniformBind, make_uniform_layout},
         vec_buffer::VecBuffer,
         vertex_layout::VertexLayout,
@@ -49,13 +50,16 @@ pub struct TexturedBoxPipeline {
 
     vertex_buffer: Buffer,
 
-    instances: IndexMap<Weak<Image>, VecBuffer<SpriteBox>>,
+    instances: IndexMap<Weak<Image>, VecBuffer<TexturedSpriteBox>>,
 }
 
 impl Default for TexturedBoxPipeline {
     fn default() -> Self {
         let device = WGPUApp::device();
-        let shader = device.create_shader_module(wgpu::include_wgsl!("../shaders/sprite_textured.wgsl"));
+        let shader = shader_module(
+            "shaders/sprite_textured.wgsl",
+            wgpu::include_wgsl!("../shaders/sprite_textured.wgsl"),
+        );
 
         let sprite_view_layout = make_uniform_layout("sprites_view_layout", ShaderStages::VERTEX_FRAGMENT);
 
@@ -71,7 +75,7 @@ impl Default for TexturedBoxPipeline {
             &shader,
             PolygonMode::Fill,
             PrimitiveTopology::TriangleStrip,
-            &[Vertex2D::VERTEX_LAYOUT, SpriteBox::VERTEX_LAYOUT],
+            &[Vertex2D::VERTEX_LAYOUT, TexturedSpriteBox::VERTEX_LAYOUT],
         );
 
         let vertex_buffer = device.buffer(&VERTICES, BufferUsages::VERTEX);
@@ -86,6 +90,9 @@ impl Default for TexturedBoxPipeline {
 }
 
 impl TexturedBoxPipeline {
+    /// `uv` is part of the image to draw in texture coordinates. Use
+    /// `Rect::new(0.0, 0.0, 1.0, 1.0)` for whole image.
+    #[allow(clippy::too_many_arguments)]
     pub fn add(
         &mut self,
         image: Weak<Image>,
@@ -94,13 +101,15 @@ impl TexturedBoxPipeline {
         rotation: f32,
         color: Color,
         z_position: f32,
+        uv: Rect,
     ) {
         let image = self.instances.entry(image).or_default();
 
-        image.push(SpriteBox {
+        image.push(TexturedSpriteBox {
             size,
             position,
             color,
+            uv,
             rotation,
             z_position,
         });
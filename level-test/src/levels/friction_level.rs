@@ -31,15 +31,15 @@ impl LevelSetup for FrictionLevel {
         self.make_sprite::<Sensor>(Shape::rect(28, 1), (-40, -20))
             .set_color(Color::ORANGE)
             .on_collision
-            .val(|sprite| {
-                dbg!(sprite.tag);
+            .val(|collision| {
+                dbg!(collision.sprite.tag);
             });
 
         self.make_sprite::<Sensor>(Shape::rect(1, 4), (-18, 44))
             .set_color(Color::ORANGE)
             .on_collision
-            .val(|sprite| {
-                dbg!(sprite.tag);
+            .val(|collision| {
+                dbg!(collision.sprite.tag);
             });
     }
 
@@ -1,12 +1,10 @@
 use std::{any::type_name, io::Write, path::PathBuf, ptr::null_mut, time::Duration};
 
 use anyhow::Result;
+use audio::Mixer;
 use dispatch::{from_main, invoke_dispatched};
 use env_logger::Builder;
-use gm::{
-    LossyConvert,
-    flat::{Point, Size},
-};
+use gm::flat::{Point, Size};
 use level::{LevelBase, LevelManager};
 use log::{Level, LevelFilter};
 use refs::{Own, Rglica};
@@ -23,7 +21,7 @@ use winit::{
 use crate::{
     assets::Assets,
     level_drawer::LevelDrawer,
-    ui::{Input, UI},
+    ui::{Input, InputReplay, UI},
 };
 
 static mut APP: *mut App = null_mut();
@@ -214,15 +212,17 @@ impl wgpu_wrapper::App for App {
         let view = UIManager::root_view_weak().__add_subview_internal(self.first_view.take().unwrap(), true);
         view.place().back();
         self.update();
-        *LevelManager::update_interval() = 1.0 / WGPUApp::display_refresh_rate().lossy_convert();
         self.window_ready.trigger(());
     }
 
     fn update(&mut self) {
         UIManager::free_deleted_views();
         invoke_dispatched();
+        InputReplay::update();
         LevelDrawer::update();
         UI::update();
+        Mixer::set_listener(*LevelManager::camera_pos());
+        Mixer::update();
     }
 
     fn render<'a>(&'a mut self, pass: &mut RenderPass<'a>) {
@@ -0,0 +1,342 @@
+use std::{
+    collections::HashMap,
+    fmt::{Debug, Formatter},
+    marker::PhantomData,
+    path::PathBuf,
+    sync::{
+        Arc, Mutex, OnceLock,
+        atomic::{AtomicU8, Ordering},
+        mpsc::{Receiver, Sender, channel},
+    },
+    thread,
+};
+
+use asset_pack::read;
+use audio::{DecodedSound, Sound};
+use dispatch::{Lane, on_main_in};
+use gm::ToF32;
+use log::error;
+use manage::data_manager::DataManager;
+use refs::Weak;
+use wgpu_wrapper::image::{DecodedImage, Image, ToImage};
+
+const MAX_WORKERS: usize = 4;
+
+static ROOTS: Mutex<Option<Roots>> = Mutex::new(None);
+static PROGRESS: Mutex<LoadProgress> = Mutex::new(LoadProgress {
+    total:  0,
+    loaded: 0,
+    failed: 0,
+});
+/// Load state of every requested asset by kind and name.
+type States = HashMap<(Kind, String), Arc<AtomicU8>>;
+
+static STATES: Mutex<Option<States>> = Mutex::new(None);
+static QUEUE: OnceLock<Mutex<Sender<Job>>> = OnceLock::new();
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum LoadState {
+    Pending,
+    Loaded,
+    Failed,
+}
+
+impl LoadState {
+    fn from_u8(val: u8) -> Self {
+        match val {
+            0 => Self::Pending,
+            1 => Self::Loaded,
+            _ => Self::Failed,
+        }
+    }
+}
+
+/// Aggregate progress of all loads requested since the last time everything
+/// finished loading.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct LoadProgress {
+    pub total:  usize,
+    pub loaded: usize,
+    pub failed: usize,
+}
+
+impl LoadProgress {
+    pub fn finished(&self) -> usize {
+        self.loaded + self.failed
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.finished() >= self.total
+    }
+
+    /// From 0.0 to 1.0
+    pub fn fraction(&self) -> f32 {
+        if self.total == 0 {
+            return 1.0;
+        }
+        self.finished().to_f32() / self.total.to_f32()
+    }
+
+    fn finish(&mut self, state: LoadState) {
+        match state {
+            LoadState::Loaded => self.loaded += 1,
+            LoadState::Failed => self.failed += 1,
+            LoadState::Pending => unreachable!("Load can't finish as pending"),
+        }
+    }
+}
+
+/// Handle to an asset which is loaded in the background. Can be used right
+/// away. Until the asset is uploaded it resolves to a placeholder.
+pub struct AssetHandle<T> {
+    name:  String,
+    state: Arc<AtomicU8>,
+    _p:    PhantomData<fn() -> T>,
+}
+
+impl<T> AssetHandle<T> {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn state(&self) -> LoadState {
+        LoadState::from_u8(self.state.load(Ordering::Acquire))
+    }
+
+    pub fn is_loaded(&self) -> bool {
+        self.state() == LoadState::Loaded
+    }
+}
+
+impl AssetHandle<Image> {
+    pub fn get(&self) -> Weak<Image> {
+        if self.is_loaded() {
+            Image::get(&self.name)
+        } else {
+            Image::placeholder()
+        }
+    }
+}
+
+impl AssetHandle<Sound> {
+    pub fn get(&self) -> Option<Weak<Sound>> {
+        self.is_loaded().then(|| Sound::get(&self.name))
+    }
+}
+
+impl ToImage for AssetHandle<Image> {
+    fn to_image(&self) -> Weak<Image> {
+        self.get()
+    }
+}
+
+impl<T> Clone for AssetHandle<T> {
+    fn clone(&self) -> Self {
+        Self {
+            name:  self.name.clone(),
+            state: self.state.clone(),
+            _p:    PhantomData,
+        }
+    }
+}
+
+impl<T> Debug for AssetHandle<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AssetHandle")
+            .field("name", &self.name)
+            .field("state", &self.state())
+            .finish()
+    }
+}
+
+struct Roots {
+    images: PathBuf,
+    sounds: PathBuf,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+enum Kind {
+    Image,
+    Sound,
+}
+
+struct Job {
+    kind:  Kind,
+    name:  String,
+    path:  PathBuf,
+    state: Arc<AtomicU8>,
+}
+
+pub(crate) struct AssetLoader;
+
+impl AssetLoader {
+    pub(crate) fn set_roots(images: PathBuf, sounds: PathBuf) {
+        *ROOTS.lock().unwrap() = Some(Roots { images, sounds });
+    }
+
+    pub(crate) fn progress() -> LoadProgress {
+        *PROGRESS.lock().unwrap()
+    }
+
+    pub(crate) fn image(name: impl ToString) -> AssetHandle<Image> {
+        Self::load(Kind::Image, name)
+    }
+
+    pub(crate) fn sound(name: impl ToString) -> AssetHandle<Sound> {
+        Self::load(Kind::Sound, name)
+    }
+
+    fn load<T>(kind: Kind, name: impl ToString) -> AssetHandle<T> {
+        let name = name.to_string();
+
+        let path = {
+            let roots = ROOTS.lock().unwrap();
+            let roots = roots.as_ref().expect("Assets::init was not called");
+            match kind {
+                Kind::Image => roots.images.join(&name),
+                Kind::Sound => roots.sounds.join(&name),
+            }
+        };
+
+        let mut states = STATES.lock().unwrap();
+        let state = states
+            .get_or_insert_with(HashMap::default)
+            .entry((kind, name.clone()))
+            .or_insert_with(|| Arc::new(AtomicU8::new(LoadState::Failed as u8)))
+            .clone();
+
+        // Already loaded or in flight. Just hand out another handle.
+        if LoadState::from_u8(state.load(Ordering::Acquire)) != LoadState::Failed {
+            return handle(name, state);
+        }
+
+        state.store(LoadState::Pending as u8, Ordering::Release);
+        drop(states);
+
+        {
+            let mut progress = PROGRESS.lock().unwrap();
+            if progress.is_done() {
+                *progress = LoadProgress::default();
+            }
+            progress.total += 1;
+        }
+
+        let job = Job {
+            kind,
+            name: name.clone(),
+            path,
+            state: state.clone(),
+        };
+
+        queue().lock().unwrap().send(job).expect("Asset loader workers are gone");
+
+        handle(name, state)
+    }
+}
+
+fn handle<T>(name: String, state: Arc<AtomicU8>) -> AssetHandle<T> {
+    AssetHandle {
+        name,
+        state,
+        _p: PhantomData,
+    }
+}
+
+fn queue() -> &'static Mutex<Sender<Job>> {
+    QUEUE.get_or_init(|| {
+        let (sender, receiver) = channel::<Job>();
+        let receiver = Arc::new(Mutex::new(receiver));
+
+        let workers = thread::available_parallelism().map_or(1, |n| n.get().min(MAX_WORKERS));
+
+        for i in 0..workers {
+            let receiver = receiver.clone();
+            thread::Builder::new()
+                .name(format!("asset-loader-{i}"))
+                .spawn(move || worker(&receiver))
+                .expect("Failed to spawn asset loader thread");
+        }
+
+        Mutex::new(sender)
+    })
+}
+
+fn worker(receiver: &Mutex<Receiver<Job>>) {
+    loop {
+        let job = receiver.lock().unwrap().recv();
+        let Ok(job) = job else {
+            return;
+        };
+        decode(job);
+    }
+}
+
+/// Runs on worker thread. Reads and decodes the asset, then sends it to main
+/// thread for upload.
+fn decode(job: Job) {
+    let data = match read(&job.path) {
+        Ok(data) => data,
+        Err(err) => {
+            error!("Failed to read asset: {}. Error: {err}", job.path.display());
+            return finish(&job.state, LoadState::Failed);
+        }
+    };
+
+    match job.kind {
+        Kind::Image => match DecodedImage::decode(&data) {
+            Ok(decoded) => on_main_in(Lane::Background, move || {
+                let state = match Image::from_decoded(&decoded, &job.name) {
+                    Ok(_) => LoadState::Loaded,
+                    Err(err) => {
+                        error!("Failed to upload image: {}. Error: {err}", job.name);
+                        LoadState::Failed
+                    }
+                };
+                finish(&job.state, state);
+            }),
+            Err(err) => {
+                error!("Failed to decode image: {}. Error: {err}", job.path.display());
+                finish(&job.state, LoadState::Failed);
+            }
+        },
+        Kind::Sound => match DecodedSound::decode(&data) {
+            Ok(decoded) => on_main_in(Lane::Background, move || {
+                Sound::from_decoded(decoded, &job.name);
+                finish(&job.state, LoadState::Loaded);
+            }),
+            Err(err) => {
+                error!("Failed to decode sound: {}. Error: {err}", job.path.display());
+                finish(&job.state, LoadState::Failed);
+            }
+        },
+    }
+}
+
+fn finish(state: &AtomicU8, result: LoadState) {
+    state.store(result as u8, Ordering::Release);
+    PROGRESS.lock().unwrap().finish(result);
+}
+
+#[cfg(test)]
+mod test {
+    use crate::asset_loader::LoadProgress;
+
+    #[test]
+    fn progress() {
+        let mut progress = LoadProgress::default();
+        assert!(progress.is_done());
+        assert_eq!(progress.fraction(), 1.0);
+
+        progress.total = 4;
+        assert!(!progress.is_done());
+        assert_eq!(progress.fraction(), 0.0);
+
+        progress.loaded = 2;
+        progress.failed = 1;
+        assert_eq!(progress.finished(), 3);
+        assert_eq!(progress.fraction(), 0.75);
+
+        progress.loaded = 3;
+        assert!(progress.is_done());
+    }
+}
@@ -1,11 +1,16 @@
 use std::path::PathBuf;
 
-use audio::Sound;
+use asset_pack::Pack;
+use audio::{Music, Sound};
+use log::{error, info};
 use manage::data_manager::DataManager;
 use refs::assert_main_thread;
 use wgpu_wrapper::image::Image;
 
-use crate::assets_paths::AssetsPaths;
+use crate::{
+    asset_loader::{AssetHandle, AssetLoader, LoadProgress},
+    assets_paths::AssetsPaths,
+};
 
 pub struct Assets;
 
@@ -15,7 +20,40 @@ impl Assets {
 
         let paths = AssetsPaths::new(root_path.into());
 
+        if paths.pack.exists() {
+            match Pack::open(&paths.pack) {
+                Ok(pack) => {
+                    info!("Mounted asset pack: {}", paths.pack.display());
+                    asset_pack::mount(&paths.assets, pack);
+                }
+                Err(err) => error!("Failed to mount asset pack. Using loose files. Error: {err}"),
+            }
+        }
+
         Image::set_root_path(&paths.images);
         Sound::set_root_path(&paths.sounds);
+        Music::set_root_path(paths.sounds.clone());
+
+        AssetLoader::set_roots(paths.images.clone(), paths.sounds.clone());
+
+        #[cfg(all(desktop, debug_assertions))]
+        if let Err(err) = crate::hot_reload::HotReload::start(&paths) {
+            error!("Failed to start hot reload: {err}");
+        }
+    }
+
+    /// Decodes image on a worker thread and uploads it on main thread.
+    /// Returned handle shows a placeholder until the upload is done.
+    pub fn load_image(name: impl ToString) -> AssetHandle<Image> {
+        AssetLoader::image(name)
+    }
+
+    pub fn load_sound(name: impl ToString) -> AssetHandle<Sound> {
+        AssetLoader::sound(name)
+    }
+
+    /// Progress of all background loads. Can be used to build a loading screen.
+    pub fn progress() -> LoadProgress {
+        AssetLoader::progress()
     }
 }
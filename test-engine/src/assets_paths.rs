@@ -3,7 +3,11 @@ use std::{
     rc::Rc,
 };
 
+use asset_pack::PACK_FILE;
+
 pub(crate) struct AssetsPaths {
+    pub(crate) assets: PathBuf,
+    pub(crate) pack:   PathBuf,
     pub(crate) images: PathBuf,
     pub(crate) sounds: PathBuf,
 }
@@ -13,6 +17,8 @@ impl AssetsPaths {
         let root = Self::root(&root);
         let assets = Self::assets(&root);
         Rc::new(Self {
+            pack:   root.join(PACK_FILE),
+            assets: assets.clone(),
             images: assets.join("Images"),
             sounds: assets.join("Sounds"),
         })
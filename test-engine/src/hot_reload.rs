@@ -0,0 +1,247 @@
+use std::{
+    collections::BTreeSet,
+    fs,
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+use anyhow::Result;
+use audio::{DecodedSound, Sound};
+use dispatch::after;
+use log::{error, info, warn};
+use manage::Managed;
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use refs::assert_main_thread;
+use wgpu_wrapper::{
+    SHADERS_ROOT,
+    image::{DecodedImage, Image},
+    reload_shader,
+};
+
+use crate::assets_paths::AssetsPaths;
+
+/// Editors often write a file in several steps. Changes are collected for
+/// this long before reloading.
+const DEBOUNCE: f32 = 0.1;
+
+static WATCHER: Mutex<Option<RecommendedWatcher>> = Mutex::new(None);
+static CHANGED: Mutex<BTreeSet<PathBuf>> = Mutex::new(BTreeSet::new());
+
+#[derive(Clone)]
+struct Roots {
+    images:  PathBuf,
+    sounds:  PathBuf,
+    shaders: PathBuf,
+}
+
+static ROOTS: Mutex<Option<Roots>> = Mutex::new(None);
+
+/// Development only. Watches asset and shader folders and reloads changed
+/// files in place. Existing `Weak` handles stay valid.
+pub(crate) struct HotReload;
+
+impl HotReload {
+    pub(crate) fn start(paths: &AssetsPaths) -> Result<()> {
+        assert_main_thread();
+
+        if asset_pack::is_mounted() {
+            warn!("Asset pack is mounted. Hot reload is disabled");
+            return Ok(());
+        }
+
+        let mut watcher = notify::recommended_watcher(|event: notify::Result<notify::Event>| match event {
+            Ok(event) => {
+                if matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_)) {
+                    schedule(event.paths);
+                }
+            }
+            Err(err) => error!("Hot reload watcher error: {err}"),
+        })?;
+
+        watcher.watch(&paths.assets, RecursiveMode::Recursive)?;
+
+        let shaders = PathBuf::from(SHADERS_ROOT);
+
+        if shaders.exists() {
+            watcher.watch(&shaders, RecursiveMode::Recursive)?;
+        }
+
+        *ROOTS.lock().unwrap() = Some(Roots {
+            images: paths.images.clone(),
+            sounds: paths.sounds.clone(),
+            shaders,
+        });
+        *WATCHER.lock().unwrap() = Some(watcher);
+
+        info!("Hot reload enabled");
+
+        Ok(())
+    }
+}
+
+/// Called on watcher thread.
+fn schedule(paths: Vec<PathBuf>) {
+    let mut changed = CHANGED.lock().unwrap();
+    let first = changed.is_empty();
+    changed.extend(paths);
+
+    if first {
+        after(DEBOUNCE, reload_changed);
+    }
+}
+
+fn reload_changed() {
+    let changed = std::mem::take(&mut *CHANGED.lock().unwrap());
+
+    let Some(roots) = ROOTS.lock().unwrap().clone() else {
+        return;
+    };
+
+    for path in changed {
+        if !path.is_file() {
+            continue;
+        }
+
+        let result = if let Some(name) = relative(&path, &roots.images) {
+            reload_image(&path, &name)
+        } else if let Some(name) = relative(&path, &roots.sounds) {
+            reload_sound(&path, &name)
+        } else if let Some(name) = relative(&path, &roots.shaders)
+            && path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("wgsl"))
+        {
+            fs::read_to_string(&path)
+                .map_err(Into::into)
+                .and_then(|source| reload_shader(&name, source))
+                .map(|()| info!("Reloaded shader: {name}"))
+        } else {
+            Ok(())
+        };
+
+        if let Err(err) = result {
+            error!("Hot reload failed for {}: {err}", path.display());
+        }
+    }
+}
+
+fn reload_image(path: &Path, name: &str) -> Result<()> {
+    // `load_path` panics on broken images
+    if reload::<Image>(path, name, |data| {
+        DecodedImage::decode(data).map(drop).map_err(Into::into)
+    })? {
+        info!("Reloaded image: {name}");
+    }
+    Ok(())
+}
+
+fn reload_sound(path: &Path, name: &str) -> Result<()> {
+    // `load_path` silently replaces broken sounds with the default one
+    if reload::<Sound>(path, name, |data| {
+        DecodedSound::decode(data).map(drop).map_err(Into::into)
+    })? {
+        info!("Reloaded sound: {name}");
+    }
+    Ok(())
+}
+
+/// Loads the asset again with `ResourceLoader::load_path` in place of the
+/// existing one. Only assets which are already loaded are reloaded. File is
+/// validated first because it can be caught in the middle of saving.
+fn reload<T: Managed>(path: &Path, name: &str, validate: impl FnOnce(&[u8]) -> Result<()>) -> Result<bool> {
+    let Some(mut asset) = T::get_existing(name) else {
+        return Ok(false);
+    };
+
+    validate(&fs::read(path)?)?;
+
+    *asset = T::load_path(path);
+
+    Ok(true)
+}
+
+fn relative(path: &Path, root: &Path) -> Option<String> {
+    let relative = path.strip_prefix(root).ok()?;
+    Some(relative.to_string_lossy().replace('\\', "/"))
+}
+
+#[cfg(test)]
+mod test {
+    use std::{
+        fs,
+        thread::sleep,
+        time::{Duration, Instant},
+    };
+
+    use audio::{DecodedSound, Sound};
+    use dispatch::{Scheduler, invoke_dispatched};
+    use generate::sfx::wav;
+    use gm::{LossyConvert, ToF32};
+    use refs::set_current_thread_as_main;
+
+    use crate::{
+        assets_paths::AssetsPaths,
+        hot_reload::{CHANGED, DEBOUNCE, HotReload, WATCHER},
+    };
+
+    fn sound(seconds: f32, sample_rate: u32) -> Vec<u8> {
+        let len: usize = (seconds * sample_rate.to_f32()).lossy_convert();
+        wav(&vec![0.5; len], sample_rate)
+    }
+
+    fn wait_for_change() {
+        let start = Instant::now();
+
+        while CHANGED.lock().unwrap().is_empty() {
+            assert!(
+                start.elapsed() < Duration::from_secs(5),
+                "Watcher didn't report the change"
+            );
+            sleep(Duration::from_millis(10));
+        }
+    }
+
+    #[test]
+    fn reload_changed_sound() {
+        set_current_thread_as_main();
+        Scheduler::set_manual_clock(true);
+
+        let root = std::env::temp_dir().join("test-engine-hot-reload");
+        _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(root.join("Sounds")).unwrap();
+        // Watcher reports resolved paths. Temp dir can be a symlink.
+        let root = root.canonicalize().unwrap();
+
+        let paths = AssetsPaths {
+            assets: root.clone(),
+            pack:   root.join("pack"),
+            images: root.join("Images"),
+            sounds: root.join("Sounds"),
+        };
+
+        let file = paths.sounds.join("hot_reload_test.wav");
+        let original = sound(0.1, 44100);
+        fs::write(&file, &original).unwrap();
+
+        let sound = Sound::from_decoded(DecodedSound::decode(&original).unwrap(), "hot_reload_test.wav");
+        assert_eq!(sound.decoded().sample_rate(), 44100);
+
+        HotReload::start(&paths).unwrap();
+
+        fs::write(&file, self::sound(0.5, 22050)).unwrap();
+        wait_for_change();
+
+        // Nothing is reloaded until debounce delay passes
+        invoke_dispatched();
+        assert_eq!(sound.decoded().sample_rate(), 44100);
+
+        Scheduler::advance(Duration::from_secs_f32(DEBOUNCE));
+        invoke_dispatched();
+
+        assert_eq!(sound.decoded().sample_rate(), 22050);
+        assert_eq!(sound.decoded().duration(), Duration::from_millis(500));
+        assert!(CHANGED.lock().unwrap().is_empty());
+
+        *WATCHER.lock().unwrap() = None;
+        Scheduler::set_manual_clock(false);
+        _ = fs::remove_dir_all(&root);
+    }
+}
@@ -1,4 +1,5 @@
-use level::LevelManager;
+use gm::{Color, flat::Size};
+use level::{Gizmo, LevelEditor, LevelManager};
 use manage::{ExistsManaged, data_manager::DataManager};
 use ui::UIManager;
 use wgpu::RenderPass;
@@ -40,29 +41,34 @@ impl LevelDrawer {
                 drawer.textured_box.add(
                     sprite.image,
                     sprite.render_size(),
-                    sprite.position(),
-                    sprite.rotation(),
+                    sprite.render_position(),
+                    sprite.render_rotation(),
                     *sprite.color(),
                     sprite.z_position,
+                    sprite.uv(),
                 );
             } else if let Some(vertex_buffer) = &sprite.vertex_buffer {
                 drawer.polygon.add(
                     vertex_buffer,
-                    sprite.position(),
+                    sprite.render_position(),
                     *sprite.color(),
-                    sprite.rotation(),
+                    sprite.render_rotation(),
                 );
             } else {
                 drawer.sprite_box.add(
                     sprite.render_size(),
-                    sprite.position(),
-                    sprite.rotation(),
+                    sprite.render_position(),
+                    sprite.render_rotation(),
                     *sprite.color(),
                     sprite.z_position,
                 );
             }
         }
 
+        if LevelEditor::is_enabled() {
+            Self::add_gizmos();
+        }
+
         drawer.sprite_box.draw(pass, scale, 0.0, camera_pos, resolution);
         drawer.textured_box.draw(pass, scale, 0.0, camera_pos, resolution);
 
@@ -73,4 +79,38 @@ impl LevelDrawer {
             scale,
         });
     }
+
+    /// Selection outline behind selected sprite and its handles on top of
+    /// everything.
+    fn add_gizmos() {
+        let selected = LevelEditor::selected();
+
+        if !selected.is_ok() {
+            return;
+        }
+
+        let drawer = WGPUApp::drawer();
+        let position = selected.position();
+        let rotation = selected.rotation();
+        let size = selected.render_size();
+        let handle_size = LevelEditor::handle_size();
+
+        drawer.sprite_box.add(
+            Size::new(size.width + handle_size / 2.0, size.height + handle_size / 2.0),
+            position,
+            rotation,
+            Color::rgba(1.0, 1.0, 0.0, 0.5),
+            selected.z_position + LevelManager::z_position_offset() / 2.0,
+        );
+
+        for (gizmo, color) in Gizmo::HANDLES.into_iter().zip([Color::LIGHT_BLUE, Color::ORANGE]) {
+            drawer.sprite_box.add(
+                Size::new(handle_size, handle_size),
+                gizmo.handle(position, size, rotation, handle_size),
+                rotation,
+                color,
+                0.1,
+            );
+        }
+    }
 }
@@ -5,8 +5,11 @@
 #![feature(const_trait_impl)]
 
 mod app;
+mod asset_loader;
 mod assets;
 mod assets_paths;
+#[cfg(all(desktop, debug_assertions))]
+mod hot_reload;
 mod level_drawer;
 
 pub mod ui;
@@ -16,9 +19,13 @@ pub use ui::ui_test;
 
 pub mod level {
     pub use ::level::{
-        Banner, Body, CoefficientCombineRule, Control, Level, LevelBase, LevelCreation, LevelInternal,
-        LevelManager, LevelSetup, LevelTemplates, Player, Sensor, Sprite, SpriteData, SpriteTemplates, Wall,
-        level,
+        AnimationClip, Animator, Banner, Body, CoefficientCombineRule, Collision, CollisionGroups,
+        Components, Contact, ContactManifold, Control, FixedJoint, FixedTimestep, Frame, Gizmo, Joint,
+        JointBuilder, JointSettings, Level, LevelBase, LevelCreation, LevelDocument, LevelEditor,
+        LevelInternal, LevelManager, LevelSetup, LevelSnapshot, LevelTemplates, Motor, PhysicsDocument,
+        PlayMode, Player, PrismaticJoint, Ragdoll, RayHit, RevoluteJoint, Rewind, RopeJoint, Sensor,
+        SpringJoint, Sprite, SpriteData, SpriteDocument, SpriteFilter, SpriteSheet, SpriteTemplates,
+        SystemStage, Unit, Wall, level,
     };
 }
 
@@ -44,7 +51,10 @@ pub mod gm {
 
 pub mod store {
     pub(crate) use store;
-    pub use store::{EncryptionKey, OnDisk, OnDiskEncrypted, Paths};
+    pub use store::{
+        Binary, Cached, Compression, EncryptionKey, Format, Json, Migration, OnDisk, OnDiskEncrypted, Paths,
+        Profile, Ron, StorageScope, StoreError, StoreResult, Toml, Transaction,
+    };
 }
 
 pub mod time {
@@ -52,8 +62,14 @@ pub mod time {
 }
 
 pub use app::App;
+pub use asset_loader::{AssetHandle, LoadProgress, LoadState};
+pub use assets::Assets;
 pub use audio;
-pub use dispatch::{after, async_after, from_main, on_main, wait_for_next_frame};
+pub use dispatch::{
+    BlockedOn, Delay, DispatchError, DispatchStats, Lane, Scheduler, TaskHandle, Timer, after, async_after,
+    blocked_on, dispatch_stats, from_main, on_main, on_main_in, on_main_sync, on_main_sync_timeout,
+    set_frame_budget, wait, wait_for_next_frame,
+};
 pub use generate;
 pub use manage::data_manager::DataManager;
 pub use vents::{DelayedEvent, Event, OnceEvent};
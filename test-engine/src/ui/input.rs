@@ -1,11 +1,14 @@
-use gm::Color;
-use level::LevelManager;
+use gm::{Color, volume::GyroData};
+use level::{LevelEditor, LevelManager};
 use log::warn;
 use ui::{
-    Container, Setup, Touch, TouchStack, UIEvents, UIManager, ViewData, ViewFrame, ViewSubviews, check_touch,
+    Container, Setup, Touch, TouchEvent, TouchStack, UIEvents, UIManager, ViewData, ViewFrame, ViewSubviews,
+    check_touch,
 };
 pub use winit::{event::KeyEvent, keyboard::NamedKey};
 
+use crate::ui::{InputRecorder, InputReplay};
+
 const LOG_TOUCHES: bool = false;
 const DRAW_TOUCHES: bool = false;
 
@@ -13,17 +16,38 @@ pub struct Input;
 
 impl Input {
     pub fn on_char(ch: char) {
+        if !InputReplay::accepts_input() {
+            return;
+        }
+        InputRecorder::record(ch);
         UIManager::keymap().check(ch);
         UIEvents::keyboard_input().trigger(ch);
     }
 
     pub fn on_key(key: NamedKey) {
+        if !InputReplay::accepts_input() {
+            return;
+        }
+        InputRecorder::record(key);
         UIEvents::keyboard_key().trigger(key);
     }
 
+    pub fn on_gyro(gyro: GyroData) {
+        if !InputReplay::accepts_input() {
+            return;
+        }
+        InputRecorder::record(gyro);
+        UIEvents::gyro().trigger(gyro);
+    }
+
     pub fn process_touch_event(mut touch: Touch) -> bool {
         UIEvents::on_debug_touch().trigger(touch);
 
+        if !InputReplay::accepts_input() {
+            return false;
+        }
+        InputRecorder::record(touch);
+
         if UIManager::touch_disabled() {
             return false;
         }
@@ -63,7 +87,19 @@ impl Input {
         //     }
         // }
 
-        if touch.is_began() && !LevelManager::no_level() {
+        if LevelManager::no_level() {
+            return false;
+        }
+
+        if LevelEditor::is_enabled() {
+            return match touch.event {
+                TouchEvent::Began => LevelEditor::touch_began(touch.position),
+                TouchEvent::Moved => LevelEditor::touch_moved(touch.position),
+                TouchEvent::Ended => LevelEditor::touch_ended(touch.position),
+            };
+        }
+
+        if touch.is_began() {
             return LevelManager::level_weak().add_touch(touch.position);
         }
 
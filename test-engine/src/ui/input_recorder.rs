@@ -0,0 +1,316 @@
+use std::{fs, path::Path};
+
+use anyhow::Result;
+use gm::{flat::Point, volume::GyroData};
+use level::{Level, LevelManager};
+use refs::{MainLock, Weak};
+use serde::{Deserialize, Serialize};
+use ui::{Touch, TouchEvent};
+use wgpu_wrapper::MouseButton;
+use winit::keyboard::NamedKey;
+
+use crate::ui::Input;
+
+static RECORDER: MainLock<InputRecorder> = MainLock::new();
+static REPLAY: MainLock<InputReplay> = MainLock::new();
+
+/// Single input passed through [`Input`].
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum InputEvent {
+    Touch {
+        id:       u64,
+        position: Point,
+        event:    TouchEvent,
+        button:   MouseButton,
+    },
+    Char(char),
+    Key(NamedKey),
+    Gyro {
+        pitch: f32,
+        roll:  f32,
+        yaw:   f32,
+    },
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RecordedInput {
+    /// Physics steps simulated before the input arrived.
+    pub step:  u64,
+    pub event: InputEvent,
+}
+
+/// Input of a level session. Replayed with [`InputReplay::start`] it
+/// reproduces the same run.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Recording {
+    /// [`LevelManager::seed`] the level was started with.
+    pub seed:   u64,
+    /// Physics steps in the session.
+    pub length: u64,
+    pub events: Vec<RecordedInput>,
+}
+
+impl Recording {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        serde_json::from_str(&fs::read_to_string(path)?)
+            .map_err(|err| anyhow::anyhow!("Failed to load recording from {}: {err}", path.display()))
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, serde_json::to_string(self)?)?;
+        Ok(())
+    }
+}
+
+/// Logs input for each physics step of the current level.
+#[derive(Default)]
+pub struct InputRecorder {
+    recording: Option<Recording>,
+}
+
+impl InputRecorder {
+    /// Restarts level `T` in deterministic mode with current
+    /// [`LevelManager::seed`] and starts recording.
+    pub fn start<T: Level + Default + 'static>() -> Weak<T> {
+        let seed = LevelManager::seed();
+        let level = start_level::<T>(seed);
+
+        RECORDER.get_mut().recording = Some(Recording {
+            seed,
+            ..Default::default()
+        });
+
+        level
+    }
+
+    pub fn stop() -> Option<Recording> {
+        let mut recording = RECORDER.get_mut().recording.take()?;
+        recording.length = current_step();
+        Some(recording)
+    }
+
+    pub fn is_recording() -> bool {
+        RECORDER.recording.is_some()
+    }
+
+    pub(crate) fn record(event: impl Into<InputEvent>) {
+        if InputReplay::is_playing() {
+            return;
+        }
+
+        let Some(recording) = &mut RECORDER.get_mut().recording else {
+            return;
+        };
+
+        recording.events.push(RecordedInput {
+            step:  current_step(),
+            event: event.into(),
+        });
+    }
+}
+
+/// Feeds recorded input back through [`Input`]. Real input is ignored while
+/// replay is playing.
+#[derive(Default)]
+pub struct InputReplay {
+    recording: Option<Recording>,
+    next:      usize,
+    feeding:   bool,
+}
+
+impl InputReplay {
+    /// Restarts level `T` with seed of the recording and plays it.
+    pub fn start<T: Level + Default + 'static>(recording: Recording) -> Weak<T> {
+        let level = start_level::<T>(recording.seed);
+
+        let replay = REPLAY.get_mut();
+        replay.recording = Some(recording);
+        replay.next = 0;
+
+        level
+    }
+
+    pub fn stop() {
+        REPLAY.get_mut().recording = None;
+    }
+
+    pub fn is_playing() -> bool {
+        REPLAY.recording.is_some()
+    }
+
+    /// `true` if input comes from real devices.
+    pub(crate) fn accepts_input() -> bool {
+        !Self::is_playing() || REPLAY.feeding
+    }
+
+    /// Feeds input recorded before current step. Called every frame before
+    /// level update.
+    pub(crate) fn update() {
+        if LevelManager::no_level() {
+            return;
+        }
+
+        let replay = REPLAY.get_mut();
+
+        let Some(recording) = replay.recording.take() else {
+            return;
+        };
+
+        let step = current_step();
+
+        replay.feeding = true;
+
+        while let Some(input) = recording.events.get(replay.next)
+            && input.step <= step
+        {
+            input.event.feed();
+            replay.next += 1;
+        }
+
+        replay.feeding = false;
+
+        if replay.next < recording.events.len() || step < recording.length {
+            replay.recording = Some(recording);
+        }
+    }
+}
+
+impl InputEvent {
+    fn feed(&self) {
+        match self {
+            Self::Touch {
+                id,
+                position,
+                event,
+                button,
+            } => {
+                Input::process_touch_event(Touch {
+                    id:       *id,
+                    position: *position,
+                    event:    *event,
+                    button:   *button,
+                });
+            }
+            Self::Char(ch) => Input::on_char(*ch),
+            Self::Key(key) => Input::on_key(*key),
+            Self::Gyro { pitch, roll, yaw } => Input::on_gyro(GyroData {
+                pitch: *pitch,
+                roll:  *roll,
+                yaw:   *yaw,
+            }),
+        }
+    }
+}
+
+impl From<Touch> for InputEvent {
+    fn from(touch: Touch) -> Self {
+        Self::Touch {
+            id:       touch.id,
+            position: touch.position,
+            event:    touch.event,
+            button:   touch.button,
+        }
+    }
+}
+
+impl From<char> for InputEvent {
+    fn from(ch: char) -> Self {
+        Self::Char(ch)
+    }
+}
+
+impl From<NamedKey> for InputEvent {
+    fn from(key: NamedKey) -> Self {
+        Self::Key(key)
+    }
+}
+
+impl From<GyroData> for InputEvent {
+    fn from(gyro: GyroData) -> Self {
+        Self::Gyro {
+            pitch: gyro.pitch,
+            roll:  gyro.roll,
+            yaw:   gyro.yaw,
+        }
+    }
+}
+
+fn start_level<T: Level + Default + 'static>(seed: u64) -> Weak<T> {
+    LevelManager::set_seed(seed);
+    let mut level = LevelManager::set_level(T::default());
+    level.timestep.deterministic = true;
+    level
+}
+
+fn current_step() -> u64 {
+    if LevelManager::no_level() {
+        return 0;
+    }
+    LevelManager::level().timestep.step_count()
+}
+
+#[cfg(test)]
+mod test {
+    use ui::{Touch, TouchEvent};
+    use wgpu_wrapper::MouseButton;
+    use winit::keyboard::NamedKey;
+
+    use crate::ui::{InputEvent, RecordedInput, Recording};
+
+    #[test]
+    fn input_event_round_trip() -> anyhow::Result<()> {
+        let recording = Recording {
+            seed:   42,
+            length: 120,
+            events: vec![
+                RecordedInput {
+                    step:  3,
+                    event: Touch {
+                        id:       1,
+                        position: (10.5, 20.0).into(),
+                        event:    TouchEvent::Began,
+                        button:   MouseButton::Right,
+                    }
+                    .into(),
+                },
+                RecordedInput {
+                    step:  5,
+                    event: 'w'.into(),
+                },
+                RecordedInput {
+                    step:  8,
+                    event: NamedKey::Enter.into(),
+                },
+            ],
+        };
+
+        let json = serde_json::to_string(&recording)?;
+        assert_eq!(serde_json::from_str::<Recording>(&json)?, recording);
+
+        for button in [MouseButton::Left, MouseButton::Forward, MouseButton::Other(7)] {
+            let event = InputEvent::Touch {
+                id: 2,
+                position: (0.0, 0.0).into(),
+                event: TouchEvent::Ended,
+                button,
+            };
+            assert_eq!(
+                serde_json::from_str::<InputEvent>(&serde_json::to_string(&event)?)?,
+                event
+            );
+        }
+
+        let key = InputEvent::from(NamedKey::F24);
+        assert_eq!(
+            serde_json::from_str::<InputEvent>(&serde_json::to_string(&key)?)?,
+            key
+        );
+
+        Ok(())
+    }
+}
@@ -1,4 +1,5 @@
 mod input;
+mod input_recorder;
 mod ui;
 pub mod ui_test;
 mod views;
@@ -9,9 +10,10 @@ pub use gm::{
     flat::{Point, PointsPath, Rect, Size},
 };
 pub use input::*;
+pub use input_recorder::{InputEvent, InputRecorder, InputReplay, RecordedInput, Recording};
 pub use ui::UI;
 pub use ui_proc::view;
 pub use views::color_meter::ColorMeter;
 pub use wgpu_wrapper::{PolygonMode, Screenshot, image::Image, include_images};
 
-pub use crate::ui::views::sprite_view::SpriteView;
+pub use crate::ui::views::{level_editor_view::LevelEditorView, sprite_view::SpriteView};
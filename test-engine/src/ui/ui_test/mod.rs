@@ -10,7 +10,7 @@ use std::{
 use anyhow::{Result, bail};
 pub use helpers::*;
 use log::{error, warn};
-use refs::Own;
+use refs::{Own, Weak};
 use serde::de::DeserializeOwned;
 pub use state::*;
 use tokio::sync::mpsc::channel;
@@ -18,8 +18,9 @@ use tokio::sync::mpsc::channel;
 use crate::{
     App, from_main,
     gm::{LossyConvert, ToF32},
+    level::Level,
     on_main,
-    ui::{Input, Touch, U8Color, UIEvents, UIManager},
+    ui::{Input, InputReplay, Recording, Touch, U8Color, UIEvents, UIManager},
     wait_for_next_frame,
 };
 
@@ -82,6 +83,18 @@ pub async fn inject_touches_delayed(data: &str) {
     }
 }
 
+/// Plays recording on level `T` and waits until it ends. Check level state
+/// afterwards to use replays as regression tests.
+pub async fn play_recording<T: Level + Default + 'static>(recording: Recording) -> Weak<T> {
+    let level = from_main(move || InputReplay::start::<T>(recording)).await;
+
+    while from_main(InputReplay::is_playing).await {
+        wait_for_next_frame().await;
+    }
+
+    level
+}
+
 pub async fn inject_keys(s: impl ToString) {
     let s = s.to_string();
     for ch in s.chars() {
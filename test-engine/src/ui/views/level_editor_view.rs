@@ -0,0 +1,200 @@
+use std::path::PathBuf;
+
+use level::{LevelEditor, SpriteDocument};
+use reflected::Reflected;
+use refs::Weak;
+use ui::{
+    AlertErr, Anchor, Button, FormView, HasText, HasTitle, Labeled, Setup, Switch, ViewCallbacks, ViewData,
+};
+use ui_proc::view;
+
+use crate as test_engine;
+
+/// Editable properties of selected sprite.
+#[derive(Clone, Default, Debug, Reflected)]
+pub struct SpriteProperties {
+    x:                   f32,
+    y:                   f32,
+    rotation:            f32,
+    width:               f32,
+    height:              f32,
+    z_position:          f32,
+    tag:                 u32,
+    friction:            f32,
+    restitution:         f32,
+    density:             f32,
+    rotation_locked:     bool,
+    collision_detection: bool,
+}
+
+impl SpriteProperties {
+    fn new(document: &SpriteDocument) -> Self {
+        let mut properties = Self {
+            x: document.position.x,
+            y: document.position.y,
+            rotation: document.rotation,
+            width: document.shape.width(),
+            height: document.shape.height(),
+            z_position: document.z_position.unwrap_or_default(),
+            tag: document.tag,
+            ..Default::default()
+        };
+
+        if let Some(physics) = &document.physics {
+            properties.friction = physics.friction;
+            properties.restitution = physics.restitution;
+            properties.density = physics.density;
+            properties.rotation_locked = physics.rotation_locked;
+            properties.collision_detection = physics.collision_detection;
+        }
+
+        properties
+    }
+
+    fn apply(&self, document: &mut SpriteDocument) {
+        document.position = (self.x, self.y).into();
+        document.rotation = self.rotation;
+        document.z_position = Some(self.z_position);
+        document.tag = self.tag;
+
+        let (width, height) = (document.shape.width(), document.shape.height());
+
+        if width > 0.0 && height > 0.0 && self.width > 0.0 && self.height > 0.0 {
+            document.shape = document.shape.scaled((self.width / width, self.height / height));
+        }
+
+        if let Some(physics) = &mut document.physics {
+            physics.friction = self.friction;
+            physics.restitution = self.restitution;
+            physics.density = self.density;
+            physics.rotation_locked = self.rotation_locked;
+            physics.collision_detection = self.collision_detection;
+        }
+    }
+}
+
+/// Toolbar and inspector for [`LevelEditor`]. Enable the editor with
+/// [`LevelEditor::set_enabled`] to select sprites on the level.
+#[view]
+pub struct LevelEditorView {
+    revision:  u64,
+    #[educe(Default = PathBuf::from("level.json"))]
+    save_path: PathBuf,
+
+    #[init]
+    undo:      Button,
+    redo:      Button,
+    duplicate: Button,
+    delete:    Button,
+    grid:      Labeled<Switch>,
+    save:      Button,
+    apply:     Button,
+    form:      FormView<SpriteProperties>,
+}
+
+impl Setup for LevelEditorView {
+    fn setup(mut self: Weak<Self>) {
+        const HEIGHT: f32 = 28.0;
+        const WIDTH: f32 = 70.0;
+        const GAP: f32 = 5.0;
+
+        self.revision = u64::MAX;
+
+        self.undo.set_text("Undo").place().tl(0).size(WIDTH, HEIGHT);
+        self.undo.on_tap(|| {
+            LevelEditor::undo().alert_err();
+        });
+
+        self.redo.set_text("Redo");
+        self.redo.place().t(0).size(WIDTH, HEIGHT).anchor(Anchor::Left, self.undo, GAP);
+        self.redo.on_tap(|| {
+            LevelEditor::redo().alert_err();
+        });
+
+        self.duplicate.set_text("Copy");
+        self.duplicate
+            .place()
+            .t(0)
+            .size(WIDTH, HEIGHT)
+            .anchor(Anchor::Left, self.redo, GAP);
+        self.duplicate.on_tap(|| {
+            LevelEditor::duplicate_selected().alert_err();
+        });
+
+        self.delete.set_text("Delete");
+        self.delete
+            .place()
+            .t(0)
+            .size(WIDTH, HEIGHT)
+            .anchor(Anchor::Left, self.duplicate, GAP);
+        self.delete.on_tap(|| {
+            LevelEditor::delete_selected().alert_err();
+        });
+
+        self.grid.set_title("Grid");
+        self.grid
+            .place()
+            .l(0)
+            .size(WIDTH * 2.0, HEIGHT)
+            .anchor(Anchor::Top, self.undo, GAP);
+        self.grid.input.set_on(LevelEditor::grid().is_some());
+        self.grid.input.selected.val(|on| {
+            LevelEditor::set_grid(on.then_some(0.5_f32));
+        });
+
+        self.save.set_text("Save");
+        self.save
+            .place()
+            .size(WIDTH, HEIGHT)
+            .anchor(Anchor::Top, self.undo, GAP)
+            .anchor(Anchor::Left, self.grid, GAP);
+        self.save.on_tap(move || {
+            LevelEditor::save(&self.save_path).alert_err();
+        });
+
+        self.apply.set_text("Apply");
+        self.apply
+            .place()
+            .size(WIDTH, HEIGHT)
+            .anchor(Anchor::Top, self.undo, GAP)
+            .anchor(Anchor::Left, self.save, GAP);
+        self.apply.on_tap(move || self.apply_properties());
+
+        self.form.place().lrb(0).anchor(Anchor::Top, self.grid, GAP);
+    }
+}
+
+impl ViewCallbacks for LevelEditorView {
+    fn update(&mut self) {
+        if self.revision == LevelEditor::revision() {
+            return;
+        }
+        self.revision = LevelEditor::revision();
+
+        let document = LevelEditor::selected_document().ok().flatten();
+
+        if let Some(document) = document {
+            self.form.set_data(&SpriteProperties::new(&document));
+            self.form.enable_editing();
+        } else {
+            self.form.set_data(&SpriteProperties::default());
+            self.form.disable_editing();
+        }
+    }
+}
+
+impl LevelEditorView {
+    /// Where Save button writes the level. `level.json` by default.
+    pub fn set_save_path(&mut self, path: impl Into<PathBuf>) -> &mut Self {
+        self.save_path = path.into();
+        self
+    }
+
+    fn apply_properties(&self) {
+        let Some(Some(mut document)) = LevelEditor::selected_document().alert_err() else {
+            return;
+        };
+        self.form.get_data().apply(&mut document);
+        LevelEditor::update_selected(&document).alert_err();
+    }
+}
@@ -1,2 +1,3 @@
 pub(crate) mod color_meter;
+pub mod level_editor_view;
 pub mod sprite_view;
@@ -1,16 +1,19 @@
+use fake::Fake;
 use test_engine::{
     App, DataManager, async_after,
     audio::Sound,
+    generate::sfx::SfxPreset,
     gm::{Apply, Direction, LossyConvert},
-    level::{Control, LevelManager},
+    level::{LevelEditor, LevelManager},
     refs::Weak,
     store::OnDisk,
     ui::{
-        Alert, Anchor,
+        Alert, AlertErr, Anchor,
         Anchor::{Height, Left, Top, Width, X, Y},
-        Button, Color, ColorMeter, Container, DPadView, DrawingView, HasText, HasTitle, ImageView, Label,
-        MovableView, NumberView, Point, PointsPath, PositionView, Setup, Spinner, SpriteView, StickView,
-        Switch, TextField, TransitionButton, UIManager, ViewData, ViewFrame, view,
+        Button, Color, ColorMeter, Container, DPadView, DrawingView, HasText, HasTitle, ImageView,
+        InputRecorder, InputReplay, Label, LevelEditorView, MovableView, NumberView, Point, PointsPath,
+        PositionView, Recording, Setup, Spinner, SpriteView, StickView, Switch, TextField, TransitionButton,
+        UIManager, ViewData, ViewFrame, view,
     },
 };
 use ui_benchmark::BenchmarkView;
@@ -69,6 +72,7 @@ pub struct TestGameView {
     some_button: Button,
 
     sprite_view: MovableView<SpriteView>,
+    editor:      MovableView<LevelEditorView>,
 
     bool_storage_view: Switch,
 
@@ -115,7 +119,7 @@ impl Setup for TestGameView {
         self.dpad.place().size(200, 140).b(20).anchor(Anchor::Left, self.bl, 10);
 
         self.dpad.on_press.val(move |direction| {
-            self.level.player.unit.body.move_by_direction(direction);
+            self.level.move_player(direction);
 
             self.label_l.set_text(format!("{direction:?}"));
             App::set_window_title(format!("{direction:?}"));
@@ -160,7 +164,11 @@ impl Setup for TestGameView {
             .anchor(Anchor::Bot, self.alert, 10);
         self.sound.set_text("Sound");
         self.sound.set_text_size(20);
-        self.sound.on_tap(|| Sound::get("retro.wav").play());
+        self.sound.on_tap(|| {
+            let preset = SfxPreset::ALL[(0..SfxPreset::ALL.len()).fake::<usize>()];
+            let seed: u32 = (0..8).fake();
+            Sound::load(&preset.wav(seed), format!("{preset:?}-{seed}")).play();
+        });
 
         self.color_meter.place().size(100, 100).b(10).anchor(Anchor::Right, self.br, 10);
 
@@ -253,6 +261,10 @@ impl Setup for TestGameView {
         let player = self.level.player;
         self.sprite_view.set_sprite(player);
 
+        self.editor.set_title("Editor:");
+        self.editor.place().size(320, 480).center_y().l(0);
+        self.editor.set_hidden(!LevelEditor::is_enabled());
+
         self.bool_storage_view.set_off_color(Color::WHITE).set_on(BOOL.get());
         self.bool_storage_view
             .place()
@@ -283,7 +295,7 @@ impl TestGameView {
         ]
         .apply(|(key, direction)| {
             UIManager::keymap().add(self, key, move || {
-                self.level.player.unit.body.move_by_direction(direction);
+                self.level.move_player(direction);
             });
         });
 
@@ -295,6 +307,74 @@ impl TestGameView {
             *LevelManager::scale() /= 2.0;
         });
 
+        UIManager::keymap().add(self, 'e', move || {
+            LevelEditor::toggle();
+            self.editor.set_hidden(!LevelEditor::is_enabled());
+        });
+
+        [
+            ('z', LevelEditor::undo as fn() -> _),
+            ('y', LevelEditor::redo),
+            ('c', LevelEditor::duplicate_selected),
+            ('x', LevelEditor::delete_selected),
+        ]
+        .apply(|(key, action)| {
+            UIManager::keymap().add(self, key, move || {
+                if LevelEditor::is_enabled() {
+                    action().alert_err();
+                }
+            });
+        });
+
+        UIManager::keymap().add(self, 'p', move || {
+            let timestep = &mut self.level.timestep;
+            if timestep.is_paused() {
+                timestep.resume();
+            } else {
+                timestep.pause();
+            }
+        });
+
+        UIManager::keymap().add(self, 't', move || {
+            let timestep = &mut self.level.timestep;
+            timestep.time_scale = if timestep.time_scale < 1.0 { 1.0 } else { 0.25 };
+        });
+
+        UIManager::keymap().add(self, 'q', move || {
+            self.level.rewind.rewind().alert_err();
+        });
+
+        UIManager::keymap().add(self, 'k', move || {
+            self.level.checkpoint = Some(LevelManager::snapshot());
+        });
+
+        UIManager::keymap().add(self, 'j', move || {
+            if let Some(checkpoint) = &self.level.checkpoint {
+                LevelManager::restore_snapshot(checkpoint).alert_err();
+            }
+        });
+
+        UIManager::keymap().add(self, 'r', move || {
+            // Replayed input must not restart the replay
+            if InputReplay::is_playing() {
+                return;
+            }
+            if let Some(recording) = InputRecorder::stop() {
+                recording.save("replay.json").alert_err();
+            } else {
+                self.level = InputRecorder::start::<TestLevel>();
+            }
+        });
+
+        UIManager::keymap().add(self, 'l', move || {
+            if InputReplay::is_playing() || InputRecorder::is_recording() {
+                return;
+            }
+            if let Some(recording) = Recording::load("replay.json").alert_err() {
+                self.level = InputReplay::start::<TestLevel>(recording);
+            }
+        });
+
         UIManager::keymap().add(self, 'b', || {
             *LevelManager::camera_pos() = Point::default();
             LevelManager::set_level(BenchmarkLevel::default());
@@ -1,64 +1,130 @@
+use std::rc::Rc;
+
 use fake::{Fake, Faker};
 use test_engine::{
     DataManager,
     audio::Sound,
-    generate::noise::{TerrainParams, generate_terrain},
-    gm::{LossyConvert, Shape},
+    generate::{
+        noise::{TerrainParams, generate_terrain},
+        sfx::SfxPreset,
+    },
+    gm::{Direction, LossyConvert, Shape},
     level::{
-        Banner, Body, Level, LevelCreation, LevelManager, LevelSetup, Player, Sprite, SpriteTemplates, Wall,
-        level,
+        AnimationClip, Animator, Banner, Body, CollisionGroups, Control, JointBuilder, Level, LevelCreation,
+        LevelEditor, LevelManager, LevelSetup, LevelSnapshot, Motor, PlayMode, Player, RevoluteJoint, Rewind,
+        SpringJoint, Sprite, SpriteFilter, SpriteSheet, SpriteTemplates, SystemStage, Wall, level,
     },
     refs::Weak,
     ui::{Color, Image, Point, Size},
 };
 
+const PLAYER: u32 = 0b01;
+const BOXES: u32 = 0b10;
+
+/// Collision impulse considered a hard landing.
+const LANDING_IMPULSE: f32 = 50.0;
+
+/// Size of frisk sheet cells in pixels.
+const FRISK_CELL: (u32, u32) = (48, 62);
+
+/// Horizontal speed at which player animation switches to running.
+const RUN_SPEED: f32 = 1.0;
+
+/// Seconds before boxes added by tapping are removed.
+const TAPPED_BOX_LIFETIME: f32 = 30.0;
+
+/// Seconds left before sprite is removed. See [`expire_sprites`].
+#[derive(Clone)]
+pub struct Lifetime(pub f32);
+
 #[level]
 #[derive(Default)]
 pub struct TestLevel {
     pub player:      Weak<Player>,
-    selected_sprite: Option<Weak<dyn Sprite>>,
     collision_sound: Weak<Sound>,
+
+    pub rewind:     Rewind,
+    pub checkpoint: Option<LevelSnapshot>,
 }
 
 impl TestLevel {
-    pub fn add_random_box(&mut self, pos: impl Into<Point>) {
-        let mut bx = self.make_sprite::<Body>(
-            Shape::Rect(Size::<f32>::new((0.2..2.8).fake(), (0.2..2.8).fake())),
-            pos,
-        );
+    pub fn add_random_box(&mut self, pos: impl Into<Point>) -> Weak<Body> {
+        // Level generator keeps boxes the same in replays
+        let rng = self.rng();
+        let size = Size::<f32>::new((0.2..2.8).fake_with_rng(rng), (0.2..2.8).fake_with_rng(rng));
+        let image: bool = Faker.fake_with_rng(rng);
+        let color = Color::ALL[(0..Color::ALL.len()).fake_with_rng::<usize, _>(rng)];
+
+        let mut bx = self.make_sprite::<Body>(Shape::Rect(size), pos);
+
+        // Boxes fall through platforms
+        bx.set_collision_groups(CollisionGroups::new(BOXES, u32::MAX));
 
-        if Faker.fake() {
+        if image {
             bx.set_image("crate_box.png");
         } else {
-            bx.set_color(Color::random());
+            bx.set_color(color);
         }
+
+        bx
+    }
+
+    /// Player can only jump while standing on something.
+    pub fn move_player(&mut self, direction: Direction) {
+        if matches!(direction, Direction::Up) && !self.player_on_ground() {
+            return;
+        }
+        self.player.unit.body.move_by_direction(direction);
+    }
+
+    fn animate_player(&mut self) {
+        let state = if !self.player_on_ground() {
+            "jump"
+        } else if self.player.unit.body.velocity().x.abs() > RUN_SPEED {
+            "run"
+        } else {
+            "idle"
+        };
+
+        let player = self.player;
+        if let Some(animator) = self.components.get_mut::<Animator>(player) {
+            animator.play(state);
+        }
+    }
+
+    fn player_on_ground(&self) -> bool {
+        let position = self.player.position();
+        let feet = Point::new(position.x, position.y - self.player.render_size().height - 0.2);
+        let player: Weak<dyn Sprite> = self.player.unit.weak();
+        self.cast_ray(position, feet, &SpriteFilter::ALL.exclude(player)).is_some()
     }
 
     fn on_touch(&mut self, pos: Point) {
-        if let Some(mut sprite) = self.sprite_at(pos) {
-            sprite.set_selected(true);
-            self.on_sprite_selected.trigger(sprite);
-            if let Some(mut old) = self.selected_sprite {
-                old.set_selected(false);
-            }
-            self.selected_sprite = sprite.into();
+        if let Some(sprite) = self.sprite_at(pos) {
+            LevelEditor::select(sprite);
             return;
         }
 
-        if let Some(mut sprite) = self.selected_sprite {
-            sprite.set_selected(false);
-            self.selected_sprite = None;
-            self.on_sprite_selected.trigger(Weak::default());
+        if LevelEditor::selected().is_ok() {
+            LevelEditor::select(Weak::default());
+            return;
         }
+
+        let bx = self.add_random_box(pos);
+        self.components.insert(bx, Lifetime(TAPPED_BOX_LIFETIME));
     }
 
     fn add_player(&mut self) {
         let mut player: Weak<Player> = self.make_sprite(Shape::Rect((1.2, 2).into()), (-50, 60));
         self.player = player;
         player.set_image("frisk.png").unit.enable_collision_detection();
+        player.unit.set_collision_groups(CollisionGroups::new(PLAYER, u32::MAX));
         player.weapon.set_image("ak.png");
 
-        player.on_collision.sub(move || {
+        player.on_collision.val(move |collision| {
+            if collision.impulse < LANDING_IMPULSE {
+                return;
+            }
             LevelManager::level_weak()
                 .as_any_mut()
                 .downcast_mut::<Self>()
@@ -67,9 +133,60 @@ impl TestLevel {
                 .play();
         });
 
+        let sheet = SpriteSheet::new("frisk_sheet.png");
+        let idle = sheet.cells(FRISK_CELL, [1]);
+        let run = sheet.cells(FRISK_CELL, [0, 2]);
+        let jump = sheet.cells(FRISK_CELL, [4]);
+        let sheet = sheet
+            .add_clip("idle", AnimationClip::uniform(idle, 1.0, PlayMode::Loop))
+            .add_clip("run", AnimationClip::uniform(run, 0.15, PlayMode::Loop))
+            .add_clip("jump", AnimationClip::uniform(jump, 1.0, PlayMode::Once));
+
+        self.components.insert(player, Animator::new(Rc::new(sheet), "idle"));
+
         self.make_sprite::<Wall>(Shape::Rect((10, 1).into()), (-50, 55));
 
-        self.collision_sound = Sound::get("pek.wav");
+        self.collision_sound = Sound::load(&SfxPreset::Hit.wav(3), "sfx-hit");
+    }
+
+    fn add_joints(&mut self) {
+        let mut previous: Weak<dyn Sprite> = self.make_sprite::<Banner>(Shape::rect(0.5, 0.5), (30, 70));
+
+        for i in 0..8 {
+            let link: Weak<dyn Sprite> = self.make_sprite::<Body>(Shape::rect(0.2, 1), (30, 69 - i * 2));
+            let anchor_a = if i == 0 { (0.0, 0.0) } else { (0.0, -1.0) };
+            self.add_joint(previous, link, RevoluteJoint::new().anchors(anchor_a, (0, 1)));
+            previous = link;
+        }
+
+        let pivot = self.make_sprite::<Banner>(Shape::rect(0.5, 0.5), (45, 40));
+        let blade = self.make_sprite::<Body>(Shape::rect(6, 0.3), (45, 40));
+        self.add_joint(
+            pivot,
+            blade,
+            RevoluteJoint::new().motor(Motor::velocity(2.0, 10.0)),
+        );
+
+        let hook = self.make_sprite::<Banner>(Shape::rect(0.5, 0.5), (60, 70));
+        let weight = self.make_sprite::<Body>(Shape::rect(1, 1), (60, 64));
+        let spring = self.add_joint(hook, weight, SpringJoint::new(5.0, 50.0, 1.0).breakable(2000.0));
+
+        spring.on_break.sub(|| {
+            LevelManager::downcast_level::<Self>().collision_sound.play();
+        });
+
+        self.add_ragdoll((75, 60), 4.0);
+    }
+
+    /// Stairs of one way platforms only the player can stand on.
+    fn add_platforms(&mut self) {
+        for i in 0..4 {
+            let x = if i % 2 == 0 { -50 } else { -44 };
+            self.make_sprite::<Wall>(Shape::rect(5, 0.4), (x, 62 + i * 5))
+                .set_collision_groups(CollisionGroups::new(u32::MAX, PLAYER))
+                .set_one_way_platform(true)
+                .set_color(Color::ORANGE);
+        }
     }
 
     fn add_house(&mut self) {
@@ -135,6 +252,8 @@ impl LevelSetup for TestLevel {
 
         self.add_player();
         self.add_house();
+        self.add_platforms();
+        self.add_joints();
 
         self.on_tap.val(move |pos| {
             LevelManager::level_weak()
@@ -144,14 +263,32 @@ impl LevelSetup for TestLevel {
                 .on_touch(pos);
         });
 
+        self.add_system(SystemStage::PostPhysics, expire_sprites);
+
         for island in make_test_terrain() {
             self.make_sprite::<Wall>(Shape::Polyline(island), (0, 20));
         }
     }
 
     fn update(&mut self) {
-        let pos = self.player.position();
-        *LevelManager::camera_pos() = pos;
+        *LevelManager::camera_pos() = self.player.render_position();
+        self.animate_player();
+        self.rewind.capture();
+    }
+}
+
+fn expire_sprites(level: &mut dyn Level, delta: f32) {
+    let expired: Vec<_> = level
+        .components
+        .query_mut::<Lifetime>()
+        .filter_map(|(sprite, lifetime)| {
+            lifetime.0 -= delta;
+            (lifetime.0 <= 0.0).then_some(sprite)
+        })
+        .collect();
+
+    for mut sprite in expired {
+        sprite.remove();
     }
 }
 